@@ -0,0 +1,143 @@
+//! Inserting transactions one at a time against inserting them in bulk
+//!
+//! Run with `cargo bench --bench transactions`. Each iteration starts from an
+//! in-memory SQLite database holding `EXISTING` transactions, then adds
+//! `INSERTED` more either after them (an import of new transactions) or
+//! before them (an older statement imported late), where every existing
+//! balance and sequence number has to change.
+use std::time::{Duration, Instant};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use networth_db::models::{
+    entities::transactions,
+    helpers::transactions::build_transaction,
+    manage::transactions::{create_transaction, create_transactions},
+};
+use sea_orm::{
+    prelude::Decimal, sqlx::types::chrono::NaiveDate, ConnectionTrait, Database,
+    DatabaseConnection, EntityTrait,
+};
+use tokio::runtime::Runtime;
+use uuid::Uuid;
+
+const EXISTING: i64 = 1000;
+const INSERTED: i64 = 100;
+
+fn transaction(account_id: Uuid, sequence_number: i64) -> transactions::ActiveModel {
+    build_transaction(
+        Decimal::new(sequence_number % 7 * 100 - 250, 2),
+        account_id,
+        NaiveDate::from_ymd_opt(2023, 1, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap(),
+        sequence_number,
+        sequence_number.to_string(),
+        "BENCHMARK".to_string(),
+    )
+}
+
+async fn setup(account_id: Uuid) -> DatabaseConnection {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    // The entity's numeric(19, 4) columns cannot be created on SQLite by
+    // sea-query, which only allows up to 16 digits there. Amounts are real,
+    // as the migrations leave them on SQLite
+    db.execute_unprepared(
+        "CREATE TABLE transactions (
+            id uuid_text NOT NULL PRIMARY KEY,
+            account_id uuid_text NOT NULL,
+            amount real NOT NULL,
+            balance real NOT NULL,
+            date datetime_text NOT NULL,
+            description varchar NOT NULL,
+            ref_no varchar NOT NULL,
+            sequence_number bigint NOT NULL,
+            import_id uuid_text,
+            CONSTRAINT uniq_accountid_sequencenumber UNIQUE (account_id, sequence_number)
+        )",
+    )
+    .await
+    .unwrap();
+
+    let existing: Vec<_> = (1..=EXISTING)
+        .map(|sequence_number| transaction(account_id, sequence_number))
+        .collect();
+    for batch in existing.chunks(500) {
+        transactions::Entity::insert_many(batch.to_vec())
+            .exec_without_returning(&db)
+            .await
+            .unwrap();
+    }
+
+    db
+}
+
+/// Sequence numbers of the inserted transactions
+fn inserted(scenario: &str) -> Vec<i64> {
+    match scenario {
+        "append" => (EXISTING + 1..=EXISTING + INSERTED).collect(),
+        _ => (1..=INSERTED).collect(),
+    }
+}
+
+async fn one_at_a_time(scenario: &str) -> Duration {
+    let account_id = Uuid::new_v4();
+    let db = setup(account_id).await;
+
+    let start = Instant::now();
+    for sequence_number in inserted(scenario) {
+        create_transaction(&db, transaction(account_id, sequence_number))
+            .await
+            .unwrap();
+    }
+    start.elapsed()
+}
+
+async fn in_bulk(scenario: &str) -> Duration {
+    let account_id = Uuid::new_v4();
+    let db = setup(account_id).await;
+    let transactions = inserted(scenario)
+        .into_iter()
+        .map(|sequence_number| transaction(account_id, sequence_number))
+        .collect();
+
+    let start = Instant::now();
+    create_transactions(&db, transactions).await.unwrap();
+    start.elapsed()
+}
+
+fn bench_insert(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let mut group = c.benchmark_group("insert_transactions");
+    group.sample_size(10);
+
+    for scenario in ["append", "backdated"] {
+        group.bench_with_input(
+            BenchmarkId::new("one_at_a_time", scenario),
+            scenario,
+            |b, scenario| {
+                b.iter_custom(|iterations| {
+                    (0..iterations)
+                        .map(|_| runtime.block_on(one_at_a_time(scenario)))
+                        .sum()
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("in_bulk", scenario),
+            scenario,
+            |b, scenario| {
+                b.iter_custom(|iterations| {
+                    (0..iterations)
+                        .map(|_| runtime.block_on(in_bulk(scenario)))
+                        .sum()
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_insert);
+criterion_main!(benches);
@@ -0,0 +1,41 @@
+//! Serves the REST API of NetworthDB, see `networth_db::server`
+use std::env;
+
+use networth_db::readers::parsers::ParserRegistry;
+use sea_orm::sqlx::types::chrono::FixedOffset;
+
+#[tokio::main]
+async fn main() {
+    dotenvy::dotenv().ok();
+
+    if let Ok(env_file_path) = env::var("ENV_FILE_PATH") {
+        dotenvy::from_path(&env_file_path).expect("error.server.main.cannot_load_with_path");
+    }
+
+    let database_url = env::var("DATABASE_URL").expect("error.server.main.database_url_not_found");
+    let address = env::var("SERVER_ADDRESS").unwrap_or_else(|_| "127.0.0.1:3000".to_string());
+
+    // Statement dates that carry no timezone are read in `TIMEZONE`
+    let mut parsers = match env::var("TIMEZONE") {
+        Ok(timezone) => ParserRegistry::with_timezone(
+            timezone
+                .parse::<FixedOffset>()
+                .expect("error.server.main.invalid_timezone"),
+        ),
+        Err(_) => ParserRegistry::default(),
+    };
+    if let Ok(templates_dir) = env::var("TEMPLATES_DIR") {
+        parsers
+            .register_templates(&templates_dir)
+            .expect("error.server.main.cannot_load_templates");
+    }
+
+    let db = sea_orm::Database::connect(&database_url)
+        .await
+        .expect("error.server.main.cannot_connect_to_database");
+
+    println!("Listening on http://{}", address);
+    networth_db::server::serve(db, parsers, address.as_str())
+        .await
+        .expect("error.server.main.cannot_serve");
+}
@@ -0,0 +1,218 @@
+use super::{
+    output::{self, Table},
+    parse_day, parse_enum, Context,
+};
+use clap::{Args, Subcommand};
+use networth_db::{
+    error::Error,
+    models::{
+        entities::{
+            accounts,
+            sea_orm_active_enums::{AccountType, InstitutionName},
+        },
+        helpers::{
+            accounts::{AccountFilter, AccountsQueryOptions},
+            transactions::{TransactionFilter, TransactionSort, TransactionsQueryOptions},
+            DateFilterType,
+        },
+        manage::{
+            accounts::{create_account, delete_account, get_accounts, update_account},
+            transactions::get_transaction,
+        },
+    },
+};
+use sea_orm::{prelude::Decimal, sqlx::types::chrono::NaiveDate, ActiveEnum, DatabaseConnection};
+use serde::Serialize;
+use serde_json::json;
+use uuid::Uuid;
+
+#[derive(Debug, Subcommand)]
+pub(crate) enum AccountsCommand {
+    /// List the accounts
+    List {
+        /// Only the accounts of this institution, such as `Hdfc`
+        #[arg(long, value_parser = parse_enum::<InstitutionName>)]
+        institution: Option<InstitutionName>,
+        /// Only the accounts of this type, such as `savings_account`
+        #[arg(long = "type", value_parser = parse_enum::<AccountType>)]
+        account_type: Option<AccountType>,
+    },
+    /// Create an account
+    Create {
+        /// Account number, in full or masked
+        #[arg(long)]
+        number: String,
+        /// Type of the account, such as `savings_account`
+        #[arg(long = "type", value_parser = parse_enum::<AccountType>)]
+        account_type: AccountType,
+        /// Institution holding the account, such as `Hdfc`
+        #[arg(long, value_parser = parse_enum::<InstitutionName>)]
+        institution: InstitutionName,
+    },
+    /// Change the number, type or institution of an account
+    Update {
+        id: Uuid,
+        #[arg(long)]
+        number: Option<String>,
+        #[arg(long = "type", value_parser = parse_enum::<AccountType>)]
+        account_type: Option<AccountType>,
+        #[arg(long, value_parser = parse_enum::<InstitutionName>)]
+        institution: Option<InstitutionName>,
+    },
+    /// Delete an account
+    Delete { id: Uuid },
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct BalancesArgs {
+    /// Only this account
+    #[arg(long)]
+    pub account: Option<Uuid>,
+    /// Balances at the end of this day (`yyyy-mm-dd`) instead of the latest
+    #[arg(long, value_parser = parse_day)]
+    pub date: Option<NaiveDate>,
+}
+
+/// Balance of an account after its last transaction
+#[derive(Serialize)]
+struct Balance {
+    account: accounts::Model,
+    balance: Decimal,
+}
+
+pub(crate) async fn run(
+    db: &DatabaseConnection,
+    context: &Context,
+    command: AccountsCommand,
+) -> Result<(), Error> {
+    match command {
+        AccountsCommand::List {
+            institution,
+            account_type,
+        } => {
+            let accounts = get_accounts(
+                db,
+                AccountsQueryOptions {
+                    filter: Some(AccountFilter {
+                        institution_name: institution,
+                        r#type: account_type,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+            )
+            .await?;
+            output::print(context.output, &accounts, |accounts| {
+                accounts_table(accounts.iter())
+            });
+        }
+        AccountsCommand::Create {
+            number,
+            account_type,
+            institution,
+        } => {
+            let account = create_account(db, &number, &account_type, &institution).await?;
+            output::print(context.output, &account, |account| {
+                accounts_table([account])
+            });
+        }
+        AccountsCommand::Update {
+            id,
+            number,
+            account_type,
+            institution,
+        } => {
+            let account = update_account(db, id, account_type, number, institution).await?;
+            output::print(context.output, &account, |account| {
+                accounts_table([account])
+            });
+        }
+        AccountsCommand::Delete { id } => {
+            delete_account(db, id).await?;
+            output::print(context.output, &json!({ "deleted": id }), |_| {
+                format!("Deleted account {}\n", id)
+            });
+        }
+    }
+    Ok(())
+}
+
+pub(crate) async fn balances(
+    db: &DatabaseConnection,
+    context: &Context,
+    args: BalancesArgs,
+) -> Result<(), Error> {
+    let accounts = get_accounts(
+        db,
+        AccountsQueryOptions {
+            filter: Some(AccountFilter {
+                id: args.account,
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    let before = args
+        .date
+        .and_then(|date| date.succ_opt())
+        .map(|date| context.start_of_day(date));
+    let mut balances = Vec::new();
+    for account in accounts {
+        let last = get_transaction(
+            db,
+            TransactionsQueryOptions {
+                filter: Some(TransactionFilter {
+                    account_id: Some(account.id),
+                    date: before.map(|date| (DateFilterType::LessThan, date)),
+                    ..Default::default()
+                }),
+                // Latest first
+                sort: Some(TransactionSort::default()),
+                ..Default::default()
+            },
+        )
+        .await?;
+        balances.push(Balance {
+            account,
+            balance: last.map_or(Decimal::ZERO, |transaction| transaction.balance),
+        });
+    }
+
+    output::print(context.output, &balances, |balances| {
+        let mut table = Table::new(&["Institution", "Type", "Number", "Balance"]);
+        for Balance { account, balance } in balances {
+            table.row(vec![
+                account.institution_name.to_value(),
+                account.r#type.to_value(),
+                account.account_number.clone(),
+                balance.to_string(),
+            ]);
+        }
+        table.render()
+    });
+    Ok(())
+}
+
+fn accounts_table<'a>(accounts: impl IntoIterator<Item = &'a accounts::Model>) -> String {
+    let mut table = Table::new(&[
+        "Id",
+        "Institution",
+        "Type",
+        "Number",
+        "Transactions",
+        "Updated At",
+    ]);
+    for account in accounts {
+        table.row(vec![
+            account.id.to_string(),
+            account.institution_name.to_value(),
+            account.r#type.to_value(),
+            account.account_number.clone(),
+            account.transaction_count.to_string(),
+            account.updated_at.to_string(),
+        ]);
+    }
+    table.render()
+}
@@ -0,0 +1,267 @@
+use super::{
+    output::{self, details, Table},
+    transactions::transactions_table,
+    Context,
+};
+use clap::Subcommand;
+use networth_db::{
+    error::{Entity, Error},
+    models::{
+        entities::{
+            imports, sea_orm_active_enums::ImportStatus, staged_transactions, transactions,
+        },
+        helpers::{
+            imports::{ImportFilter, ImportsQueryOptions},
+            staged_transactions::{
+                StagedTransactionFilter, StagedTransactionSort, StagedTransactionsQueryOptions,
+            },
+            transactions::{TransactionFilter, TransactionSort, TransactionsQueryOptions},
+        },
+        manage::{
+            imports::{
+                approve_staged_transactions, get_import, get_imports, reject_import,
+                rollback_import, ImportApproval,
+            },
+            staged_transactions::get_staged_transactions,
+            transactions::get_transactions,
+        },
+    },
+};
+use sea_orm::{ActiveEnum, DatabaseConnection, Order};
+use serde::Serialize;
+use serde_json::json;
+use uuid::Uuid;
+
+#[derive(Debug, Subcommand)]
+pub(crate) enum ImportsCommand {
+    /// List the imports
+    List {
+        /// Only the imports into this account
+        #[arg(long)]
+        account: Option<Uuid>,
+        /// Only the imports in this status, such as `pending`
+        #[arg(long, value_parser = super::parse_enum::<ImportStatus>)]
+        status: Option<ImportStatus>,
+    },
+    /// Print an import with its staged and approved transactions
+    Show { id: Uuid },
+    /// Add the staged transactions of an import to its account
+    Approve {
+        id: Uuid,
+        /// Approve only these staged transactions, leaving the rest for later
+        #[arg(long, value_delimiter = ',')]
+        ids: Option<Vec<Uuid>>,
+    },
+    /// Drop the staged transactions of an import
+    Reject {
+        id: Uuid,
+        /// Also delete the transactions already approved from the import
+        #[arg(long)]
+        rollback: bool,
+    },
+}
+
+/// An import with the transactions it holds
+#[derive(Serialize)]
+struct ImportDetails {
+    import: imports::Model,
+    staged_transactions: Vec<staged_transactions::Model>,
+    transactions: Vec<transactions::Model>,
+}
+
+pub(crate) async fn run(
+    db: &DatabaseConnection,
+    context: &Context,
+    command: ImportsCommand,
+) -> Result<(), Error> {
+    match command {
+        ImportsCommand::List { account, status } => {
+            let imports = get_imports(
+                db,
+                ImportsQueryOptions {
+                    filter: Some(ImportFilter {
+                        account_id: account,
+                        status,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+            )
+            .await?;
+            output::print(context.output, &imports, |imports| {
+                let mut table =
+                    Table::new(&["Id", "Account", "Status", "File Date", "Imported At"]);
+                for import in imports {
+                    table.row(vec![
+                        import.id.to_string(),
+                        import.account_id.to_string(),
+                        import.status.to_value(),
+                        context.day(&import.source_file_date).to_string(),
+                        import.import_date.to_string(),
+                    ]);
+                }
+                table.render()
+            });
+        }
+        ImportsCommand::Show { id } => {
+            let import = find_import(db, id).await?;
+            let staged_transactions = get_staged_transactions(
+                db,
+                StagedTransactionsQueryOptions {
+                    filter: Some(StagedTransactionFilter {
+                        import_id: Some(id),
+                        ..Default::default()
+                    }),
+                    sort: Some(StagedTransactionSort {
+                        column: staged_transactions::Column::SequenceNumber,
+                        direction: Order::Asc,
+                    }),
+                    ..Default::default()
+                },
+            )
+            .await?;
+            let transactions = get_transactions(
+                db,
+                TransactionsQueryOptions {
+                    filter: Some(TransactionFilter {
+                        import_id: Some(id),
+                        ..Default::default()
+                    }),
+                    sort: Some(TransactionSort {
+                        column: transactions::Column::SequenceNumber,
+                        direction: Order::Asc,
+                    }),
+                    ..Default::default()
+                },
+            )
+            .await?;
+
+            let details = ImportDetails {
+                import,
+                staged_transactions,
+                transactions,
+            };
+            output::print(context.output, &details, |details| {
+                import_table(context, details)
+            });
+        }
+        ImportsCommand::Approve { id, ids } => {
+            let account_id = find_import(db, id).await?.account_id;
+            let filter = StagedTransactionFilter {
+                ids,
+                ..Default::default()
+            };
+            let approval = approve_staged_transactions(db, id, account_id, filter).await?;
+            output::print(context.output, &approval, |approval| {
+                approval_table(context, approval)
+            });
+        }
+        ImportsCommand::Reject { id, rollback } => {
+            if rollback {
+                let deleted = rollback_import(db, id).await?;
+                output::print(
+                    context.output,
+                    &json!({ "rejected": id, "deleted_transactions": deleted }),
+                    |_| {
+                        format!(
+                            "Rejected import {} and deleted {} transactions\n",
+                            id, deleted
+                        )
+                    },
+                );
+            } else {
+                let import = reject_import(db, id).await?;
+                output::print(context.output, &import, |import| {
+                    format!("Rejected import {}\n", import.id)
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn find_import(db: &DatabaseConnection, id: Uuid) -> Result<imports::Model, Error> {
+    get_import(
+        db,
+        ImportsQueryOptions {
+            filter: Some(ImportFilter {
+                id: Some(id),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+    )
+    .await?
+    .ok_or(Error::NotFound {
+        key: "error.cli.imports.not_found",
+        entity: Entity::Import,
+    })
+}
+
+fn import_table(context: &Context, details: &ImportDetails) -> String {
+    let import = &details.import;
+    let mut text = output::details(&[
+        ("Import", import.id.to_string()),
+        ("Account", import.account_id.to_string()),
+        ("Status", import.status.to_value()),
+        (
+            "File Date",
+            context.day(&import.source_file_date).to_string(),
+        ),
+        ("Imported At", import.import_date.to_string()),
+    ]);
+
+    if !details.staged_transactions.is_empty() {
+        let mut table = Table::new(&[
+            "Id",
+            "Seq",
+            "Date",
+            "Description",
+            "Ref No",
+            "Amount",
+            "Balance",
+            "Match",
+        ]);
+        for transaction in &details.staged_transactions {
+            table.row(vec![
+                transaction.id.to_string(),
+                transaction.sequence_number.to_string(),
+                context.day(&transaction.date).to_string(),
+                transaction.description.clone(),
+                transaction.ref_no.clone(),
+                transaction.amount.to_string(),
+                transaction.balance.to_string(),
+                transaction.match_status.to_value(),
+            ]);
+        }
+        text += &format!("\nStaged:\n{}", table.render());
+    }
+
+    if !details.transactions.is_empty() {
+        text += &format!(
+            "\nApproved:\n{}",
+            transactions_table(context, &details.transactions)
+        );
+    }
+    text
+}
+
+fn approval_table(context: &Context, approval: &ImportApproval) -> String {
+    let summary = details(&[
+        ("Status", approval.status.to_value()),
+        ("Approved", approval.approved.len().to_string()),
+        (
+            "Skipped Duplicates",
+            approval.skipped_duplicates.to_string(),
+        ),
+        ("Remaining", approval.remaining.to_string()),
+    ]);
+    if approval.approved.is_empty() {
+        return summary;
+    }
+    format!(
+        "{}\n{}",
+        summary,
+        transactions_table(context, &approval.approved)
+    )
+}
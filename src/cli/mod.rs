@@ -0,0 +1,168 @@
+//! Command line interface of the `networth_db` binary
+//!
+//! Each command is a thin layer over `networth_db::models::manage`, printing
+//! its result as a table or, with `--output json`, as JSON.
+mod accounts;
+mod imports;
+mod output;
+mod statements;
+mod transactions;
+
+pub(crate) use output::print_error;
+
+use crate::config::{get_database_connection, Config};
+use clap::{Parser, Subcommand};
+use networth_db::{error::Error, models::migrations};
+use networth_db_migrations::{Migrator, MigratorTrait};
+use output::OutputFormat;
+use sea_orm::{
+    prelude::DateTime,
+    sqlx::types::chrono::{FixedOffset, NaiveDate, TimeZone, Utc},
+    ActiveEnum,
+};
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "networth_db",
+    version,
+    about = "Read financial statements and keep their transactions in a database"
+)]
+pub(crate) struct Cli {
+    /// Database to use, instead of `DATABASE_URL`
+    #[arg(long, global = true)]
+    pub database_url: Option<String>,
+    /// How results are printed
+    #[arg(long, short, global = true, value_enum, default_value_t = OutputFormat::Table)]
+    pub output: OutputFormat,
+    /// Directory of statement templates, instead of `TEMPLATES_DIR`
+    #[arg(long, global = true)]
+    pub templates: Option<String>,
+    /// Timezone dates are read and printed in
+    #[arg(long, global = true, default_value = "+05:30")]
+    pub timezone: FixedOffset,
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+pub(crate) enum Command {
+    /// Read a statement and print it, without saving anything
+    Parse(statements::ParseArgs),
+    /// Read a statement and stage its transactions for review
+    Import(statements::ImportArgs),
+    /// Tell why a statement cannot be read, parser by parser and row by row
+    Diagnose(statements::ParseArgs),
+    /// List the parsers statements can be read with
+    Parsers,
+    /// Review imported statements
+    #[command(subcommand)]
+    Imports(imports::ImportsCommand),
+    /// Manage accounts
+    #[command(subcommand)]
+    Accounts(accounts::AccountsCommand),
+    /// Manage the transactions of accounts
+    #[command(subcommand)]
+    Transactions(transactions::TransactionsCommand),
+    /// Print the balance of each account
+    Balances(accounts::BalancesArgs),
+    /// Apply the database migrations that have not run yet
+    Migrate,
+}
+
+/// Settings every command prints with
+pub(crate) struct Context {
+    pub output: OutputFormat,
+    pub timezone: FixedOffset,
+}
+
+impl Context {
+    /// Day of a stored date, in the chosen timezone
+    pub(crate) fn day(&self, date: &DateTime) -> NaiveDate {
+        Utc.from_utc_datetime(date)
+            .with_timezone(&self.timezone)
+            .date_naive()
+    }
+
+    /// Start of a day in the chosen timezone, as dates are stored
+    pub(crate) fn start_of_day(&self, day: NaiveDate) -> DateTime {
+        self.timezone
+            .from_local_datetime(&day.and_time(Default::default()))
+            .unwrap()
+            .naive_utc()
+    }
+}
+
+/// Runs a command, connecting to the database unless it works without one
+pub(crate) async fn run(cli: Cli, config: Config) -> Result<(), Error> {
+    let context = Context {
+        output: cli.output,
+        timezone: cli.timezone,
+    };
+    let database_url = cli.database_url.or(config.database_url);
+    let connect = || get_database_connection(database_url.as_deref());
+    let templates_dir = cli.templates.or(config.templates_dir);
+    let parsers = || statements::parser_registry(templates_dir.as_deref(), cli.timezone);
+
+    match cli.command {
+        Command::Parse(args) => statements::parse(&context, &parsers()?, args, &config.file_secret),
+        Command::Diagnose(args) => {
+            statements::diagnose(&context, &parsers()?, args, &config.file_secret)
+        }
+        Command::Parsers => statements::parsers(&context, &parsers()?),
+        Command::Import(args) => {
+            let parsers = parsers()?;
+            statements::import(
+                &connect().await,
+                &context,
+                &parsers,
+                args,
+                &config.file_secret,
+            )
+            .await
+        }
+        Command::Imports(command) => imports::run(&connect().await, &context, command).await,
+        Command::Accounts(command) => accounts::run(&connect().await, &context, command).await,
+        Command::Transactions(command) => {
+            transactions::run(&connect().await, &context, command).await
+        }
+        Command::Balances(args) => accounts::balances(&connect().await, &context, args).await,
+        Command::Migrate => migrate(&connect().await, &context).await,
+    }
+}
+
+async fn migrate(db: &sea_orm::DatabaseConnection, context: &Context) -> Result<(), Error> {
+    let pending: Vec<String> = Migrator::get_pending_migrations(db)
+        .await?
+        .iter()
+        .map(|migration| migration.name().to_string())
+        .collect();
+    Migrator::up(db, None).await?;
+
+    output::print(context.output, &pending, |applied| {
+        let mut table = output::Table::new(&["Applied Migration"]);
+        for name in applied {
+            table.row(vec![name.clone()]);
+        }
+        format!(
+            "{}{} of {} migrations applied\n",
+            table.render(),
+            applied.len(),
+            migrations().len()
+        )
+    });
+    Ok(())
+}
+
+/// Reads an enum from the value it is stored as, such as `savings_account`
+pub(crate) fn parse_enum<T: ActiveEnum<Value = String>>(value: &str) -> Result<T, String> {
+    T::try_from_value(&value.to_string())
+        .map_err(|_| format!("expected one of: {}", T::values().join(", ")))
+}
+
+/// Reads a day written as `yyyy-mm-dd`
+pub(crate) fn parse_day(value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|error| error.to_string())
+}
+
+#[cfg(test)]
+mod tests;
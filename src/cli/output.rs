@@ -0,0 +1,113 @@
+use clap::ValueEnum;
+use networth_db::error::Error;
+use serde::Serialize;
+
+/// How a command prints its result
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum OutputFormat {
+    /// Aligned columns, for reading
+    Table,
+    /// Pretty printed JSON, for scripts
+    Json,
+}
+
+/// Prints a command's result, rendering it with `table` unless JSON is asked for
+pub(crate) fn print<T: Serialize>(
+    format: OutputFormat,
+    value: &T,
+    table: impl FnOnce(&T) -> String,
+) {
+    match format {
+        OutputFormat::Table => print!("{}", table(value)),
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(value).expect("error.cli.output.cannot_serialize")
+        ),
+    }
+}
+
+/// Prints why a command failed, with what to do about it when the error tells
+pub(crate) fn print_error(error: &Error) {
+    eprintln!("{}", error);
+    match error {
+        Error::AmbiguousAccount { candidates, .. } => {
+            if candidates.is_empty() {
+                eprintln!("Create the account and choose it with --account");
+            } else {
+                eprintln!("Choose one of these accounts with --account:");
+                for candidate in candidates {
+                    eprintln!("  {}", candidate);
+                }
+            }
+        }
+        Error::AlreadyImported { import_id, .. } => {
+            eprintln!("The file was imported as {}", import_id);
+        }
+        _ => {}
+    }
+}
+
+/// Rows of cells printed in columns as wide as their widest cell
+pub(crate) struct Table {
+    headers: Vec<&'static str>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub(crate) fn new(headers: &[&'static str]) -> Self {
+        Table {
+            headers: headers.to_vec(),
+            rows: Vec::new(),
+        }
+    }
+
+    pub(crate) fn row(&mut self, cells: Vec<String>) {
+        self.rows.push(cells);
+    }
+
+    pub(crate) fn render(&self) -> String {
+        let mut widths: Vec<usize> = self.headers.iter().map(|header| header.len()).collect();
+        for row in &self.rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let rule: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+        let mut lines = vec![
+            line(
+                self.headers.iter().map(|header| header.to_string()),
+                &widths,
+            ),
+            line(rule, &widths),
+        ];
+        for row in &self.rows {
+            lines.push(line(row.iter().cloned(), &widths));
+        }
+
+        lines.join("\n") + "\n"
+    }
+}
+
+fn line(cells: impl IntoIterator<Item = String>, widths: &[usize]) -> String {
+    let padded: Vec<String> = cells
+        .into_iter()
+        .zip(widths)
+        .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+        .collect();
+    padded.join("  ").trim_end().to_string()
+}
+
+/// Labelled values, one per line, with the values lined up
+pub(crate) fn details(fields: &[(&str, String)]) -> String {
+    let width = fields
+        .iter()
+        .map(|(label, _)| label.len() + 1)
+        .max()
+        .unwrap_or(0);
+
+    fields
+        .iter()
+        .map(|(label, value)| format!("{:<width$}  {}\n", format!("{}:", label), value))
+        .collect()
+}
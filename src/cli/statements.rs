@@ -0,0 +1,279 @@
+use super::{
+    output::{self, details, Table},
+    Context,
+};
+use clap::Args;
+use networth_db::{
+    error::{Error, Location},
+    models::manage::imports::{import_statement, AccountResolution, StatementImport},
+    readers::{
+        diagnose_file, get_statement_from_file_with_parsers,
+        parsers::{types::Statement, ParserRegistry},
+    },
+};
+use sea_orm::{sqlx::types::chrono::FixedOffset, ActiveEnum, DatabaseConnection};
+use serde::Serialize;
+use uuid::Uuid;
+
+#[derive(Debug, Args)]
+pub(crate) struct ParseArgs {
+    /// Statement file to read
+    pub file: String,
+    /// Password of the file, instead of `FILE_SECRET`
+    #[arg(long)]
+    pub secret: Option<String>,
+    /// Parser to read the file with, instead of the one identifying it
+    #[arg(long)]
+    pub parser: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct ImportArgs {
+    #[command(flatten)]
+    pub statement: ParseArgs,
+    /// Account to import into, when the statement's account is ambiguous
+    #[arg(long)]
+    pub account: Option<Uuid>,
+}
+
+/// A parser as `parsers` lists it
+#[derive(Serialize)]
+struct ParserDetails {
+    name: String,
+    institution: Option<String>,
+    file_types: Vec<String>,
+    version: u32,
+}
+
+/// The built-in parsers, and those of the templates of a directory
+pub(crate) fn parser_registry(
+    templates_dir: Option<&str>,
+    timezone: FixedOffset,
+) -> Result<ParserRegistry, Error> {
+    let mut parsers = ParserRegistry::with_timezone(timezone);
+    if let Some(templates_dir) = templates_dir {
+        parsers.register_templates(templates_dir)?;
+    }
+    Ok(parsers)
+}
+
+fn read_statement(
+    parsers: &ParserRegistry,
+    args: &ParseArgs,
+    file_secret: &str,
+) -> Result<Statement, Error> {
+    let secret = args.secret.as_deref().unwrap_or(file_secret);
+    get_statement_from_file_with_parsers(&args.file, secret, parsers, args.parser.as_deref())
+        .map(|(statement, _)| statement)
+}
+
+pub(crate) fn parsers(context: &Context, parsers: &ParserRegistry) -> Result<(), Error> {
+    let parsers: Vec<ParserDetails> = parsers
+        .parsers()
+        .map(|parser| ParserDetails {
+            name: parser.name().to_string(),
+            institution: parser.institution().map(|name| name.to_value()),
+            file_types: parser
+                .file_types()
+                .iter()
+                .map(|file_type| format!("{:?}", file_type))
+                .collect(),
+            version: parser.version(),
+        })
+        .collect();
+
+    output::print(context.output, &parsers, |parsers| {
+        let mut table = Table::new(&["Name", "Institution", "File Types", "Version"]);
+        for parser in parsers {
+            table.row(vec![
+                parser.name.clone(),
+                parser.institution.clone().unwrap_or_default(),
+                parser.file_types.join(", "),
+                parser.version.to_string(),
+            ]);
+        }
+        table.render()
+    });
+    Ok(())
+}
+
+pub(crate) fn parse(
+    context: &Context,
+    parsers: &ParserRegistry,
+    args: ParseArgs,
+    file_secret: &str,
+) -> Result<(), Error> {
+    let statement = read_statement(parsers, &args, file_secret)?;
+
+    output::print(context.output, &statement, |statement| {
+        let day = |date: &sea_orm::prelude::DateTimeUtc| {
+            date.with_timezone(&statement.timezone)
+                .date_naive()
+                .to_string()
+        };
+        let optional = |value: Option<String>| value.unwrap_or_default();
+
+        let mut table = Table::new(&[
+            "Date",
+            "Description",
+            "Ref No",
+            "Withdrawal",
+            "Deposit",
+            "Balance",
+        ]);
+        for transaction in &statement.transactions {
+            table.row(vec![
+                day(&transaction.date),
+                transaction.description.clone(),
+                transaction.ref_no.clone(),
+                transaction.withdrawal.to_string(),
+                transaction.deposit.to_string(),
+                transaction.balance.to_string(),
+            ]);
+        }
+
+        let summary = details(&[
+            (
+                "Institution",
+                optional(statement.institution.as_ref().map(|name| name.to_value())),
+            ),
+            ("Account Type", statement.account_type.to_value()),
+            (
+                "Account Number",
+                optional(
+                    statement
+                        .account_number
+                        .clone()
+                        .or(statement.masked_account_number.clone()),
+                ),
+            ),
+            ("Holder", optional(statement.holder_name.clone())),
+            ("Currency", optional(statement.currency.clone())),
+            ("Statement Date", day(&statement.date)),
+            (
+                "Period Start",
+                optional(statement.period_start.as_ref().map(day)),
+            ),
+            (
+                "Period End",
+                optional(statement.period_end.as_ref().map(day)),
+            ),
+            (
+                "Opening Balance",
+                optional(statement.opening_balance.map(|balance| balance.to_string())),
+            ),
+            (
+                "Closing Balance",
+                optional(statement.closing_balance.map(|balance| balance.to_string())),
+            ),
+            ("Skipped Rows", statement.skipped_rows.len().to_string()),
+        ]);
+
+        format!("{}\n{}", summary, table.render())
+    });
+    Ok(())
+}
+
+pub(crate) fn diagnose(
+    context: &Context,
+    parsers: &ParserRegistry,
+    args: ParseArgs,
+    file_secret: &str,
+) -> Result<(), Error> {
+    let secret = args.secret.as_deref().unwrap_or(file_secret);
+    let diagnosis = diagnose_file(&args.file, secret, parsers, args.parser.as_deref())?;
+
+    output::print(context.output, &diagnosis, |diagnosis| {
+        let optional = |value: Option<String>| value.unwrap_or_default();
+
+        let mut parsers = Table::new(&["Parser", "Confidence", "Mismatch"]);
+        for parser in &diagnosis.parsers {
+            parsers.row(vec![
+                parser.parser.clone(),
+                format!("{:.2}", parser.confidence.score()),
+                optional(parser.mismatch.clone()),
+            ]);
+        }
+
+        let summary = details(&[
+            ("File Type", format!("{:?}", diagnosis.file_type)),
+            ("Parser", optional(diagnosis.parser.clone())),
+            (
+                "Transactions",
+                optional(
+                    diagnosis
+                        .statement
+                        .as_ref()
+                        .map(|statement| statement.transactions.len().to_string()),
+                ),
+            ),
+            (
+                "Error",
+                optional(diagnosis.error.as_ref().map(|error| error.to_string())),
+            ),
+        ]);
+
+        let mut rows = Table::new(&["Location", "Issue", "Reason", "Content"]);
+        for row in &diagnosis.skipped_rows {
+            rows.row(vec![
+                optional(row.location.map(|location| match location {
+                    Location::Row(row) => format!("row {}", row),
+                    Location::Line(line) => format!("line {}", line),
+                })),
+                row.key.to_string(),
+                optional(row.reason.clone()),
+                row.content.clone(),
+            ]);
+        }
+
+        format!("{}\n{}\n{}", parsers.render(), summary, rows.render())
+    });
+    Ok(())
+}
+
+pub(crate) async fn import(
+    db: &DatabaseConnection,
+    context: &Context,
+    parsers: &ParserRegistry,
+    args: ImportArgs,
+    file_secret: &str,
+) -> Result<(), Error> {
+    let statement = read_statement(parsers, &args.statement, file_secret)?;
+    let import = import_statement(db, &statement, args.account).await?;
+
+    output::print(context.output, &import, import_table);
+    Ok(())
+}
+
+fn import_table(import: &StatementImport) -> String {
+    let account = match &import.account {
+        AccountResolution::Matched(account) => account.account_number.clone(),
+        AccountResolution::Created(account) => format!("{} (created)", account.account_number),
+    };
+    let summary = details(&[
+        ("Import", import.import_id.to_string()),
+        ("Account", account),
+        ("Duplicates", import.duplicates.to_string()),
+        ("Conflicts", import.conflicts.to_string()),
+        (
+            "Skipped Rows",
+            import.validation.skipped_rows.len().to_string(),
+        ),
+    ]);
+    if import.validation.issues.is_empty() {
+        return summary;
+    }
+
+    let optional = |value: Option<String>| value.unwrap_or_default();
+    let mut table = Table::new(&["Severity", "Issue", "Row", "Expected", "Found"]);
+    for issue in &import.validation.issues {
+        table.row(vec![
+            format!("{:?}", issue.severity),
+            issue.key.to_string(),
+            optional(issue.row.map(|row| row.to_string())),
+            optional(issue.expected.map(|expected| expected.to_string())),
+            optional(issue.found.map(|found| found.to_string())),
+        ]);
+    }
+    format!("{}\n{}", summary, table.render())
+}
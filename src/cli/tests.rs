@@ -0,0 +1,116 @@
+#[cfg(test)]
+use crate::cli::{
+    accounts::AccountsCommand,
+    imports::ImportsCommand,
+    output::{details, OutputFormat, Table},
+    parse_enum, Cli, Command, Context,
+};
+use clap::Parser;
+use networth_db::models::entities::sea_orm_active_enums::AccountType;
+use sea_orm::sqlx::types::chrono::{FixedOffset, NaiveDate};
+use uuid::Uuid;
+
+fn _context() -> Context {
+    Context {
+        output: OutputFormat::Table,
+        timezone: FixedOffset::east_opt(5 * 3600 + 30 * 60).unwrap(),
+    }
+}
+
+#[test]
+fn test_global_options_after_subcommand() {
+    let cli = Cli::try_parse_from(["networth_db", "accounts", "list", "-o", "json"]).unwrap();
+    assert_eq!(cli.output, OutputFormat::Json);
+    assert_eq!(cli.timezone, _context().timezone);
+    assert!(matches!(
+        cli.command,
+        Command::Accounts(AccountsCommand::List {
+            institution: None,
+            account_type: None
+        })
+    ));
+}
+
+#[test]
+fn test_approve_selected_staged_transactions() {
+    let import = Uuid::new_v4();
+    let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+    let cli = Cli::try_parse_from([
+        "networth_db".to_string(),
+        "imports".to_string(),
+        "approve".to_string(),
+        import.to_string(),
+        "--ids".to_string(),
+        format!("{},{}", first, second),
+    ])
+    .unwrap();
+
+    match cli.command {
+        Command::Imports(ImportsCommand::Approve { id, ids }) => {
+            assert_eq!(id, import);
+            assert_eq!(ids, Some(vec![first, second]));
+        }
+        command => panic!("unexpected command {:?}", command),
+    }
+}
+
+#[test]
+fn test_parse_enum_from_stored_value() {
+    assert_eq!(
+        parse_enum::<AccountType>("savings_account"),
+        Ok(AccountType::SavingsAccount)
+    );
+    let error = parse_enum::<AccountType>("Savings").unwrap_err();
+    assert!(error.contains("checking_account"));
+    assert!(Cli::try_parse_from([
+        "networth_db",
+        "accounts",
+        "create",
+        "--number",
+        "1234",
+        "--type",
+        "Savings",
+        "--institution",
+        "Hdfc"
+    ])
+    .is_err());
+}
+
+#[test]
+fn test_days_in_timezone() {
+    let context = _context();
+    let day = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+    let stored = context.start_of_day(day);
+
+    assert_eq!(
+        stored,
+        NaiveDate::from_ymd_opt(2024, 1, 1)
+            .unwrap()
+            .and_hms_opt(18, 30, 0)
+            .unwrap()
+    );
+    assert_eq!(context.day(&stored), day);
+}
+
+#[test]
+fn test_table_columns_fit_widest_cell() {
+    let mut table = Table::new(&["Date", "Amount"]);
+    table.row(vec!["2024-01-02".to_string(), "-5".to_string()]);
+    table.row(vec!["2024-01-03".to_string(), "1500.25".to_string()]);
+
+    assert_eq!(
+        table.render(),
+        "Date        Amount\n\
+         ----------  -------\n\
+         2024-01-02  -5\n\
+         2024-01-03  1500.25\n"
+    );
+}
+
+#[test]
+fn test_details_line_up_values() {
+    assert_eq!(
+        details(&[("Status", "pending".to_string()), ("Id", "1".to_string())]),
+        "Status:  pending\nId:      1\n"
+    );
+}
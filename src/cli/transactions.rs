@@ -0,0 +1,144 @@
+use super::{
+    output::{self, Table},
+    parse_day, Context,
+};
+use clap::Subcommand;
+use networth_db::{
+    error::Error,
+    models::{
+        entities::transactions,
+        helpers::{
+            transactions::{TransactionFilter, TransactionSort, TransactionsQueryOptions},
+            StringFilterType,
+        },
+        manage::transactions::{delete_transaction, get_transactions, update_transaction},
+    },
+};
+use sea_orm::{prelude::Decimal, sqlx::types::chrono::NaiveDate, DatabaseConnection};
+use serde_json::json;
+use uuid::Uuid;
+
+#[derive(Debug, Subcommand)]
+pub(crate) enum TransactionsCommand {
+    /// List transactions, latest first
+    List {
+        /// Only the transactions of this account
+        #[arg(long)]
+        account: Option<Uuid>,
+        /// Only the transactions approved from this import
+        #[arg(long)]
+        import: Option<Uuid>,
+        /// Only the transactions whose description contains this text
+        #[arg(long)]
+        description: Option<String>,
+        #[arg(long)]
+        limit: Option<u64>,
+        #[arg(long)]
+        offset: Option<u64>,
+    },
+    /// Change a transaction, recalculating the balances after it
+    Edit {
+        id: Uuid,
+        /// Move the transaction to another account
+        #[arg(long)]
+        account: Option<Uuid>,
+        /// Deposits are positive, withdrawals negative
+        #[arg(long, allow_negative_numbers = true)]
+        amount: Option<Decimal>,
+        /// Day of the transaction, as `yyyy-mm-dd`
+        #[arg(long, value_parser = parse_day)]
+        date: Option<NaiveDate>,
+        #[arg(long)]
+        ref_no: Option<String>,
+        #[arg(long)]
+        description: Option<String>,
+    },
+    /// Delete a transaction, recalculating the balances after it
+    Delete { id: Uuid },
+}
+
+pub(crate) async fn run(
+    db: &DatabaseConnection,
+    context: &Context,
+    command: TransactionsCommand,
+) -> Result<(), Error> {
+    match command {
+        TransactionsCommand::List {
+            account,
+            import,
+            description,
+            limit,
+            offset,
+        } => {
+            let transactions = get_transactions(
+                db,
+                TransactionsQueryOptions {
+                    filter: Some(TransactionFilter {
+                        account_id: account,
+                        import_id: import,
+                        description: description
+                            .map(|description| (StringFilterType::Contains, description)),
+                        ..Default::default()
+                    }),
+                    sort: Some(TransactionSort::default()),
+                    limit,
+                    offset,
+                },
+            )
+            .await?;
+            output::print(context.output, &transactions, |transactions| {
+                transactions_table(context, transactions)
+            });
+        }
+        TransactionsCommand::Edit {
+            id,
+            account,
+            amount,
+            date,
+            ref_no,
+            description,
+        } => {
+            let date = date.map(|date| context.start_of_day(date));
+            let transaction =
+                update_transaction(db, id, account, amount, date, ref_no, description, None)
+                    .await?;
+            output::print(context.output, &transaction, |transaction| {
+                transactions_table(context, std::slice::from_ref(transaction))
+            });
+        }
+        TransactionsCommand::Delete { id } => {
+            delete_transaction(db, id).await?;
+            output::print(context.output, &json!({ "deleted": id }), |_| {
+                format!("Deleted transaction {}\n", id)
+            });
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn transactions_table(
+    context: &Context,
+    transactions: &[transactions::Model],
+) -> String {
+    let mut table = Table::new(&[
+        "Id",
+        "Seq",
+        "Date",
+        "Description",
+        "Ref No",
+        "Amount",
+        "Balance",
+    ]);
+    for transaction in transactions {
+        table.row(vec![
+            transaction.id.to_string(),
+            transaction.sequence_number.to_string(),
+            context.day(&transaction.date).to_string(),
+            transaction.description.clone(),
+            transaction.ref_no.clone(),
+            transaction.amount.to_string(),
+            transaction.balance.to_string(),
+        ]);
+    }
+    table.render()
+}
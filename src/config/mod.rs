@@ -1,32 +1,34 @@
 mod types;
 
+pub(crate) use types::Config;
+
 use std::env;
 
-pub(crate) async fn get_config() -> types::Config {
+pub(crate) fn get_config() -> Config {
     dotenvy::dotenv().ok();
 
     if let Ok(env_file_path) = env::var("ENV_FILE_PATH") {
         let reason = "Failed to load environment variables from specified path";
-        dotenvy::from_path(&env_file_path).expect(&format!(
-            "error.config.get_config.cannot_load_with_path: {}",
-            reason
-        ));
+        dotenvy::from_path(&env_file_path).unwrap_or_else(|_| {
+            panic!("error.config.get_config.cannot_load_with_path: {}", reason)
+        });
     }
 
-    let file_path = env::var("FILE_PATH").expect("error.config.get_config.file_path_not_found");
     let file_secret = env::var("FILE_SECRET").unwrap_or_default();
+    let database_url = env::var("DATABASE_URL").ok();
+    let templates_dir = env::var("TEMPLATES_DIR").ok();
 
-    let database_url =
-        env::var("DATABASE_URL").expect("error.config.get_config.database_url_not_found");
-
-    return types::Config {
-        db: get_database_connection(&database_url).await,
-        file_path,
+    Config {
+        database_url,
         file_secret,
-    };
+        templates_dir,
+    }
 }
 
-async fn get_database_connection(database_url: &str) -> sea_orm::DatabaseConnection {
+pub(crate) async fn get_database_connection(
+    database_url: Option<&str>,
+) -> sea_orm::DatabaseConnection {
+    let database_url = database_url.expect("error.config.get_config.database_url_not_found");
     sea_orm::Database::connect(database_url)
         .await
         .expect("error.config.get_config.cannot_connect_to_database")
@@ -1,5 +1,9 @@
 pub(crate) struct Config {
-    pub db: sea_orm::DatabaseConnection,
-    pub file_path: String,
+    /// Database to connect to, unless the command line names one
+    pub database_url: Option<String>,
+    /// Password of encrypted statements, unless the command line gives one
     pub file_secret: String,
+    /// Directory of statement templates to read statements with, besides the
+    /// built-in parsers
+    pub templates_dir: Option<String>,
 }
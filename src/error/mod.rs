@@ -0,0 +1,223 @@
+//! Errors returned by the readers and the models
+//!
+//! Every error carries a stable key such as
+//! `error.parser.hdfcind.start_of_data_not_found`, meant to be looked up in
+//! translations, while the variants let callers match on the kind of failure.
+use std::fmt;
+
+use sea_orm::DbErr;
+use serde::{ser::SerializeStruct, Serialize};
+use uuid::Uuid;
+
+use crate::models::entities::sea_orm_active_enums::ImportStatus;
+
+#[derive(Debug)]
+pub enum Error {
+    /// The file could not be loaded
+    Io { key: &'static str, reason: String },
+    /// The file is not in any of the formats that can be read
+    UnsupportedFormat {
+        key: &'static str,
+        reason: Option<String>,
+    },
+    /// The file is encrypted and none of the secrets decrypted it
+    Decryption {
+        key: &'static str,
+        reason: Option<String>,
+    },
+    /// No parser recognised the statement, or a parser was handed data it
+    /// does not read
+    ParserMismatch { key: &'static str },
+    /// The file, or the statement in it, could not be read
+    Parse {
+        key: &'static str,
+        location: Option<Location>,
+        reason: Option<String>,
+    },
+    /// A database operation failed
+    Database(DbErr),
+    /// A record the operation works on does not exist
+    NotFound { key: &'static str, entity: Entity },
+    /// The account a statement belongs to could not be told apart from the
+    /// `candidates`, and has to be chosen by the caller
+    AmbiguousAccount {
+        key: &'static str,
+        candidates: Vec<Uuid>,
+    },
+    /// The same file was already imported as `import_id`
+    AlreadyImported { key: &'static str, import_id: Uuid },
+    /// The import is not in a status the operation can start from
+    InvalidImportStatus {
+        key: &'static str,
+        status: ImportStatus,
+    },
+}
+
+/// Where in the file a parse failure happened, counted from 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Location {
+    /// A row of a table, or a record of a statement
+    Row(usize),
+    /// A line of a text file
+    Line(usize),
+}
+
+/// The kind of record that could not be found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Entity {
+    Account,
+    Import,
+    Transaction,
+    StagedTransaction,
+}
+
+impl Error {
+    pub(crate) fn unsupported_format(key: &'static str) -> Self {
+        Error::UnsupportedFormat { key, reason: None }
+    }
+
+    pub(crate) fn decryption(key: &'static str) -> Self {
+        Error::Decryption { key, reason: None }
+    }
+
+    pub(crate) fn parser_mismatch(key: &'static str) -> Self {
+        Error::ParserMismatch { key }
+    }
+
+    pub(crate) fn parse(key: &'static str) -> Self {
+        Error::Parse {
+            key,
+            location: None,
+            reason: None,
+        }
+    }
+
+    pub(crate) fn not_found(key: &'static str, entity: Entity) -> Self {
+        Error::NotFound { key, entity }
+    }
+
+    pub(crate) fn ambiguous_account(key: &'static str, candidates: Vec<Uuid>) -> Self {
+        Error::AmbiguousAccount { key, candidates }
+    }
+
+    pub(crate) fn already_imported(key: &'static str, import_id: Uuid) -> Self {
+        Error::AlreadyImported { key, import_id }
+    }
+
+    pub(crate) fn invalid_import_status(key: &'static str, status: ImportStatus) -> Self {
+        Error::InvalidImportStatus { key, status }
+    }
+
+    /// Adds the underlying cause, for the variants that can hold one
+    pub(crate) fn with_reason(mut self, cause: impl fmt::Display) -> Self {
+        match &mut self {
+            Error::Io { reason, .. } => *reason = cause.to_string(),
+            Error::UnsupportedFormat { reason, .. }
+            | Error::Decryption { reason, .. }
+            | Error::Parse { reason, .. } => *reason = Some(cause.to_string()),
+            Error::ParserMismatch { .. }
+            | Error::Database(_)
+            | Error::NotFound { .. }
+            | Error::AmbiguousAccount { .. }
+            | Error::AlreadyImported { .. }
+            | Error::InvalidImportStatus { .. } => {}
+        }
+        self
+    }
+
+    /// Adds where the failure happened, for parse failures
+    pub(crate) fn at(mut self, at: Location) -> Self {
+        if let Error::Parse { location, .. } = &mut self {
+            *location = Some(at);
+        }
+        self
+    }
+
+    /// The stable key of the error, for translations
+    pub fn key(&self) -> &'static str {
+        match self {
+            Error::Io { key, .. }
+            | Error::UnsupportedFormat { key, .. }
+            | Error::Decryption { key, .. }
+            | Error::ParserMismatch { key }
+            | Error::Parse { key, .. }
+            | Error::NotFound { key, .. }
+            | Error::AmbiguousAccount { key, .. }
+            | Error::AlreadyImported { key, .. }
+            | Error::InvalidImportStatus { key, .. } => key,
+            Error::Database(_) => "error.database",
+        }
+    }
+
+    /// The underlying cause of the error, if known
+    pub fn reason(&self) -> Option<String> {
+        match self {
+            Error::Io { reason, .. } => Some(reason.clone()),
+            Error::UnsupportedFormat { reason, .. }
+            | Error::Decryption { reason, .. }
+            | Error::Parse { reason, .. } => reason.clone(),
+            Error::Database(error) => Some(error.to_string()),
+            Error::ParserMismatch { .. }
+            | Error::NotFound { .. }
+            | Error::AmbiguousAccount { .. }
+            | Error::AlreadyImported { .. }
+            | Error::InvalidImportStatus { .. } => None,
+        }
+    }
+
+    /// Where in the file the error happened, for parse failures
+    pub fn location(&self) -> Option<Location> {
+        match self {
+            Error::Parse { location, .. } => *location,
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.key())?;
+        match self.location() {
+            Some(Location::Row(row)) => write!(f, " (row {})", row)?,
+            Some(Location::Line(line)) => write!(f, " (line {})", line)?,
+            None => {}
+        }
+        if let Some(reason) = self.reason() {
+            write!(f, ": {}", reason)?;
+        }
+        Ok(())
+    }
+}
+
+/// Written as its key, location and reason
+impl Serialize for Error {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut error = serializer.serialize_struct("Error", 3)?;
+        error.serialize_field("key", self.key())?;
+        error.serialize_field("location", &self.location())?;
+        error.serialize_field("reason", &self.reason())?;
+        error.end()
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Database(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<DbErr> for Error {
+    fn from(error: DbErr) -> Self {
+        Error::Database(error)
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,46 @@
+#[cfg(test)]
+use crate::error::{Entity, Error, Location};
+use sea_orm::DbErr;
+
+#[test]
+fn test_display_keeps_key_first() {
+    let error = Error::parse("error.parser.ofx.invalid_amount")
+        .at(Location::Row(3))
+        .with_reason("1,0.0");
+
+    assert_eq!(error.key(), "error.parser.ofx.invalid_amount");
+    assert_eq!(error.location(), Some(Location::Row(3)));
+    assert_eq!(
+        error.to_string(),
+        "error.parser.ofx.invalid_amount (row 3): 1,0.0"
+    );
+}
+
+#[test]
+fn test_reason_and_location_only_where_supported() {
+    let error = Error::not_found("error.imports.delete_import.not_found", Entity::Import)
+        .at(Location::Line(1))
+        .with_reason("ignored");
+
+    assert!(matches!(
+        error,
+        Error::NotFound {
+            entity: Entity::Import,
+            ..
+        }
+    ));
+    assert_eq!(error.location(), None);
+    assert_eq!(error.to_string(), "error.imports.delete_import.not_found");
+}
+
+#[test]
+fn test_database_error() {
+    let error: Error = DbErr::Custom("connection lost".to_string()).into();
+
+    assert_eq!(error.key(), "error.database");
+    assert!(std::error::Error::source(&error).is_some());
+    assert_eq!(
+        error.to_string(),
+        "error.database: Custom Error: connection lost"
+    );
+}
@@ -1,3 +1,6 @@
+pub mod error;
 pub mod models;
 pub mod readers;
+#[cfg(feature = "server")]
+pub mod server;
 mod utils;
@@ -1,5 +1,6 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.1
 
+use super::sea_orm_active_enums::ImportStatus;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -11,6 +12,10 @@ pub struct Model {
     pub account_id: Uuid,
     pub import_date: DateTime,
     pub source_file_date: DateTime,
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    pub validation_report: Option<Json>,
+    pub source_file_hash: Option<String>,
+    pub status: ImportStatus,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -25,6 +30,8 @@ pub enum Relation {
     Accounts,
     #[sea_orm(has_many = "super::staged_transactions::Entity")]
     StagedTransactions,
+    #[sea_orm(has_many = "super::transactions::Entity")]
+    Transactions,
 }
 
 impl Related<super::accounts::Entity> for Entity {
@@ -39,4 +46,10 @@ impl Related<super::staged_transactions::Entity> for Entity {
     }
 }
 
+impl Related<super::transactions::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Transactions.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -18,6 +18,18 @@ pub enum AccountType {
     Unknown,
 }
 #[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "import_status")]
+pub enum ImportStatus {
+    #[sea_orm(string_value = "approved")]
+    Approved,
+    #[sea_orm(string_value = "partially_approved")]
+    PartiallyApproved,
+    #[sea_orm(string_value = "pending")]
+    Pending,
+    #[sea_orm(string_value = "rejected")]
+    Rejected,
+}
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "institution_name")]
 pub enum InstitutionName {
     #[sea_orm(string_value = "Axis")]
@@ -47,3 +59,13 @@ pub enum InstitutionName {
     #[sea_orm(string_value = "Yes")]
     Yes,
 }
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "match_status")]
+pub enum MatchStatus {
+    #[sea_orm(string_value = "conflicting")]
+    Conflicting,
+    #[sea_orm(string_value = "duplicate")]
+    Duplicate,
+    #[sea_orm(string_value = "new")]
+    New,
+}
@@ -1,5 +1,6 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.1
 
+use super::sea_orm_active_enums::MatchStatus;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -10,13 +11,15 @@ pub struct Model {
     pub id: Uuid,
     pub import_id: Uuid,
     pub date: DateTime,
-    #[sea_orm(column_type = "Float")]
-    pub amount: f32,
-    #[sea_orm(column_type = "Float")]
-    pub balance: f32,
+    #[sea_orm(column_type = "Decimal(Some((19, 4)))")]
+    pub amount: Decimal,
+    #[sea_orm(column_type = "Decimal(Some((19, 4)))")]
+    pub balance: Decimal,
     pub ref_no: String,
     pub description: String,
     pub sequence_number: i64,
+    pub match_status: MatchStatus,
+    pub matched_transaction_id: Option<Uuid>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -9,14 +9,15 @@ pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     pub id: Uuid,
     pub account_id: Uuid,
-    #[sea_orm(column_type = "Float")]
-    pub amount: f32,
-    #[sea_orm(column_type = "Float")]
-    pub balance: f32,
+    #[sea_orm(column_type = "Decimal(Some((19, 4)))")]
+    pub amount: Decimal,
+    #[sea_orm(column_type = "Decimal(Some((19, 4)))")]
+    pub balance: Decimal,
     pub date: DateTime,
     pub description: String,
     pub ref_no: String,
     pub sequence_number: i64,
+    pub import_id: Option<Uuid>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -29,6 +30,14 @@ pub enum Relation {
         on_delete = "Cascade"
     )]
     Accounts,
+    #[sea_orm(
+        belongs_to = "super::imports::Entity",
+        from = "Column::ImportId",
+        to = "super::imports::Column::Id",
+        on_update = "Cascade",
+        on_delete = "SetNull"
+    )]
+    Imports,
 }
 
 impl Related<super::accounts::Entity> for Entity {
@@ -37,4 +46,10 @@ impl Related<super::accounts::Entity> for Entity {
     }
 }
 
+impl Related<super::imports::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Imports.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -1,6 +1,6 @@
 use crate::models::entities::{accounts, sea_orm_active_enums};
 use sea_orm::Order;
-use sea_orm_active_enums::AccountType;
+use sea_orm_active_enums::{AccountType, InstitutionName};
 use serde::Deserialize;
 use uuid::Uuid;
 
@@ -37,6 +37,7 @@ impl<'de> Deserialize<'de> for AccountSort {
             Some("id") => accounts::Column::Id,
             Some("account_number") => accounts::Column::AccountNumber,
             Some("type") => accounts::Column::Type,
+            Some("institution_name") => accounts::Column::InstitutionName,
             Some("updated_at") => accounts::Column::UpdatedAt,
             Some("transaction_count") => accounts::Column::TransactionCount,
             Some("max_sequence_number") => accounts::Column::MaxSequenceNumber,
@@ -60,6 +61,7 @@ pub struct AccountFilter {
     pub id: Option<Uuid>,
     pub account_number: Option<(StringFilterType, String)>,
     pub r#type: Option<AccountType>,
+    pub institution_name: Option<InstitutionName>,
 }
 
 #[derive(Default, Deserialize)]
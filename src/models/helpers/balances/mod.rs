@@ -0,0 +1,168 @@
+//! Running balances and sequence numbers of transactions added between
+//! others
+use crate::models::entities::transactions;
+use sea_orm::prelude::{DateTime, Decimal};
+
+/// Gives distinct sequence numbers to transactions inserted together
+///
+/// Transactions asking for a sequence number already given to an earlier one
+/// of the batch go right after it.
+///
+/// # Arguments
+/// * `requested` - Sequence numbers asked for, in the order of the batch
+///
+/// # Returns
+/// * `Vec<i64>` - Sequence number of each transaction, in the same order
+pub fn allocate_sequence_numbers(requested: &[i64]) -> Vec<i64> {
+    let mut order: Vec<usize> = (0..requested.len()).collect();
+    order.sort_by_key(|index| requested[*index]);
+
+    let mut allocated = vec![0; requested.len()];
+    let mut previous: Option<i64> = None;
+    for index in order {
+        let sequence_number = match previous {
+            Some(previous) => requested[index].max(previous + 1),
+            None => requested[index],
+        };
+        allocated[index] = sequence_number;
+        previous = Some(sequence_number);
+    }
+
+    allocated
+}
+
+/// Moves existing transactions out of the way of inserted ones
+///
+/// Inserted transactions keep their sequence numbers. An existing
+/// transaction holding one of them moves down to the next free number,
+/// pushing the ones after it as far as needed, the same as inserting the
+/// transactions one at a time would.
+///
+/// # Arguments
+/// * `existing` - Sequence numbers in the account, sorted
+/// * `inserted` - Distinct sequence numbers of the inserted transactions,
+///   sorted
+///
+/// # Returns
+/// * `Vec<(i64, i64)>` - Old and new sequence number of each existing
+///   transaction that moves, sorted
+pub fn shift_sequence_numbers(existing: &[i64], inserted: &[i64]) -> Vec<(i64, i64)> {
+    let mut shifts = Vec::new();
+    let mut inserted = inserted.iter().peekable();
+    let mut next_free = i64::MIN;
+
+    for &sequence_number in existing {
+        let mut candidate = sequence_number.max(next_free);
+        while let Some(&&taken) = inserted.peek() {
+            if taken < candidate {
+                inserted.next();
+            } else if taken == candidate {
+                inserted.next();
+                candidate += 1;
+            } else {
+                break;
+            }
+        }
+        if candidate != sequence_number {
+            shifts.push((sequence_number, candidate));
+        }
+        next_free = candidate + 1;
+    }
+
+    shifts
+}
+
+/// Places transactions among those of the account by date
+///
+/// A transaction goes after the last transaction of the account dated the
+/// same day or earlier, so a statement covering older days is interleaved
+/// with the history, and one covering the same day goes after what is
+/// already there. The transactions keep their order among themselves.
+///
+/// # Arguments
+/// * `existing` - Transactions of the account, sorted by sequence number
+/// * `dates` - Dates of the transactions to place, in their order
+///
+/// # Returns
+/// * `Vec<usize>` - For each transaction, how many transactions of the
+///   account go before it
+pub fn place_by_date(existing: &[transactions::Model], dates: &[DateTime]) -> Vec<usize> {
+    let mut position = 0;
+
+    dates
+        .iter()
+        .map(|date| {
+            let by_date = existing
+                .iter()
+                .rposition(|transaction| transaction.date <= *date)
+                .map_or(0, |index| index + 1);
+            position = position.max(by_date);
+            position
+        })
+        .collect()
+}
+
+/// Computes the sequence numbers of placed transactions
+///
+/// Transactions of the account after a placed one move down as far as
+/// needed (see [`shift_sequence_numbers`]), gaps in the sequence absorbing
+/// the move where there are any.
+///
+/// # Arguments
+/// * `existing` - Transactions of the account, sorted by sequence number
+/// * `positions` - Positions from [`place_by_date`]
+///
+/// # Returns
+/// * `Vec<i64>` - Sequence number of each placed transaction
+pub fn placed_sequence_numbers(existing: &[transactions::Model], positions: &[usize]) -> Vec<i64> {
+    let mut sequence_numbers = Vec::with_capacity(positions.len());
+    let mut positions = positions.iter().peekable();
+    let mut last = 0;
+
+    for (index, transaction) in existing.iter().enumerate() {
+        while positions.next_if(|position| **position == index).is_some() {
+            last += 1;
+            sequence_numbers.push(last);
+        }
+        last = transaction.sequence_number.max(last + 1);
+    }
+    for _ in positions {
+        last += 1;
+        sequence_numbers.push(last);
+    }
+
+    sequence_numbers
+}
+
+/// Computes the balances placed transactions will have
+///
+/// # Arguments
+/// * `existing` - Transactions of the account, sorted by sequence number
+/// * `positions` - Positions from [`place_by_date`]
+/// * `amounts` - Amounts of the placed transactions
+///
+/// # Returns
+/// * `Vec<Decimal>` - Balance of each placed transaction
+pub fn placed_balances(
+    existing: &[transactions::Model],
+    positions: &[usize],
+    amounts: &[Decimal],
+) -> Vec<Decimal> {
+    let mut placed = Decimal::ZERO;
+
+    positions
+        .iter()
+        .zip(amounts)
+        .map(|(position, amount)| {
+            placed += amount;
+            let previous = match position {
+                0 => Decimal::ZERO,
+                position => existing[position - 1].balance,
+            };
+            previous + placed
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,168 @@
+#[cfg(test)]
+use crate::models::{
+    entities::transactions,
+    helpers::balances::{
+        allocate_sequence_numbers, place_by_date, placed_balances, placed_sequence_numbers,
+        shift_sequence_numbers,
+    },
+};
+use rust_decimal_macros::dec;
+use sea_orm::{
+    prelude::{DateTime, Decimal},
+    sqlx::types::chrono::NaiveDate,
+};
+use uuid::Uuid;
+
+fn _existing(sequence_number: i64, amount: Decimal, balance: Decimal) -> transactions::Model {
+    transactions::Model {
+        id: Uuid::new_v4(),
+        account_id: Uuid::nil(),
+        amount,
+        balance,
+        date: DateTime::default(),
+        description: String::new(),
+        ref_no: String::new(),
+        sequence_number,
+        import_id: None,
+    }
+}
+
+fn _day(day: u32) -> DateTime {
+    NaiveDate::from_ymd_opt(2023, 1, day)
+        .unwrap()
+        .and_hms_opt(0, 0, 0)
+        .unwrap()
+}
+
+fn _account(days: &[(u32, Decimal)]) -> Vec<transactions::Model> {
+    let mut balance = Decimal::ZERO;
+    days.iter()
+        .zip(1..)
+        .map(|((day, amount), sequence_number)| {
+            balance += amount;
+            transactions::Model {
+                date: _day(*day),
+                ..(_existing(sequence_number, *amount, balance))
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn test_place_by_date() {
+    let existing = _account(&[(1, dec!(10)), (5, dec!(10)), (9, dec!(10))]);
+    let dates = [_day(3), _day(5), _day(4), _day(12)];
+
+    // The same day goes after what is there, and the fourth transaction
+    // keeps to the statement's order even though it is dated earlier
+    assert_eq!(place_by_date(&existing, &dates), [1, 2, 2, 3]);
+    assert_eq!(place_by_date(&[], &dates), [0, 0, 0, 0]);
+}
+
+#[test]
+fn test_placed_sequence_numbers() {
+    let existing = _account(&[(1, dec!(10)), (5, dec!(10)), (9, dec!(10))]);
+
+    assert_eq!(placed_sequence_numbers(&existing, &[3, 3]), [4, 5]);
+    assert_eq!(
+        placed_sequence_numbers(&existing, &[0, 1, 1, 2]),
+        [1, 3, 4, 6]
+    );
+    assert_eq!(placed_sequence_numbers(&[], &[0, 0]), [1, 2]);
+
+    // A gap in the sequence is filled before anything moves
+    let gapped = vec![
+        _existing(1, dec!(10), dec!(10)),
+        _existing(5, dec!(10), dec!(20)),
+    ];
+    assert_eq!(placed_sequence_numbers(&gapped, &[1, 1]), [2, 3]);
+}
+
+#[test]
+fn test_placed_sequence_numbers_match_shifts() {
+    // Inserting at the placed sequence numbers moves the account's
+    // transactions exactly behind the placed ones
+    let existing = _account(&[(1, dec!(10)), (5, dec!(10)), (9, dec!(10))]);
+    let placed = placed_sequence_numbers(&existing, &[0, 1, 1, 2]);
+    let existing_sequence_numbers: Vec<i64> = existing
+        .iter()
+        .map(|transaction| transaction.sequence_number)
+        .collect();
+
+    assert_eq!(
+        shift_sequence_numbers(&existing_sequence_numbers, &placed),
+        [(1, 2), (2, 5), (3, 7)]
+    );
+}
+
+#[test]
+fn test_placed_balances() {
+    let existing = _account(&[(1, dec!(100)), (5, dec!(-30)), (9, dec!(5))]);
+
+    assert_eq!(
+        placed_balances(
+            &existing,
+            &[0, 1, 1, 3],
+            &[dec!(1), dec!(2), dec!(3), dec!(4)]
+        ),
+        // Each starts from the balance of the transaction of the account
+        // before it, plus the amounts placed so far
+        [dec!(1), dec!(103), dec!(106), dec!(85)]
+    );
+}
+
+#[test]
+fn test_place_two_imports_staged_together() {
+    // Two statements of the same account were staged before either was
+    // approved: January 10-20, and an older one for January 1-9. The newer
+    // one is approved first.
+    let newer = [(10, dec!(50)), (15, dec!(-20)), (20, dec!(5))];
+    let older = [(1, dec!(100)), (9, dec!(-10))];
+
+    let dates: Vec<DateTime> = newer.iter().map(|(day, _)| _day(*day)).collect();
+    let positions = place_by_date(&[], &dates);
+    assert_eq!(placed_sequence_numbers(&[], &positions), [1, 2, 3]);
+    let account = _account(&newer);
+
+    // The older statement goes before everything approved so far
+    let dates: Vec<DateTime> = older.iter().map(|(day, _)| _day(*day)).collect();
+    let amounts: Vec<Decimal> = older.iter().map(|(_, amount)| *amount).collect();
+    let positions = place_by_date(&account, &dates);
+    assert_eq!(positions, [0, 0]);
+    assert_eq!(placed_sequence_numbers(&account, &positions), [1, 2]);
+    assert_eq!(
+        placed_balances(&account, &positions, &amounts),
+        [dec!(100), dec!(90)]
+    );
+}
+
+#[test]
+fn test_allocate_sequence_numbers() {
+    assert_eq!(allocate_sequence_numbers(&[12, 11, 14]), [12, 11, 14]);
+    assert_eq!(allocate_sequence_numbers(&[11, 11, 12]), [11, 12, 13]);
+    assert!(allocate_sequence_numbers(&[]).is_empty());
+}
+
+#[test]
+fn test_shift_sequence_numbers_appended() {
+    assert!(shift_sequence_numbers(&[1, 2, 3], &[4, 5]).is_empty());
+}
+
+#[test]
+fn test_shift_sequence_numbers_between() {
+    // 3 and 4 are pushed by the insert at 3, 6 by the one at 6, and 7 by 6
+    assert_eq!(
+        shift_sequence_numbers(&[1, 2, 3, 4, 6, 7, 10], &[3, 6]),
+        [(3, 4), (4, 5), (6, 7), (7, 8)]
+    );
+}
+
+#[test]
+fn test_shift_sequence_numbers_into_gap() {
+    // The gap after 4 absorbs the push
+    assert_eq!(
+        shift_sequence_numbers(&[3, 4, 8], &[3, 4]),
+        [(3, 5), (4, 6)]
+    );
+    assert!(shift_sequence_numbers(&[1, 5], &[2, 3]).is_empty());
+}
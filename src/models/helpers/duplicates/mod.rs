@@ -0,0 +1,94 @@
+//! Compares staged transactions with the transactions already in an account
+//!
+//! A staged row is a duplicate when a transaction of the same day has the
+//! same amount, reference and description. It conflicts with one that looks
+//! like the same transaction (same reference, or same amount and
+//! description) but differs otherwise, for example in its amount. Anything
+//! else is new. Descriptions are compared without whitespace or case, since
+//! the PDF and spreadsheet of a statement space them differently. Balances
+//! are not compared, as the account recalculates them from its own history,
+//! which need not start where the statement's does.
+use std::collections::HashSet;
+
+use crate::models::entities::{
+    sea_orm_active_enums::MatchStatus, staged_transactions, transactions,
+};
+use uuid::Uuid;
+
+/// Matches every staged transaction against the existing transactions
+///
+/// # Arguments
+/// * `staged` - Staged transactions of an import
+/// * `existing` - Transactions of the account, at least those on the dates
+///   of the staged transactions
+///
+/// # Returns
+/// * `Vec<(MatchStatus, Option<Uuid>)>` - For each staged transaction, in
+///   order, how it compares and the transaction it matched. An existing
+///   transaction is matched at most once.
+pub fn match_transactions(
+    staged: &[staged_transactions::Model],
+    existing: &[transactions::Model],
+) -> Vec<(MatchStatus, Option<Uuid>)> {
+    let mut matched: HashSet<Uuid> = HashSet::new();
+    let mut statuses = Vec::with_capacity(staged.len());
+
+    for row in staged {
+        let same_day: Vec<&transactions::Model> = existing
+            .iter()
+            .filter(|transaction| {
+                transaction.date == row.date && !matched.contains(&transaction.id)
+            })
+            .collect();
+
+        let status = if let Some(transaction) = same_day
+            .iter()
+            .find(|transaction| is_duplicate(transaction, row))
+        {
+            (MatchStatus::Duplicate, Some(transaction.id))
+        } else if let Some(transaction) = same_day
+            .iter()
+            .find(|transaction| is_same_transaction(transaction, row))
+        {
+            (MatchStatus::Conflicting, Some(transaction.id))
+        } else {
+            (MatchStatus::New, None)
+        };
+
+        if let (_, Some(id)) = status {
+            matched.insert(id);
+        }
+        statuses.push(status);
+    }
+
+    statuses
+}
+
+fn is_duplicate(transaction: &transactions::Model, row: &staged_transactions::Model) -> bool {
+    transaction.amount == row.amount
+        && transaction.ref_no.trim() == row.ref_no.trim()
+        && normalize(&transaction.description) == normalize(&row.description)
+}
+
+fn is_same_transaction(
+    transaction: &transactions::Model,
+    row: &staged_transactions::Model,
+) -> bool {
+    let same_reference =
+        !row.ref_no.trim().is_empty() && transaction.ref_no.trim() == row.ref_no.trim();
+    let same_amount_and_description = transaction.amount == row.amount
+        && normalize(&transaction.description) == normalize(&row.description);
+
+    same_reference || same_amount_and_description
+}
+
+fn normalize(description: &str) -> String {
+    description
+        .chars()
+        .filter(|character| !character.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,130 @@
+#[cfg(test)]
+use crate::models::{
+    entities::{sea_orm_active_enums::MatchStatus, staged_transactions, transactions},
+    helpers::duplicates::match_transactions,
+};
+use rust_decimal_macros::dec;
+use sea_orm::{
+    prelude::{DateTime, Decimal},
+    sqlx::types::chrono::NaiveDate,
+};
+use uuid::Uuid;
+
+fn _day(day: u32) -> DateTime {
+    NaiveDate::from_ymd_opt(2023, 1, day)
+        .unwrap()
+        .and_hms_opt(0, 0, 0)
+        .unwrap()
+}
+
+fn _existing(
+    day: u32,
+    amount: Decimal,
+    balance: Decimal,
+    ref_no: &str,
+    description: &str,
+) -> transactions::Model {
+    transactions::Model {
+        id: Uuid::new_v4(),
+        account_id: Uuid::nil(),
+        amount,
+        balance,
+        date: _day(day),
+        description: description.to_string(),
+        ref_no: ref_no.to_string(),
+        sequence_number: 0,
+        import_id: None,
+    }
+}
+
+fn _staged(
+    day: u32,
+    amount: Decimal,
+    balance: Decimal,
+    ref_no: &str,
+    description: &str,
+) -> staged_transactions::Model {
+    staged_transactions::Model {
+        id: Uuid::new_v4(),
+        import_id: Uuid::nil(),
+        date: _day(day),
+        amount,
+        balance,
+        ref_no: ref_no.to_string(),
+        description: description.to_string(),
+        sequence_number: 0,
+        match_status: MatchStatus::New,
+        matched_transaction_id: None,
+    }
+}
+
+#[test]
+fn test_match_transactions() {
+    let existing = vec![
+        _existing(1, dec!(-100), dec!(900), "0001", "UPI-TEST USER"),
+        _existing(2, dec!(200), dec!(1100), "0002", "NEFT-TESTBANK"),
+    ];
+    let staged = vec![
+        // The same row, read from the PDF of the statement
+        _staged(1, dec!(-100), dec!(900), "0001", "UPI-TESTUSER"),
+        // The same reference with another amount
+        _staged(2, dec!(250), dec!(1150), "0002", "NEFT-TESTBANK"),
+        _staged(3, dec!(-50), dec!(1100), "0003", "POS-TESTSHOP"),
+    ];
+
+    assert_eq!(
+        match_transactions(&staged, &existing),
+        [
+            (MatchStatus::Duplicate, Some(existing[0].id)),
+            (MatchStatus::Conflicting, Some(existing[1].id)),
+            (MatchStatus::New, None),
+        ]
+    );
+}
+
+#[test]
+fn test_match_transactions_other_balance() {
+    // The account's history starts at zero, the statement's does not
+    let existing = vec![_existing(
+        1,
+        dec!(-100),
+        dec!(-100),
+        "0001",
+        "UPI-TEST USER",
+    )];
+    let staged = vec![_staged(1, dec!(-100), dec!(900), "0001", "UPI-TEST USER")];
+
+    assert_eq!(
+        match_transactions(&staged, &existing),
+        [(MatchStatus::Duplicate, Some(existing[0].id))]
+    );
+}
+
+#[test]
+fn test_match_transactions_once() {
+    // Two identical purchases on the same day, only one of them imported
+    let existing = vec![_existing(1, dec!(-10), dec!(0), "", "COFFEE")];
+    let staged = vec![
+        _staged(1, dec!(-10), dec!(0), "", "COFFEE"),
+        _staged(1, dec!(-10), dec!(0), "", "COFFEE"),
+    ];
+
+    assert_eq!(
+        match_transactions(&staged, &existing),
+        [
+            (MatchStatus::Duplicate, Some(existing[0].id)),
+            (MatchStatus::New, None),
+        ]
+    );
+}
+
+#[test]
+fn test_match_transactions_other_day() {
+    let existing = vec![_existing(1, dec!(-10), dec!(90), "0001", "COFFEE")];
+    let staged = vec![_staged(2, dec!(-10), dec!(90), "0001", "COFFEE")];
+
+    assert_eq!(
+        match_transactions(&staged, &existing),
+        [(MatchStatus::New, None)]
+    );
+}
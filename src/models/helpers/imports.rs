@@ -1,4 +1,4 @@
-use crate::models::entities::imports;
+use crate::models::entities::{imports, sea_orm_active_enums::ImportStatus};
 use sea_orm::{prelude::DateTime, Order};
 use serde::Deserialize;
 use uuid::Uuid;
@@ -37,6 +37,7 @@ impl<'de> Deserialize<'de> for ImportSort {
             Some("account_id") => imports::Column::AccountId,
             Some("import_date") => imports::Column::ImportDate,
             Some("source_file_date") => imports::Column::SourceFileDate,
+            Some("status") => imports::Column::Status,
             None => imports::Column::ImportDate,
             _ => return Err(serde::de::Error::custom("Invalid column value")),
         };
@@ -58,6 +59,8 @@ pub struct ImportFilter {
     pub account_id: Option<Uuid>,
     pub import_date: Option<(DateFilterType, DateTime)>,
     pub source_file_date: Option<(DateFilterType, DateTime)>,
+    pub source_file_hash: Option<String>,
+    pub status: Option<ImportStatus>,
 }
 
 #[derive(Default, Deserialize)]
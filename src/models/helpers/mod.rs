@@ -1,4 +1,6 @@
 pub mod accounts;
+pub mod balances;
+pub mod duplicates;
 pub mod imports;
 pub mod staged_transactions;
 pub mod transactions;
@@ -85,7 +87,7 @@ where
     if let Some((filter_type, value)) = filter {
         query = match filter_type {
             StringFilterType::Contains => query.filter(column.contains(&value)),
-            StringFilterType::NotContains => query.filter(column.not_like(&format!("%{}%", value))),
+            StringFilterType::NotContains => query.filter(column.not_like(format!("%{}%", value))),
             StringFilterType::Equal => query.filter(column.eq(value)),
             StringFilterType::StartsWith => query.filter(column.starts_with(&value)),
             StringFilterType::EndsWith => query.filter(column.ends_with(&value)),
@@ -1,4 +1,4 @@
-use crate::models::entities::staged_transactions;
+use crate::models::entities::{sea_orm_active_enums::MatchStatus, staged_transactions};
 use prelude::DateTime;
 use sea_orm::{entity::*, prelude::Decimal, ActiveValue::Set, Order};
 use serde::Deserialize;
@@ -22,10 +22,10 @@ use super::{DateFilterType, NumberFilterType, StringFilterType};
 ///
 /// * `staged_transactions::ActiveModel` - The constructed ActiveModel for the staged transaction
 pub fn build_staged_transaction(
-    amount: f32,
+    amount: Decimal,
     import_id: Uuid,
     date: DateTime,
-    balance: f32,
+    balance: Decimal,
     sequence_number: i64,
     ref_no: String,
     description: String,
@@ -39,7 +39,8 @@ pub fn build_staged_transaction(
         sequence_number: Set(sequence_number),
         ref_no: Set(ref_no),
         description: Set(description),
-        ..Default::default()
+        match_status: Set(MatchStatus::New),
+        matched_transaction_id: Set(None),
     }
 }
 
@@ -80,6 +81,7 @@ impl<'de> Deserialize<'de> for StagedTransactionSort {
             Some("balance") => staged_transactions::Column::Balance,
             Some("ref_no") => staged_transactions::Column::RefNo,
             Some("description") => staged_transactions::Column::Description,
+            Some("match_status") => staged_transactions::Column::MatchStatus,
             None => staged_transactions::Column::SequenceNumber,
             _ => return Err(serde::de::Error::custom("Invalid column value")),
         };
@@ -98,6 +100,7 @@ impl<'de> Deserialize<'de> for StagedTransactionSort {
 #[derive(Debug, Default, Deserialize)]
 pub struct StagedTransactionFilter {
     pub id: Option<Uuid>,
+    pub ids: Option<Vec<Uuid>>,
     pub import_id: Option<Uuid>,
     pub sequence_number: Option<(NumberFilterType, i64)>,
     pub date: Option<(DateFilterType, DateTime)>,
@@ -105,6 +108,7 @@ pub struct StagedTransactionFilter {
     pub balance: Option<(NumberFilterType, Decimal)>,
     pub ref_no: Option<(StringFilterType, String)>,
     pub description: Option<(StringFilterType, String)>,
+    pub match_status: Option<MatchStatus>,
 }
 
 #[derive(Debug, Default, Deserialize)]
@@ -22,7 +22,7 @@ use uuid::Uuid;
 ///
 /// * `staged_transactions::ActiveModel` - The constructed ActiveModel for the staged transaction
 pub fn build_transaction(
-    amount: f32,
+    amount: Decimal,
     account_id: Uuid,
     date: DateTime,
     sequence_number: i64,
@@ -34,11 +34,11 @@ pub fn build_transaction(
         amount: Set(amount),
         account_id: Set(account_id),
         date: Set(date),
-        balance: Set(0.0),
+        balance: Set(Decimal::ZERO),
         sequence_number: Set(sequence_number),
         ref_no: Set(ref_no),
         description: Set(description),
-        ..Default::default()
+        import_id: Set(None),
     }
 }
 
@@ -78,6 +78,7 @@ impl<'de> Deserialize<'de> for TransactionSort {
             Some("balance") => transactions::Column::Balance,
             Some("ref_no") => transactions::Column::RefNo,
             Some("description") => transactions::Column::Description,
+            Some("import_id") => transactions::Column::ImportId,
             None => transactions::Column::SequenceNumber,
             _ => return Err(serde::de::Error::custom("Invalid column value")),
         };
@@ -103,6 +104,7 @@ pub struct TransactionFilter {
     pub balance: Option<(NumberFilterType, Decimal)>,
     pub ref_no: Option<(StringFilterType, String)>,
     pub description: Option<(StringFilterType, String)>,
+    pub import_id: Option<Uuid>,
 }
 
 #[derive(Default, Deserialize)]
@@ -1,9 +1,10 @@
+use crate::error::{Entity, Error};
 use crate::models::entities::{
     accounts, sea_orm_active_enums::AccountType, sea_orm_active_enums::InstitutionName,
 };
 use crate::models::helpers::{accounts::*, apply_string_filter};
 
-use sea_orm::{entity::*, query::*, DatabaseConnection, DbErr, DeleteResult, Set};
+use sea_orm::{entity::*, query::*, DatabaseConnection, DeleteResult, Set};
 use uuid::Uuid;
 
 /// Creates a new account in the database
@@ -16,13 +17,13 @@ use uuid::Uuid;
 ///
 /// # Returns
 ///
-/// * `Result<Uuid, DbErr>` - The UUID of the created account on success, or a database error on failure
+/// * `Result<Uuid, Error>` - The UUID of the created account on success, or an error on failure
 pub async fn create_account(
     db: &DatabaseConnection,
     account_number: &str,
     account_type: &AccountType,
     institution_name: &InstitutionName,
-) -> Result<accounts::Model, DbErr> {
+) -> Result<accounts::Model, Error> {
     let account = accounts::ActiveModel {
         id: Set(Uuid::new_v4()),
         account_number: Set(account_number.to_string()),
@@ -43,8 +44,9 @@ pub async fn create_account(
         },
     )
     .await?
-    .ok_or(DbErr::RecordNotFound(
-        "error.fiscal_accounts.create_account.could_not_find".to_string(),
+    .ok_or(Error::not_found(
+        "error.fiscal_accounts.create_account.could_not_find",
+        Entity::Account,
     ))
 }
 
@@ -59,19 +61,20 @@ pub async fn create_account(
 ///
 /// # Returns
 ///
-/// * `Result<accounts::Model, DbErr>` - The updated account on success, or a database error on failure
+/// * `Result<accounts::Model, Error>` - The updated account on success, or an error on failure
 pub async fn update_account(
     db: &DatabaseConnection,
     id: Uuid,
     account_type: Option<AccountType>,
     account_number: Option<String>,
     institution_name: Option<InstitutionName>,
-) -> Result<accounts::Model, DbErr> {
+) -> Result<accounts::Model, Error> {
     let mut account: accounts::ActiveModel = accounts::Entity::find_by_id(id)
         .one(db)
         .await?
-        .ok_or(DbErr::RecordNotFound(
-            "error.fiscal_accounts.update_account.not_found".to_string(),
+        .ok_or(Error::not_found(
+            "error.fiscal_accounts.update_account.not_found",
+            Entity::Account,
         ))?
         .into();
 
@@ -87,7 +90,7 @@ pub async fn update_account(
         account.institution_name = Set(new_institution_name);
     }
 
-    account.update(db).await
+    Ok(account.update(db).await?)
 }
 
 /// Delete an account by ID
@@ -99,13 +102,14 @@ pub async fn update_account(
 ///
 /// # Returns
 ///
-/// * `Result<DeleteResult, DbErr>` - The result of the delete operation
-pub async fn delete_account(db: &DatabaseConnection, id: Uuid) -> Result<DeleteResult, DbErr> {
+/// * `Result<DeleteResult, Error>` - The result of the delete operation
+pub async fn delete_account(db: &DatabaseConnection, id: Uuid) -> Result<DeleteResult, Error> {
     let delete_result = accounts::Entity::delete_by_id(id).exec(db).await?;
 
     if delete_result.rows_affected == 0 {
-        return Err(DbErr::RecordNotFound(
-            "error.fiscal_accounts.delete_account.not_found".to_string(),
+        return Err(Error::not_found(
+            "error.fiscal_accounts.delete_account.not_found",
+            Entity::Account,
         ));
     }
 
@@ -121,11 +125,11 @@ pub async fn delete_account(db: &DatabaseConnection, id: Uuid) -> Result<DeleteR
 ///
 /// # Returns
 ///
-/// * `Result<Vec<accounts::Model>, DbErr>` - List of account records or error
+/// * `Result<Vec<accounts::Model>, Error>` - List of account records or error
 pub async fn get_accounts(
     db: &DatabaseConnection,
     options: AccountsQueryOptions,
-) -> Result<Vec<accounts::Model>, DbErr> {
+) -> Result<Vec<accounts::Model>, Error> {
     let query = build_query(options);
     let accounts_list = query.all(db).await?;
     Ok(accounts_list)
@@ -140,14 +144,14 @@ pub async fn get_accounts(
 ///
 /// # Returns
 ///
-/// * `Result<Option<accounts::Model>, DbErr>` - The account record or error
+/// * `Result<Option<accounts::Model>, Error>` - The account record or error
 pub async fn get_account(
     db: &DatabaseConnection,
     options: AccountsQueryOptions,
-) -> Result<Option<accounts::Model>, DbErr> {
+) -> Result<Option<accounts::Model>, Error> {
     if let Some(filter) = &options.filter {
         if let Some(id) = &filter.id {
-            return accounts::Entity::find_by_id(id.clone()).one(db).await;
+            return Ok(accounts::Entity::find_by_id(*id).one(db).await?);
         }
     }
 
@@ -178,6 +182,10 @@ fn build_query(options: AccountsQueryOptions) -> Select<accounts::Entity> {
         if let Some(account_type) = filter.r#type {
             query = query.filter(accounts::Column::Type.eq(account_type));
         }
+
+        if let Some(institution_name) = filter.institution_name {
+            query = query.filter(accounts::Column::InstitutionName.eq(institution_name));
+        }
     }
 
     // Apply sorting if present
@@ -1,76 +1,330 @@
 use super::{
-    accounts::get_account, staged_transactions::txn_create_staged_transaction,
-    transactions::txn_create_transaction,
+    accounts::{create_account, get_account, get_accounts, update_account},
+    staged_transactions::{txn_create_staged_transaction, txn_get_staged_transactions},
+    transactions::{txn_create_transactions, txn_recalculate_balances},
 };
 use crate::{
+    error::{Entity, Error},
     models::{
-        entities::{imports, transactions},
+        entities::{
+            accounts as account_entity, imports,
+            sea_orm_active_enums::{AccountType, ImportStatus, InstitutionName, MatchStatus},
+            staged_transactions, transactions,
+        },
         helpers::{
+            balances::{place_by_date, placed_balances, placed_sequence_numbers},
+            duplicates::match_transactions,
             imports::*,
             staged_transactions::{
-                build_staged_transaction, StagedTransactionFilter, StagedTransactionsQueryOptions,
+                build_staged_transaction, StagedTransactionFilter, StagedTransactionSort,
+                StagedTransactionsQueryOptions,
             },
             *,
         },
-        manage::staged_transactions::get_staged_transactions,
     },
-    readers::parsers::types::Statement,
-    utils::datetime::get_current_naive_datetime,
+    readers::{
+        parsers::types::Statement,
+        validation::{validate_statement, ValidationReport},
+    },
+    utils::{account_number, datetime::get_current_naive_datetime},
 };
 
 use accounts::{AccountFilter, AccountsQueryOptions};
-use sea_orm::{entity::*, query::*, ActiveValue::Set, DatabaseConnection, DbErr, DeleteResult};
+use sea_orm::{
+    entity::*, prelude::Decimal, query::*, ActiveValue::Set, ConnectionTrait, DatabaseConnection,
+    DatabaseTransaction, DeleteResult, SqlErr,
+};
+use serde::Serialize;
 use uuid::Uuid;
 
-/// Put statement object in database
+/// How the account of an imported statement was found
+#[derive(Debug, Serialize)]
+#[serde(tag = "resolution", content = "account", rename_all = "snake_case")]
+pub enum AccountResolution {
+    /// An existing account holds the statement
+    Matched(account_entity::Model),
+    /// No account matched the statement, so one was created from it
+    Created(account_entity::Model),
+}
+
+impl AccountResolution {
+    pub fn account(&self) -> &account_entity::Model {
+        match self {
+            AccountResolution::Matched(account) | AccountResolution::Created(account) => account,
+        }
+    }
+}
+
+/// Outcome of importing a statement
+#[derive(Debug, Serialize)]
+pub struct StatementImport {
+    pub import_id: Uuid,
+    pub account: AccountResolution,
+    /// Checks run over the statement, also stored on the import
+    pub validation: ValidationReport,
+    /// Staged transactions already in the account
+    pub duplicates: usize,
+    /// Staged transactions that differ from one already in the account
+    pub conflicts: usize,
+}
+
+/// Put statement object in database, in the account it belongs to
+///
+/// The account is found from the statement's institution and account number
+/// (see [`resolve_account`]) unless the caller names it.
 ///
 /// # Arguments
 /// * `db` - Database connection handle
 /// * `statement` - Statement object containing account and transaction data
+/// * `account_id` - Account to import into, to settle an ambiguous match
 ///
 /// # Returns
-/// * `Result<Uuid, DbErr>` - ID of the created import staging record or error
-pub async fn create_import(
+/// * `Result<StatementImport, Error>` - ID of the created import and its account, or error
+pub async fn import_statement(
     db: &DatabaseConnection,
     statement: &Statement,
-    account_id: &Uuid,
-) -> Result<Uuid, DbErr> {
-    // Create transaction staging record
-    let import = imports::ActiveModel {
-        id: Set(Uuid::new_v4()),
-        account_id: Set(account_id.clone()),
-        import_date: Set(get_current_naive_datetime()),
-        source_file_date: Set(statement.date.naive_utc()),
-        ..Default::default()
+    account_id: Option<Uuid>,
+) -> Result<StatementImport, Error> {
+    let account = match account_id {
+        Some(account_id) => AccountResolution::Matched(
+            get_account(
+                db,
+                AccountsQueryOptions {
+                    filter: Some(AccountFilter {
+                        id: Some(account_id),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+            )
+            .await?
+            .ok_or(Error::not_found(
+                "error.imports.import_statement.account_not_found",
+                Entity::Account,
+            ))?,
+        ),
+        None => resolve_account(db, statement).await?,
     };
 
-    let import_id = imports::Entity::insert(import)
-        .exec(db)
-        .await?
-        .last_insert_id;
+    let (import_id, validation, staged) =
+        insert_import(db, statement, &account.account().id).await?;
+    let count = |status: MatchStatus| {
+        staged
+            .iter()
+            .filter(|transaction| transaction.match_status == status)
+            .count()
+    };
 
-    let mut sequence_number = match get_account(
+    Ok(StatementImport {
+        import_id,
+        account,
+        validation,
+        duplicates: count(MatchStatus::Duplicate),
+        conflicts: count(MatchStatus::Conflicting),
+    })
+}
+
+/// Find the account a statement belongs to, creating it when there is none
+///
+/// Accounts of the statement's institution are matched on the account
+/// number: an exact match wins over a masked one, where only the digits the
+/// mask leaves readable are compared. Several matches are narrowed down to
+/// those of the statement's account type. A masked account number is
+/// replaced with the full one once a statement prints it.
+///
+/// # Arguments
+/// * `db` - Database connection handle
+/// * `statement` - Statement to find the account of
+///
+/// # Returns
+/// * `Result<AccountResolution, Error>` - The matched or created account, or error
+///
+/// # Errors
+/// * `Error::AmbiguousAccount` with the accounts to choose from, when several
+///   match or the statement has no account number
+pub async fn resolve_account(
+    db: &DatabaseConnection,
+    statement: &Statement,
+) -> Result<AccountResolution, Error> {
+    let accounts = get_accounts(
         db,
         AccountsQueryOptions {
             filter: Some(AccountFilter {
-                id: Some(account_id.clone()),
+                institution_name: statement.institution.clone(),
                 ..Default::default()
             }),
             ..Default::default()
         },
     )
-    .await?
+    .await?;
+
+    let Some(number) = statement
+        .account_number
+        .as_deref()
+        .or(statement.masked_account_number.as_deref())
+    else {
+        return Err(Error::ambiguous_account(
+            "error.imports.resolve_account.account_number_missing",
+            accounts.iter().map(|account| account.id).collect(),
+        ));
+    };
+
+    let exact: Vec<&account_entity::Model> = accounts
+        .iter()
+        .filter(|account| {
+            !account_number::is_masked(number)
+                && !account_number::is_masked(&account.account_number)
+                && account_number::matches(&account.account_number, number)
+        })
+        .collect();
+    let mut candidates = if exact.is_empty() {
+        accounts
+            .iter()
+            .filter(|account| account_number::matches(&account.account_number, number))
+            .collect()
+    } else {
+        exact
+    };
+
+    if candidates.len() > 1 && statement.account_type != AccountType::Unknown {
+        let same_type: Vec<&account_entity::Model> = candidates
+            .iter()
+            .copied()
+            .filter(|account| account.r#type == statement.account_type)
+            .collect();
+        if !same_type.is_empty() {
+            candidates = same_type;
+        }
+    }
+
+    match candidates.as_slice() {
+        [] => Ok(AccountResolution::Created(
+            create_account(
+                db,
+                number,
+                &statement.account_type,
+                statement
+                    .institution
+                    .as_ref()
+                    .unwrap_or(&InstitutionName::Other),
+            )
+            .await?,
+        )),
+        [account] => {
+            let full_number = statement
+                .account_number
+                .as_ref()
+                .filter(|number| !account_number::is_masked(number));
+            match full_number {
+                Some(full_number) if account_number::is_masked(&account.account_number) => {
+                    Ok(AccountResolution::Matched(
+                        update_account(db, account.id, None, Some(full_number.clone()), None)
+                            .await?,
+                    ))
+                }
+                _ => Ok(AccountResolution::Matched((*account).clone())),
+            }
+        }
+        _ => Err(Error::ambiguous_account(
+            "error.imports.resolve_account.several_accounts_match",
+            candidates.iter().map(|account| account.id).collect(),
+        )),
+    }
+}
+
+/// Put statement object in database
+///
+/// The statement is validated first (see [`validate_statement`]) and the
+/// report is stored on the import. Inconsistencies do not stop the import.
+/// The staged transactions are then compared with those already in the
+/// account (see [`detect_duplicates`]).
+///
+/// # Arguments
+/// * `db` - Database connection handle
+/// * `statement` - Statement object containing account and transaction data
+///
+/// # Returns
+/// * `Result<Uuid, Error>` - ID of the created import staging record or error
+///
+/// # Errors
+/// * `Error::AlreadyImported` when the file the statement was read from was
+///   imported into the account before, and that import was not rejected
+pub async fn create_import(
+    db: &DatabaseConnection,
+    statement: &Statement,
+    account_id: &Uuid,
+) -> Result<Uuid, Error> {
+    let (import_id, _, _) = insert_import(db, statement, account_id).await?;
+    Ok(import_id)
+}
+
+async fn insert_import(
+    db: &DatabaseConnection,
+    statement: &Statement,
+    account_id: &Uuid,
+) -> Result<(Uuid, ValidationReport, Vec<staged_transactions::Model>), Error> {
+    let validation = validate_statement(statement);
+
+    // The import and its staged transactions are written and marked
+    // together, so a failure leaves nothing behind
+    let txn = db.begin().await?;
+
+    if account_entity::Entity::find_by_id(*account_id)
+        .one(&txn)
+        .await?
+        .is_none()
     {
-        Some(account) => account.max_sequence_number,
-        None => {
-            return Err(DbErr::RecordNotFound(
-                "error.import.account_not_found".to_string(),
+        return Err(Error::not_found(
+            "error.import.account_not_found",
+            Entity::Account,
+        ));
+    }
+
+    if let Some(source_hash) = &statement.source_hash {
+        if let Some(previous) = find_previous_import(&txn, account_id, source_hash).await? {
+            return Err(Error::already_imported(
+                "error.imports.create_import.already_imported",
+                previous.id,
             ));
         }
+    }
+
+    // Create transaction staging record
+    let import = imports::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        account_id: Set(*account_id),
+        import_date: Set(get_current_naive_datetime()),
+        source_file_date: Set(statement.date.naive_utc()),
+        validation_report: Set(serde_json::to_value(&validation).ok()),
+        source_file_hash: Set(statement.source_hash.clone()),
+        status: Set(ImportStatus::Pending),
     };
-    let txn = db.begin().await?;
+
+    let import_id = match imports::Entity::insert(import).exec(&txn).await {
+        Ok(result) => result.last_insert_id,
+        // The same file was imported at the same time, past the check above
+        Err(error) if matches!(error.sql_err(), Some(SqlErr::UniqueConstraintViolation(_))) => {
+            txn.rollback().await?;
+            let previous = match &statement.source_hash {
+                Some(source_hash) => find_previous_import(db, account_id, source_hash).await?,
+                None => None,
+            };
+            return Err(match previous {
+                Some(previous) => Error::already_imported(
+                    "error.imports.create_import.already_imported",
+                    previous.id,
+                ),
+                None => error.into(),
+            });
+        }
+        Err(error) => return Err(error.into()),
+    };
+
+    // Staged transactions are numbered in the statement's order. Their
+    // place in the account is only decided when they are approved.
+    let mut sequence_number = 0;
     for transaction in statement.transactions.iter() {
-        let amount = if transaction.deposit > 0.0 {
+        let amount = if transaction.deposit > Decimal::ZERO {
             transaction.deposit
         } else {
             -transaction.withdrawal
@@ -87,8 +341,118 @@ pub async fn create_import(
         );
         txn_create_staged_transaction(&txn, &mut staged_transaction).await?;
     }
+    let staged = txn_detect_duplicates(&txn, import_id).await?;
     txn.commit().await?;
-    Ok(import_id)
+    Ok((import_id, validation, staged))
+}
+
+/// Import of the same file into the account that was not rejected
+async fn find_previous_import<C: ConnectionTrait>(
+    db: &C,
+    account_id: &Uuid,
+    source_hash: &str,
+) -> Result<Option<imports::Model>, Error> {
+    Ok(imports::Entity::find()
+        .filter(imports::Column::AccountId.eq(*account_id))
+        .filter(imports::Column::SourceFileHash.eq(source_hash))
+        .filter(imports::Column::Status.ne(ImportStatus::Rejected))
+        .one(db)
+        .await?)
+}
+
+/// Compare the staged transactions of an import with the transactions
+/// already in its account
+///
+/// Each staged transaction is marked as new, a duplicate of a transaction in
+/// the account, or conflicting with one (see
+/// [`match_transactions`](crate::models::helpers::duplicates::match_transactions)).
+/// Only transactions on the days the import covers are compared.
+///
+/// # Arguments
+/// * `db` - Database connection handle
+/// * `import_id` - UUID of the import to check
+///
+/// # Returns
+/// * `Result<Vec<staged_transactions::Model>, Error>` - The staged transactions of the import, marked, or error
+pub async fn detect_duplicates(
+    db: &DatabaseConnection,
+    import_id: Uuid,
+) -> Result<Vec<staged_transactions::Model>, Error> {
+    let txn = db.begin().await?;
+    let marked = txn_detect_duplicates(&txn, import_id).await?;
+    txn.commit().await?;
+    Ok(marked)
+}
+
+async fn txn_detect_duplicates(
+    txn: &DatabaseTransaction,
+    import_id: Uuid,
+) -> Result<Vec<staged_transactions::Model>, Error> {
+    let import = imports::Entity::find_by_id(import_id)
+        .one(txn)
+        .await?
+        .ok_or(Error::not_found(
+            "error.imports.detect_duplicates.not_found",
+            Entity::Import,
+        ))?;
+
+    let staged = staged_transactions::Entity::find()
+        .filter(staged_transactions::Column::ImportId.eq(import_id))
+        .order_by_asc(staged_transactions::Column::SequenceNumber)
+        .all(txn)
+        .await?;
+
+    let (Some(first), Some(last)) = (
+        staged.iter().map(|transaction| transaction.date).min(),
+        staged.iter().map(|transaction| transaction.date).max(),
+    ) else {
+        return Ok(staged);
+    };
+
+    let existing = transactions::Entity::find()
+        .filter(transactions::Column::AccountId.eq(import.account_id))
+        .filter(transactions::Column::Date.between(first, last))
+        .order_by_asc(transactions::Column::SequenceNumber)
+        .all(txn)
+        .await?;
+
+    txn_mark_staged_transactions(txn, import_id, staged, &existing).await
+}
+
+/// Match staged transactions against transactions of their account, and store
+/// how each compares when it changed
+///
+/// Transactions of the staged transactions' own import are left out, so the
+/// rows a statement repeats are not taken for duplicates of each other once
+/// some are approved.
+async fn txn_mark_staged_transactions(
+    txn: &DatabaseTransaction,
+    import_id: Uuid,
+    staged: Vec<staged_transactions::Model>,
+    existing: &[transactions::Model],
+) -> Result<Vec<staged_transactions::Model>, Error> {
+    let others: Vec<transactions::Model> = existing
+        .iter()
+        .filter(|transaction| transaction.import_id != Some(import_id))
+        .cloned()
+        .collect();
+    let matches = match_transactions(&staged, &others);
+
+    let mut marked = Vec::with_capacity(staged.len());
+    for (transaction, (match_status, matched_transaction_id)) in staged.into_iter().zip(matches) {
+        if transaction.match_status == match_status
+            && transaction.matched_transaction_id == matched_transaction_id
+        {
+            marked.push(transaction);
+            continue;
+        }
+        let mut transaction: staged_transactions::ActiveModel = transaction.into();
+        transaction.match_status = Set(match_status);
+        transaction.matched_transaction_id = Set(matched_transaction_id);
+        marked.push(transaction.update(txn).await?);
+    }
+
+    Ok(marked)
 }
 
 /// Update an import's account number
@@ -101,17 +465,18 @@ pub async fn create_import(
 ///
 /// # Returns
 ///
-/// * `Result<imports::Model, DbErr>` - The updated import on success, or a database error on failure
+/// * `Result<imports::Model, Error>` - The updated import on success, or an error on failure
 pub async fn update_import(
     db: &DatabaseConnection,
     id: Uuid,
     account_id: Option<Uuid>,
-) -> Result<imports::Model, DbErr> {
+) -> Result<imports::Model, Error> {
     let mut import: imports::ActiveModel = imports::Entity::find_by_id(id)
         .one(db)
         .await?
-        .ok_or(DbErr::RecordNotFound(
-            "error.imports.update_import.not_found".to_string(),
+        .ok_or(Error::not_found(
+            "error.imports.update_import.not_found",
+            Entity::Import,
         ))?
         .into();
 
@@ -129,15 +494,16 @@ pub async fn update_import(
         .await?;
 
         if account.is_none() {
-            return Err(DbErr::RecordNotFound(
-                "error.imports.update_import.account_not_found".to_string(),
+            return Err(Error::not_found(
+                "error.imports.update_import.account_not_found",
+                Entity::Account,
             ));
         }
 
         import.account_id = Set(new_account_id);
     }
 
-    import.update(db).await
+    Ok(import.update(db).await?)
 }
 
 /// Delete an import by ID
@@ -149,87 +515,395 @@ pub async fn update_import(
 ///
 /// # Returns
 ///
-/// * `Result<DeleteResult, DbErr>` - The result of the delete operation or error
-pub async fn delete_import(db: &DatabaseConnection, id: Uuid) -> Result<DeleteResult, DbErr> {
+/// * `Result<DeleteResult, Error>` - The result of the delete operation or error
+pub async fn delete_import(db: &DatabaseConnection, id: Uuid) -> Result<DeleteResult, Error> {
     let delete_result = imports::Entity::delete_by_id(id).exec(db).await?;
 
     if delete_result.rows_affected == 0 {
-        return Err(DbErr::RecordNotFound(
-            "error.imports.delete_import.not_found".to_string(),
+        return Err(Error::not_found(
+            "error.imports.delete_import.not_found",
+            Entity::Import,
         ));
     }
 
     Ok(delete_result)
 }
 
+/// Outcome of approving the staged transactions of an import
+#[derive(Debug, Serialize)]
+pub struct ImportApproval {
+    /// Status of the import afterwards
+    pub status: ImportStatus,
+    /// Transactions added to the account
+    pub approved: Vec<transactions::Model>,
+    /// Selected staged transactions left out as duplicates
+    pub skipped_duplicates: usize,
+    /// Staged transactions still waiting for review, duplicates left out
+    pub remaining: usize,
+}
+
 /// Approve an import by ID
 ///
-/// This function promotes staged transactions associated with a given import ID to actual transactions
-/// and assigns them to a specified account. It also deletes the import record after the transactions
-/// have been successfully created.
+/// All the staged transactions of the import are approved, see
+/// [`approve_staged_transactions`].
+///
+/// # Arguments
+///
+/// * `db` - Database connection handle
+/// * `id` - UUID of the import record to approve
+/// * `account_id` - UUID of the account of the import, to which the transactions will be
+///   assigned
+///
+/// # Returns
+///
+/// * `Result<ImportApproval, Error>` - What was approved or an error
+pub async fn approve_import(
+    db: &DatabaseConnection,
+    id: Uuid,
+    account_id: Uuid,
+) -> Result<ImportApproval, Error> {
+    approve_staged_transactions(db, id, account_id, StagedTransactionFilter::default()).await
+}
+
+/// Approve some of the staged transactions of an import
+///
+/// This function promotes the staged transactions of an import that match the filter to actual
+/// transactions and assigns them to the import's account. The transactions keep a link to the
+/// import, which can be rolled back later (see [`rollback_import`]).
+///
+/// Duplicates of transactions already in the account are never approved. They are matched
+/// again here, as imports approved since they were staged may hold them. The other staged
+/// transactions stay for a later review, with their balances recalculated as if they were
+/// approved after the ones approved now, and the import is marked partially approved. Once
+/// none are left the import is marked approved, and the duplicates still staged are dropped
+/// with it.
 ///
 /// # Arguments
 ///
 /// * `db` - Database connection handle
 /// * `id` - UUID of the import record to approve
-/// * `account_id` - UUID of the account to which the transactions will be assigned
+/// * `account_id` - UUID of the account of the import, to which the transactions will be
+///   assigned
+/// * `filter` - Staged transactions to approve, such as a list of `ids`; its `import_id` is
+///   ignored
 ///
 /// # Returns
 ///
-/// * `Result<(), DbErr>` - An empty result or an error
+/// * `Result<ImportApproval, Error>` - What was approved or an error
+///
+/// # Errors
+///
+/// * `Error::InvalidImportStatus` when the import was already approved or rejected
+/// * `Error::NotFound` when the account is not the import's, as its staged transactions were
+///   matched and balanced against that one
 ///
 /// # Steps
 ///
-/// 1. Retrieve all staged transactions associated with the import ID.
-/// 2. Get the maximum sequence number for the specified account ID.
-/// 3. Promote staged transactions to actual transactions, adjusting their sequence numbers.
-/// 4. Add the new transactions to the account's records.
-/// 5. Delete the import record by its ID.
-pub async fn approve_import(
+/// 1. Retrieve the selected staged transactions of the import.
+/// 2. Place them among the account's transactions by date, which gives their sequence numbers.
+///    Duplicates are left out.
+/// 3. Add the new transactions to the account's records and drop their staged transactions.
+/// 4. Recalculate the balances of the staged transactions left, or close the import when
+///    there are none.
+///
+/// The account is locked while this runs, so imports approved at the same time are placed
+/// one after the other. SQLite has no row locks, and relies on its database-wide write lock
+/// instead: approvals write one at a time, and one that cannot take the lock may fail as busy.
+pub async fn approve_staged_transactions(
     db: &DatabaseConnection,
     id: Uuid,
     account_id: Uuid,
-) -> Result<(), DbErr> {
-    // 1. Get all the staged transactions for this import
-    let staged_transactions = get_staged_transactions(
-        db,
+    filter: StagedTransactionFilter,
+) -> Result<ImportApproval, Error> {
+    let txn = db.begin().await?;
+    let import = get_reviewable_import(
+        &txn,
+        id,
+        "error.imports.approve_import.not_found",
+        "error.imports.approve_import.invalid_status",
+    )
+    .await?;
+    if import.account_id != account_id {
+        return Err(Error::not_found(
+            "error.imports.approve_import.account_mismatch",
+            Entity::Account,
+        ));
+    }
+    account_entity::Entity::find_by_id(account_id)
+        .lock_exclusive()
+        .one(&txn)
+        .await?
+        .ok_or(Error::not_found(
+            "error.imports.approve_import.account_not_found",
+            Entity::Account,
+        ))?;
+
+    // 1. Get the selected staged transactions for this import
+    let selected = txn_get_staged_transactions(
+        &txn,
         StagedTransactionsQueryOptions {
             filter: Some(StagedTransactionFilter {
                 import_id: Some(id),
-                ..Default::default()
+                ..filter
+            }),
+            sort: Some(StagedTransactionSort {
+                column: staged_transactions::Column::SequenceNumber,
+                direction: Order::Asc,
             }),
             ..Default::default()
         },
     )
     .await?;
 
-    // 2. Promote staged_transactions to transaction with seq_id to maxSequenceId + seq_id of staged Transaction
-    let mut new_transactions: Vec<transactions::ActiveModel> = staged_transactions
+    // 2. Place the staged_transactions among the account's transactions,
+    //    leaving out those the account already has. They are matched again,
+    //    as imports approved since they were staged may hold them.
+    let existing = get_account_transactions(&txn, account_id).await?;
+    let (duplicates, selected): (Vec<_>, Vec<_>) =
+        txn_mark_staged_transactions(&txn, id, selected, &existing)
+            .await?
+            .into_iter()
+            .partition(|staged_transaction| {
+                staged_transaction.match_status == MatchStatus::Duplicate
+            });
+    let dates: Vec<_> = selected
+        .iter()
+        .map(|staged_transaction| staged_transaction.date)
+        .collect();
+    let sequence_numbers = placed_sequence_numbers(&existing, &place_by_date(&existing, &dates));
+
+    let promoted_ids: Vec<Uuid> = selected
+        .iter()
+        .map(|staged_transaction| staged_transaction.id)
+        .collect();
+    let new_transactions: Vec<transactions::ActiveModel> = selected
         .into_iter()
-        .map(|staged_transaction| transactions::ActiveModel {
-            id: Set(Uuid::new_v4()),
-            account_id: Set(account_id),
-            date: Set(staged_transaction.date),
-            amount: Set(staged_transaction.amount),
-            balance: Set(staged_transaction.balance),
-            ref_no: Set(staged_transaction.ref_no.clone()),
-            description: Set(staged_transaction.description.clone()),
-            sequence_number: Set(staged_transaction.sequence_number),
-            ..Default::default()
-        })
+        .zip(sequence_numbers)
+        .map(
+            |(staged_transaction, sequence_number)| transactions::ActiveModel {
+                id: Set(Uuid::new_v4()),
+                account_id: Set(account_id),
+                date: Set(staged_transaction.date),
+                amount: Set(staged_transaction.amount),
+                balance: Set(staged_transaction.balance),
+                ref_no: Set(staged_transaction.ref_no),
+                description: Set(staged_transaction.description),
+                sequence_number: Set(sequence_number),
+                import_id: Set(Some(id)),
+            },
+        )
         .collect();
 
     // 3. Add transactions to the account's records
+    let approved_ids = txn_create_transactions(&txn, new_transactions).await?;
+    staged_transactions::Entity::delete_many()
+        .filter(staged_transactions::Column::Id.is_in(promoted_ids))
+        .exec(&txn)
+        .await?;
+
+    // 4. Recalculate what is left, or close the import
+    let remaining = staged_transactions::Entity::find()
+        .filter(staged_transactions::Column::ImportId.eq(id))
+        .order_by_asc(staged_transactions::Column::SequenceNumber)
+        .all(&txn)
+        .await?;
+    let existing = get_account_transactions(&txn, account_id).await?;
+    let remaining: Vec<_> = txn_mark_staged_transactions(&txn, id, remaining, &existing)
+        .await?
+        .into_iter()
+        .filter(|staged_transaction| staged_transaction.match_status != MatchStatus::Duplicate)
+        .collect();
+
+    let status = if remaining.is_empty() {
+        close_import(&txn, import, ImportStatus::Approved).await?;
+        ImportStatus::Approved
+    } else {
+        txn_recalculate_staged_balances(&txn, account_id, &remaining).await?;
+        let mut import: imports::ActiveModel = import.into();
+        import.status = Set(ImportStatus::PartiallyApproved);
+        import.update(&txn).await?;
+        ImportStatus::PartiallyApproved
+    };
+
+    let approved = transactions::Entity::find()
+        .filter(transactions::Column::Id.is_in(approved_ids))
+        .order_by_asc(transactions::Column::SequenceNumber)
+        .all(&txn)
+        .await?;
+    txn.commit().await?;
+
+    Ok(ImportApproval {
+        status,
+        approved,
+        skipped_duplicates: duplicates.len(),
+        remaining: remaining.len(),
+    })
+}
+
+/// Transactions of an account, sorted by sequence number
+async fn get_account_transactions(
+    txn: &DatabaseTransaction,
+    account_id: Uuid,
+) -> Result<Vec<transactions::Model>, Error> {
+    Ok(transactions::Entity::find()
+        .filter(transactions::Column::AccountId.eq(account_id))
+        .order_by_asc(transactions::Column::SequenceNumber)
+        .all(txn)
+        .await?)
+}
+
+/// Set the balances of staged transactions to those they would have once approved
+async fn txn_recalculate_staged_balances(
+    txn: &DatabaseTransaction,
+    account_id: Uuid,
+    staged: &[staged_transactions::Model],
+) -> Result<(), Error> {
+    let existing = get_account_transactions(txn, account_id).await?;
+    let dates: Vec<_> = staged.iter().map(|transaction| transaction.date).collect();
+    let amounts: Vec<_> = staged
+        .iter()
+        .map(|transaction| transaction.amount)
+        .collect();
+    let balances = placed_balances(&existing, &place_by_date(&existing, &dates), &amounts);
+
+    for (transaction, balance) in staged.iter().zip(balances) {
+        if transaction.balance != balance {
+            let mut transaction: staged_transactions::ActiveModel = transaction.clone().into();
+            transaction.balance = Set(balance);
+            transaction.update(txn).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Reject an import by ID
+///
+/// The staged transactions of the import are dropped, and the import is kept as a record that
+/// the file was reviewed. A rejected file can be imported again.
+///
+/// # Arguments
+///
+/// * `db` - Database connection handle
+/// * `id` - UUID of the import record to reject
+///
+/// # Returns
+///
+/// * `Result<imports::Model, Error>` - The rejected import or an error
+///
+/// # Errors
+///
+/// * `Error::InvalidImportStatus` when the import was already approved or rejected
+pub async fn reject_import(db: &DatabaseConnection, id: Uuid) -> Result<imports::Model, Error> {
+    let txn = db.begin().await?;
+    let import = get_reviewable_import(
+        &txn,
+        id,
+        "error.imports.reject_import.not_found",
+        "error.imports.reject_import.invalid_status",
+    )
+    .await?;
+    let import = close_import(&txn, import, ImportStatus::Rejected).await?;
+    txn.commit().await?;
+
+    Ok(import)
+}
+
+/// Roll back an approved import by ID
+///
+/// Every transaction the import introduced is deleted, the balances of the transactions after
+/// them are recalculated, and the import is marked rejected.
+///
+/// # Arguments
+///
+/// * `db` - Database connection handle
+/// * `id` - UUID of the import record to roll back
+///
+/// # Returns
+///
+/// * `Result<u64, Error>` - Number of transactions deleted or an error
+///
+/// # Errors
+///
+/// * `Error::InvalidImportStatus` when the import was not approved
+pub async fn rollback_import(db: &DatabaseConnection, id: Uuid) -> Result<u64, Error> {
     let txn = db.begin().await?;
-    for transaction in new_transactions.iter_mut() {
-        txn_create_transaction(&txn, transaction).await?;
+    let import = imports::Entity::find_by_id(id)
+        .lock_exclusive()
+        .one(&txn)
+        .await?
+        .ok_or(Error::not_found(
+            "error.imports.rollback_import.not_found",
+            Entity::Import,
+        ))?;
+
+    match import.status {
+        ImportStatus::Approved | ImportStatus::PartiallyApproved => {}
+        ImportStatus::Pending | ImportStatus::Rejected => {
+            return Err(Error::invalid_import_status(
+                "error.imports.rollback_import.invalid_status",
+                import.status,
+            ));
+        }
+    }
+
+    // Only the balances from the earliest transaction removed on change
+    let earliest = transactions::Entity::find()
+        .filter(transactions::Column::ImportId.eq(id))
+        .order_by_asc(transactions::Column::SequenceNumber)
+        .one(&txn)
+        .await?;
+
+    let deleted = transactions::Entity::delete_many()
+        .filter(transactions::Column::ImportId.eq(id))
+        .exec(&txn)
+        .await?
+        .rows_affected;
+
+    if let Some(earliest) = earliest {
+        txn_recalculate_balances(&txn, earliest.account_id, earliest.sequence_number).await?;
     }
+    close_import(&txn, import, ImportStatus::Rejected).await?;
     txn.commit().await?;
 
-    // 4. Delete the import by id
-    delete_import(db, id).await?;
+    Ok(deleted)
+}
 
-    Ok(())
+/// Find an import that is still waiting for review, and lock it
+async fn get_reviewable_import(
+    txn: &DatabaseTransaction,
+    id: Uuid,
+    not_found: &'static str,
+    invalid_status: &'static str,
+) -> Result<imports::Model, Error> {
+    let import = imports::Entity::find_by_id(id)
+        .lock_exclusive()
+        .one(txn)
+        .await?
+        .ok_or(Error::not_found(not_found, Entity::Import))?;
+
+    match import.status {
+        ImportStatus::Pending | ImportStatus::PartiallyApproved => Ok(import),
+        ImportStatus::Approved | ImportStatus::Rejected => {
+            Err(Error::invalid_import_status(invalid_status, import.status))
+        }
+    }
+}
+
+/// Drop the staged transactions left in an import and set its final status
+async fn close_import(
+    txn: &DatabaseTransaction,
+    import: imports::Model,
+    status: ImportStatus,
+) -> Result<imports::Model, Error> {
+    staged_transactions::Entity::delete_many()
+        .filter(staged_transactions::Column::ImportId.eq(import.id))
+        .exec(txn)
+        .await?;
+
+    let mut import: imports::ActiveModel = import.into();
+    import.status = Set(status);
+    Ok(import.update(txn).await?)
 }
 
 /// Get imports with query options
@@ -243,11 +917,11 @@ pub async fn approve_import(
 ///
 /// # Returns
 ///
-/// * `Result<Vec<imports::Model>, DbErr>` - A vector of import models or an error
+/// * `Result<Vec<imports::Model>, Error>` - A vector of import models or an error
 pub async fn get_imports(
     db: &DatabaseConnection,
     options: ImportsQueryOptions,
-) -> Result<Vec<imports::Model>, DbErr> {
+) -> Result<Vec<imports::Model>, Error> {
     let query = build_query(options);
     let imports = query.all(db).await?;
     Ok(imports)
@@ -262,14 +936,14 @@ pub async fn get_imports(
 ///
 /// # Returns
 ///
-/// * `Result<Option<imports::Model>, DbErr>` - The import record or error
+/// * `Result<Option<imports::Model>, Error>` - The import record or error
 pub async fn get_import(
     db: &DatabaseConnection,
     options: ImportsQueryOptions,
-) -> Result<Option<imports::Model>, DbErr> {
+) -> Result<Option<imports::Model>, Error> {
     if let Some(filter) = &options.filter {
         if let Some(id) = &filter.id {
-            return imports::Entity::find_by_id(id.clone()).one(db).await;
+            return Ok(imports::Entity::find_by_id(*id).one(db).await?);
         }
     }
 
@@ -290,6 +964,14 @@ fn build_query(options: ImportsQueryOptions) -> Select<imports::Entity> {
             query = query.filter(imports::Column::AccountId.eq(account_id));
         }
 
+        if let Some(source_file_hash) = filter.source_file_hash {
+            query = query.filter(imports::Column::SourceFileHash.eq(source_file_hash));
+        }
+
+        if let Some(status) = filter.status {
+            query = query.filter(imports::Column::Status.eq(status));
+        }
+
         query = apply_date_filter(query, filter.import_date, imports::Column::ImportDate);
         query = apply_date_filter(
             query,
@@ -1,9 +1,10 @@
+use crate::error::Error;
 use crate::models::{
     entities::accounts,
     helpers::accounts::AccountsQueryOptions,
     manage::accounts::{get_account, get_accounts},
 };
-use sea_orm::{query::*, DatabaseConnection, DbBackend, DbErr};
+use sea_orm::{prelude::Decimal, query::*, DatabaseConnection, DbBackend};
 use uuid::Uuid;
 
 /// Retrieves a list of accounts along with their balances based on the provided query options.
@@ -15,11 +16,11 @@ use uuid::Uuid;
 ///
 /// # Returns
 ///
-/// * `Result<Vec<(accounts::Model, f32)>, DbErr>` - A vector of tuples containing an account model and its corresponding balance, or a database error.
+/// * `Result<Vec<(accounts::Model, Decimal)>, Error>` - A vector of tuples containing an account model and its corresponding balance, or an error.
 pub async fn get_accounts_with_balance(
     db: &DatabaseConnection,
     options: AccountsQueryOptions,
-) -> Result<Vec<(accounts::Model, f32)>, DbErr> {
+) -> Result<Vec<(accounts::Model, Decimal)>, Error> {
     let accounts = get_accounts(db, options).await?;
     force_raw_join(db, &accounts).await
 }
@@ -33,18 +34,18 @@ pub async fn get_accounts_with_balance(
 ///
 /// # Returns
 ///
-/// * `Result<Option<(accounts::Model, f32)>, DbErr>` - An optional tuple containing an account model and its corresponding balance, or a database error. Returns `None` if no account matches the filter.
+/// * `Result<Option<(accounts::Model, Decimal)>, Error>` - An optional tuple containing an account model and its corresponding balance, or an error. Returns `None` if no account matches the filter.
 pub async fn get_account_with_balance(
     db: &DatabaseConnection,
     options: AccountsQueryOptions,
-) -> Result<Option<(accounts::Model, f32)>, DbErr> {
+) -> Result<Option<(accounts::Model, Decimal)>, Error> {
     let account = get_account(db, options).await?;
 
     if account.is_none() {
         return Ok(None);
     }
 
-    let accounts_with_balance = force_raw_join(db, &vec![account.unwrap()]).await?;
+    let accounts_with_balance = force_raw_join(db, &[account.unwrap()]).await?;
     Ok(accounts_with_balance.first().cloned())
 }
 
@@ -54,8 +55,8 @@ pub async fn get_account_with_balance(
 // I'll come back to this later.
 async fn force_raw_join(
     db: &DatabaseConnection,
-    accounts: &Vec<accounts::Model>,
-) -> Result<Vec<(accounts::Model, f32)>, DbErr> {
+    accounts: &[accounts::Model],
+) -> Result<Vec<(accounts::Model, Decimal)>, Error> {
     let account_ids: Vec<String> = accounts
         .iter()
         .map(|account| account.id.to_string())
@@ -96,10 +97,10 @@ async fn force_raw_join(
         .iter()
         .map(|row| {
             let account_id: Uuid = row.try_get("", "account_id")?;
-            let balance: f32 = row.try_get("", "balance")?;
+            let balance: Decimal = row.try_get("", "balance")?;
             Ok((account_id, balance))
         })
-        .collect::<Result<Vec<(Uuid, f32)>, DbErr>>()?
+        .collect::<Result<Vec<(Uuid, Decimal)>, Error>>()?
         .into_iter()
         .map(|(account_id, balance)| {
             let account = accounts
@@ -109,7 +110,7 @@ async fn force_raw_join(
                 .clone();
             Ok((account, balance))
         })
-        .collect::<Result<Vec<(accounts::Model, f32)>, DbErr>>()?;
+        .collect::<Result<Vec<(accounts::Model, Decimal)>, Error>>()?;
 
     Ok(accounts_with_balance)
 }
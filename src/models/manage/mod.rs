@@ -6,3 +6,6 @@ pub mod imports;
 pub mod joins;
 pub mod staged_transactions;
 pub mod transactions;
+
+#[cfg(test)]
+pub(crate) mod tests;
@@ -1,8 +1,11 @@
-use crate::models::{
-    entities::staged_transactions,
-    helpers::{staged_transactions::*, *},
+use crate::{
+    error::{Entity, Error},
+    models::{
+        entities::staged_transactions,
+        helpers::{staged_transactions::*, *},
+    },
 };
-use prelude::DateTime;
+use prelude::{DateTime, Decimal};
 use sea_orm::{
     entity::*, query::*, ActiveValue::Set, DatabaseConnection, DatabaseTransaction, DeleteResult,
 };
@@ -15,11 +18,11 @@ use uuid::Uuid;
 /// * `transaction` - Staging transaction object to be inserted
 ///
 /// # Returns
-/// * `Result<staged_transactions::Model, sea_orm::DbErr>` - The created staging transaction or error
+/// * `Result<staged_transactions::Model, Error>` - The created staging transaction or error
 pub async fn create_staged_transaction(
     db: &DatabaseConnection,
     mut transaction: staged_transactions::ActiveModel,
-) -> Result<staged_transactions::Model, sea_orm::DbErr> {
+) -> Result<staged_transactions::Model, Error> {
     let txn = db.begin().await?;
     let inserted_transaction_id = txn_create_staged_transaction(&txn, &mut transaction).await?;
     txn.commit().await?;
@@ -35,8 +38,9 @@ pub async fn create_staged_transaction(
         },
     )
     .await?
-    .ok_or(sea_orm::DbErr::RecordNotFound(
-        "error.staged_transactions.create_staged_transaction.not_found".to_string(),
+    .ok_or(Error::not_found(
+        "error.staged_transactions.create_staged_transaction.not_found",
+        Entity::StagedTransaction,
     ))?;
 
     Ok(inserted_transaction)
@@ -45,7 +49,7 @@ pub async fn create_staged_transaction(
 pub(super) async fn txn_create_staged_transaction(
     txn: &DatabaseTransaction,
     transaction: &mut staged_transactions::ActiveModel,
-) -> Result<Uuid, sea_orm::DbErr> {
+) -> Result<Uuid, Error> {
     // Calculate the correct balance after current transaction
     if let Some(prev_txn) = build_query(StagedTransactionsQueryOptions {
         filter: Some(StagedTransactionFilter {
@@ -56,6 +60,10 @@ pub(super) async fn txn_create_staged_transaction(
             )),
             ..Default::default()
         }),
+        sort: Some(StagedTransactionSort {
+            column: staged_transactions::Column::SequenceNumber,
+            direction: Order::Desc,
+        }),
         ..Default::default()
     })
     .one(txn)
@@ -73,8 +81,10 @@ pub(super) async fn txn_create_staged_transaction(
     )
     .await?;
 
-    let mut curr_sequence_number = transaction.sequence_number.clone().unwrap();
-    for dependant in dependent_transactions.iter_mut() {
+    let first_sequence_number = transaction.sequence_number.clone().unwrap();
+    for (curr_sequence_number, dependant) in
+        (first_sequence_number..).zip(dependent_transactions.iter_mut())
+    {
         let dependant_seq_number = dependant.sequence_number.clone().unwrap();
         // This means there is a gap in the sequence numbers, so we
         // don't need to add +1 to remaining transactions
@@ -82,7 +92,6 @@ pub(super) async fn txn_create_staged_transaction(
             break;
         }
         dependant.sequence_number = Set(dependant_seq_number + 1);
-        curr_sequence_number += 1;
     }
 
     for dependant in dependent_transactions.iter_mut().rev() {
@@ -110,21 +119,22 @@ pub(super) async fn txn_create_staged_transaction(
 /// * `sequence_number` - Optional new sequence number for the transaction
 ///
 /// # Returns
-/// * `Result<staged_transactions::Model, sea_orm::DbErr>` - The updated staged transaction record or error
+/// * `Result<staged_transactions::Model, Error>` - The updated staged transaction record or error
 pub async fn update_staged_transaction(
     db: &DatabaseConnection,
     id: Uuid,
     date: Option<DateTime>,
-    amount: Option<f32>,
+    amount: Option<Decimal>,
     ref_no: Option<String>,
     description: Option<String>,
     sequence_number: Option<i64>,
-) -> Result<staged_transactions::Model, sea_orm::DbErr> {
+) -> Result<staged_transactions::Model, Error> {
     let mut transaction = staged_transactions::Entity::find_by_id(id)
         .one(db)
         .await?
-        .ok_or(sea_orm::DbErr::RecordNotFound(
-            "error.staged_transactions.update_staged_transaction.not_found".to_string(),
+        .ok_or(Error::not_found(
+            "error.staged_transactions.update_staged_transaction.not_found",
+            Entity::StagedTransaction,
         ))?
         .into_active_model();
     let original_transaction = transaction.clone();
@@ -166,8 +176,9 @@ pub async fn update_staged_transaction(
         },
     )
     .await?
-    .ok_or(sea_orm::DbErr::RecordNotFound(
-        "error.staged_transactions.update_staged_transaction.not_found".to_string(),
+    .ok_or(Error::not_found(
+        "error.staged_transactions.update_staged_transaction.not_found",
+        Entity::StagedTransaction,
     ))?;
 
     Ok(updated_transaction)
@@ -180,17 +191,18 @@ pub async fn update_staged_transaction(
 /// * `id` - UUID of the staged transaction record
 ///
 /// # Returns
-/// * `Result<DeleteResult, sea_orm::DbErr>` - The result of the delete operation or error
+/// * `Result<DeleteResult, Error>` - The result of the delete operation or error
 pub async fn delete_staged_transaction(
     db: &DatabaseConnection,
     id: Uuid,
-) -> Result<DeleteResult, sea_orm::DbErr> {
+) -> Result<DeleteResult, Error> {
     let transaction = staged_transactions::Entity::find_by_id(id)
         .one(db)
         .await?
         .ok_or_else(|| {
-            sea_orm::DbErr::RecordNotFound(
-                "error.staged_transactions.delete_staged_transaction_by_id.not_found".to_string(),
+            Error::not_found(
+                "error.staged_transactions.delete_staged_transaction_by_id.not_found",
+                Entity::StagedTransaction,
             )
         })?;
 
@@ -205,7 +217,7 @@ pub async fn delete_staged_transaction(
 pub(super) async fn txn_delete_staged_transaction(
     txn: &DatabaseTransaction,
     transaction: &staged_transactions::ActiveModel,
-) -> Result<DeleteResult, sea_orm::DbErr> {
+) -> Result<DeleteResult, Error> {
     let mut dependent_transactions = recalculate_balance(
         txn,
         transaction.import_id.clone().unwrap(),
@@ -233,16 +245,25 @@ pub(super) async fn txn_delete_staged_transaction(
 /// * `filter` - StagedTransactionFilter struct containing filter parameters
 ///
 /// # Returns
-/// * `Result<Vec<staged_transactions::Model>, sea_orm::DbErr>` - List of staged transaction records or error
+/// * `Result<Vec<staged_transactions::Model>, Error>` - List of staged transaction records or error
 pub async fn get_staged_transactions(
     db: &DatabaseConnection,
     options: StagedTransactionsQueryOptions,
-) -> Result<Vec<staged_transactions::Model>, sea_orm::DbErr> {
+) -> Result<Vec<staged_transactions::Model>, Error> {
     let query = build_query(options);
     let transactions_list = query.all(db).await?;
     Ok(transactions_list)
 }
 
+pub(super) async fn txn_get_staged_transactions(
+    txn: &DatabaseTransaction,
+    options: StagedTransactionsQueryOptions,
+) -> Result<Vec<staged_transactions::Model>, Error> {
+    let query = build_query(options);
+    let transactions_list = query.all(txn).await?;
+    Ok(transactions_list)
+}
+
 /// Get a staged transaction based on the provided filter
 ///
 /// # Arguments
@@ -250,16 +271,14 @@ pub async fn get_staged_transactions(
 /// * `filter` - StagedTransactionFilter struct containing filter parameters
 ///
 /// # Returns
-/// * `Result<Option<staged_transactions::Model>, sea_orm::DbErr>` - The staged transaction record or error
+/// * `Result<Option<staged_transactions::Model>, Error>` - The staged transaction record or error
 pub async fn get_staged_transaction(
     db: &DatabaseConnection,
     options: StagedTransactionsQueryOptions,
-) -> Result<Option<staged_transactions::Model>, sea_orm::DbErr> {
+) -> Result<Option<staged_transactions::Model>, Error> {
     if let Some(filter) = &options.filter {
         if let Some(id) = &filter.id {
-            return staged_transactions::Entity::find_by_id(id.clone())
-                .one(db)
-                .await;
+            return Ok(staged_transactions::Entity::find_by_id(*id).one(db).await?);
         }
     }
 
@@ -278,6 +297,10 @@ fn build_query(options: StagedTransactionsQueryOptions) -> Select<staged_transac
             query = query.filter(staged_transactions::Column::Id.eq(id));
         }
 
+        if let Some(ids) = filter.ids {
+            query = query.filter(staged_transactions::Column::Id.is_in(ids));
+        }
+
         if let Some(import_id) = filter.import_id {
             query = query.filter(staged_transactions::Column::ImportId.eq(import_id));
         }
@@ -296,6 +319,10 @@ fn build_query(options: StagedTransactionsQueryOptions) -> Select<staged_transac
             filter.description,
             staged_transactions::Column::Description,
         );
+
+        if let Some(match_status) = filter.match_status {
+            query = query.filter(staged_transactions::Column::MatchStatus.eq(match_status));
+        }
     }
 
     if let Some(limit) = options.limit {
@@ -310,7 +337,7 @@ fn build_query(options: StagedTransactionsQueryOptions) -> Select<staged_transac
         query = query.order_by(sort.column, sort.direction);
     }
 
-    return query;
+    query
 }
 
 /// Recalculate the balance for all staged transactions with a higher sequence number
@@ -318,8 +345,8 @@ async fn recalculate_balance(
     txn: &DatabaseTransaction,
     import_id: Uuid,
     sequence_number: i64,
-    mut current_balance: f32,
-) -> Result<Vec<staged_transactions::ActiveModel>, sea_orm::DbErr> {
+    mut current_balance: Decimal,
+) -> Result<Vec<staged_transactions::ActiveModel>, Error> {
     // Get all transactions with sequence number higher than the given transaction
     let query = build_query(StagedTransactionsQueryOptions {
         filter: Some(StagedTransactionFilter {
@@ -0,0 +1,586 @@
+#[cfg(test)]
+use crate::{
+    error::Error,
+    models::{
+        entities::{
+            accounts, imports,
+            sea_orm_active_enums::{AccountType, ImportStatus, InstitutionName},
+            staged_transactions, transactions,
+        },
+        manage::imports::{
+            approve_import, create_import, import_statement, reject_import, rollback_import,
+        },
+    },
+    readers::parsers::types::{Statement, Transaction},
+    utils::datetime::{get_current_naive_datetime, parse_date, DateFormat, IST},
+};
+use networth_db_migrations::{Migrator, MigratorTrait};
+use rust_decimal_macros::dec;
+use sea_orm::{
+    prelude::Decimal, ActiveValue::Set, ConnectOptions, ConnectionTrait, Database,
+    DatabaseConnection, EntityTrait, QueryOrder,
+};
+use std::env;
+use uuid::Uuid;
+
+/// In-memory database with the tables the imports use
+///
+/// The tables are written out, as sea-query cannot create the entities'
+/// decimal columns on SQLite. Amounts are `real`, as the migrations leave
+/// them there.
+pub(crate) async fn _database() -> DatabaseConnection {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    for table in [
+        "CREATE TABLE accounts (
+            id uuid_text NOT NULL PRIMARY KEY,
+            updated_at datetime_text NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            account_number varchar NOT NULL,
+            max_sequence_number bigint NOT NULL DEFAULT 0,
+            transaction_count bigint NOT NULL DEFAULT 0,
+            type varchar NOT NULL,
+            institution_name varchar NOT NULL
+        )",
+        "CREATE TABLE imports (
+            id uuid_text NOT NULL PRIMARY KEY,
+            account_id uuid_text NOT NULL,
+            import_date datetime_text NOT NULL,
+            source_file_date datetime_text NOT NULL,
+            validation_report json_text,
+            source_file_hash varchar,
+            status varchar NOT NULL DEFAULT 'pending'
+        )",
+        "CREATE UNIQUE INDEX idx_import_source_file_hash
+        ON imports (account_id, source_file_hash)
+        WHERE status <> 'rejected'",
+        "CREATE TABLE staged_transactions (
+            id uuid_text NOT NULL PRIMARY KEY,
+            import_id uuid_text NOT NULL,
+            date datetime_text NOT NULL,
+            amount real NOT NULL,
+            balance real NOT NULL,
+            ref_no varchar NOT NULL,
+            description varchar NOT NULL,
+            sequence_number bigint NOT NULL,
+            match_status varchar NOT NULL DEFAULT 'new',
+            matched_transaction_id uuid_text,
+            UNIQUE (import_id, sequence_number)
+        )",
+        "CREATE TABLE transactions (
+            id uuid_text NOT NULL PRIMARY KEY,
+            account_id uuid_text NOT NULL,
+            amount real NOT NULL,
+            balance real NOT NULL,
+            date datetime_text NOT NULL,
+            description varchar NOT NULL,
+            ref_no varchar NOT NULL,
+            sequence_number bigint NOT NULL,
+            import_id uuid_text,
+            UNIQUE (account_id, sequence_number)
+        )",
+    ] {
+        db.execute_unprepared(table).await.unwrap();
+    }
+    db
+}
+
+/// Throwaway schema of the Postgres database `DATABASE_URL` names, with the
+/// migrations run
+struct _Postgres {
+    db: DatabaseConnection,
+    schema: String,
+}
+
+impl _Postgres {
+    /// Connects to a new schema, or returns `None` when `DATABASE_URL` does
+    /// not name a Postgres database, to leave the Postgres tests out
+    async fn connect() -> Option<Self> {
+        let url = env::var("DATABASE_URL")
+            .ok()
+            .filter(|url| url.starts_with("postgres"))?;
+        let schema = format!("test_{}", Uuid::new_v4().simple());
+        Database::connect(&url)
+            .await
+            .unwrap()
+            .execute_unprepared(&format!("CREATE SCHEMA {schema}"))
+            .await
+            .unwrap();
+
+        let mut options = ConnectOptions::new(url);
+        options.set_schema_search_path(schema.clone());
+        let db = Database::connect(options).await.unwrap();
+        Migrator::up(&db, None).await.unwrap();
+        Some(_Postgres { db, schema })
+    }
+
+    async fn drop(self) {
+        self.db
+            .execute_unprepared(&format!("DROP SCHEMA {} CASCADE", self.schema))
+            .await
+            .unwrap();
+    }
+}
+
+/// Makes a test of each scenario, run against the tables of [`_database`],
+/// then against the migrated schema of [`_Postgres`] when there is one
+macro_rules! import_tests {
+    ($($test:ident => $scenario:ident),* $(,)?) => {
+        $(
+            #[tokio::test]
+            async fn $test() {
+                $scenario(&_database().await).await;
+                if let Some(postgres) = _Postgres::connect().await {
+                    $scenario(&postgres.db).await;
+                    postgres.drop().await;
+                }
+            }
+        )*
+    };
+}
+
+import_tests! {
+    test_approve_imports_staged_together => _approve_imports_staged_together,
+    test_import_overlapping_statement => _import_overlapping_statement,
+    test_approve_overlapping_imports_staged_together => _approve_overlapping_imports_staged_together,
+    test_approve_into_another_account => _approve_into_another_account,
+    test_import_same_file_again => _import_same_file_again,
+    test_import_into_missing_account => _import_into_missing_account,
+    test_amounts => _amounts,
+    test_rollback_import => _rollback_import,
+    test_approve_interleaved_imports => _approve_interleaved_imports,
+}
+
+#[tokio::test]
+async fn test_approve_imports_at_the_same_time() {
+    // SQLite writes one transaction at a time, so only Postgres approves at
+    // the same time, each approval waiting on the account's row lock
+    let Some(postgres) = _Postgres::connect().await else {
+        return;
+    };
+    let db = &postgres.db;
+    let account_id = _account(db).await;
+
+    let first = _statement("first", &[("01/01/2023", dec!(1)), ("03/01/2023", dec!(3))]);
+    let second = _statement(
+        "second",
+        &[("02/01/2023", dec!(2)), ("04/01/2023", dec!(4))],
+    );
+    let first = import_statement(db, &first, Some(account_id))
+        .await
+        .unwrap();
+    let second = import_statement(db, &second, Some(account_id))
+        .await
+        .unwrap();
+
+    let (first, second) = tokio::join!(
+        approve_import(db, first.import_id, account_id),
+        approve_import(db, second.import_id, account_id),
+    );
+    first.unwrap();
+    second.unwrap();
+
+    // Whichever ran first, the other was placed around it
+    assert_eq!(
+        _transactions(db).await,
+        [
+            (1, "DEPOSIT 01/01/2023".to_string(), dec!(1)),
+            (2, "DEPOSIT 02/01/2023".to_string(), dec!(3)),
+            (3, "DEPOSIT 03/01/2023".to_string(), dec!(6)),
+            (4, "DEPOSIT 04/01/2023".to_string(), dec!(10)),
+        ]
+    );
+
+    postgres.drop().await;
+}
+
+#[tokio::test]
+async fn test_postgres_migrations() {
+    let Some(postgres) = _Postgres::connect().await else {
+        return;
+    };
+
+    Migrator::down(&postgres.db, None).await.unwrap();
+    assert!(Migrator::get_applied_migrations(&postgres.db)
+        .await
+        .unwrap()
+        .is_empty());
+    Migrator::up(&postgres.db, None).await.unwrap();
+    assert!(Migrator::get_pending_migrations(&postgres.db)
+        .await
+        .unwrap()
+        .is_empty());
+
+    postgres.drop().await;
+}
+
+async fn _account(db: &DatabaseConnection) -> Uuid {
+    let id = Uuid::new_v4();
+    accounts::Entity::insert(accounts::ActiveModel {
+        id: Set(id),
+        updated_at: Set(get_current_naive_datetime()),
+        account_number: Set("50100012345678".to_string()),
+        max_sequence_number: Set(0),
+        transaction_count: Set(0),
+        r#type: Set(AccountType::SavingsAccount),
+        institution_name: Set(InstitutionName::Hdfc),
+    })
+    .exec(db)
+    .await
+    .unwrap();
+    id
+}
+
+/// Statement of deposits on the given days of January 2023
+fn _statement(source_hash: &str, deposits: &[(&str, Decimal)]) -> Statement {
+    _statement_from(source_hash, Decimal::ZERO, deposits)
+}
+
+/// Statement of deposits on the given days of January 2023, onto an
+/// opening balance
+fn _statement_from(
+    source_hash: &str,
+    opening_balance: Decimal,
+    deposits: &[(&str, Decimal)],
+) -> Statement {
+    let date = |day: &str| parse_date(day, &[DateFormat::DD_MM_YYYY], IST).unwrap();
+    let mut balance = opening_balance;
+
+    Statement {
+        institution: Some(InstitutionName::Hdfc),
+        account_type: AccountType::SavingsAccount,
+        account_number: Some("50100012345678".to_string()),
+        masked_account_number: None,
+        ifsc: None,
+        branch: None,
+        holder_name: None,
+        currency: None,
+        date: date(deposits[deposits.len() - 1].0),
+        timezone: IST,
+        period_start: None,
+        period_end: None,
+        opening_balance: None,
+        closing_balance: None,
+        totals: None,
+        source_hash: Some(source_hash.to_string()),
+        skipped_rows: Vec::new(),
+        warnings: Vec::new(),
+        transactions: deposits
+            .iter()
+            .map(|(day, deposit)| {
+                balance += deposit;
+                Transaction {
+                    date: date(day),
+                    description: format!("DEPOSIT {}", day),
+                    ref_no: String::new(),
+                    withdrawal: Decimal::ZERO,
+                    deposit: *deposit,
+                    balance,
+                    warnings: Vec::new(),
+                }
+            })
+            .collect(),
+    }
+}
+
+async fn _transactions(db: &DatabaseConnection) -> Vec<(i64, String, Decimal)> {
+    transactions::Entity::find()
+        .order_by_asc(transactions::Column::SequenceNumber)
+        .all(db)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|transaction| {
+            (
+                transaction.sequence_number,
+                transaction.description,
+                transaction.balance,
+            )
+        })
+        .collect()
+}
+
+async fn _approve_imports_staged_together(db: &DatabaseConnection) {
+    let account_id = _account(db).await;
+
+    // Both statements are staged before either is approved, and the older
+    // one is approved last
+    let newer = _statement(
+        "newer",
+        &[("10/01/2023", dec!(10)), ("20/01/2023", dec!(20))],
+    );
+    let older = _statement("older", &[("05/01/2023", dec!(1)), ("15/01/2023", dec!(2))]);
+    let newer = import_statement(db, &newer, Some(account_id))
+        .await
+        .unwrap();
+    let older = import_statement(db, &older, Some(account_id))
+        .await
+        .unwrap();
+
+    approve_import(db, newer.import_id, account_id)
+        .await
+        .unwrap();
+    let approval = approve_import(db, older.import_id, account_id)
+        .await
+        .unwrap();
+
+    assert_eq!(approval.status, ImportStatus::Approved);
+    assert_eq!(
+        _transactions(db).await,
+        [
+            (1, "DEPOSIT 05/01/2023".to_string(), dec!(1)),
+            (2, "DEPOSIT 10/01/2023".to_string(), dec!(11)),
+            (3, "DEPOSIT 15/01/2023".to_string(), dec!(13)),
+            (4, "DEPOSIT 20/01/2023".to_string(), dec!(33)),
+        ]
+    );
+}
+
+async fn _import_overlapping_statement(db: &DatabaseConnection) {
+    let account_id = _account(db).await;
+
+    // The account's balances start at zero, while the statements carry on
+    // from an opening balance
+    let first = _statement_from(
+        "first",
+        dec!(1000),
+        &[("01/01/2023", dec!(1)), ("02/01/2023", dec!(2))],
+    );
+    let first = import_statement(db, &first, Some(account_id))
+        .await
+        .unwrap();
+    approve_import(db, first.import_id, account_id)
+        .await
+        .unwrap();
+
+    let second = _statement_from(
+        "second",
+        dec!(1000),
+        &[
+            ("01/01/2023", dec!(1)),
+            ("02/01/2023", dec!(2)),
+            ("03/01/2023", dec!(3)),
+        ],
+    );
+    let second = import_statement(db, &second, Some(account_id))
+        .await
+        .unwrap();
+    assert_eq!(second.duplicates, 2);
+    assert_eq!(second.conflicts, 0);
+
+    let approval = approve_import(db, second.import_id, account_id)
+        .await
+        .unwrap();
+    assert_eq!(approval.skipped_duplicates, 2);
+    assert_eq!(
+        _transactions(db).await,
+        [
+            (1, "DEPOSIT 01/01/2023".to_string(), dec!(1)),
+            (2, "DEPOSIT 02/01/2023".to_string(), dec!(3)),
+            (3, "DEPOSIT 03/01/2023".to_string(), dec!(6)),
+        ]
+    );
+}
+
+async fn _approve_overlapping_imports_staged_together(db: &DatabaseConnection) {
+    let account_id = _account(db).await;
+
+    // The second statement repeats the rows of the first, and both are
+    // staged before either is approved
+    let first = _statement("first", &[("01/01/2023", dec!(1)), ("02/01/2023", dec!(2))]);
+    let second = _statement(
+        "second",
+        &[
+            ("01/01/2023", dec!(1)),
+            ("02/01/2023", dec!(2)),
+            ("03/01/2023", dec!(3)),
+        ],
+    );
+    let first = import_statement(db, &first, Some(account_id))
+        .await
+        .unwrap();
+    let second = import_statement(db, &second, Some(account_id))
+        .await
+        .unwrap();
+    assert_eq!(second.duplicates, 0);
+
+    approve_import(db, first.import_id, account_id)
+        .await
+        .unwrap();
+    let approval = approve_import(db, second.import_id, account_id)
+        .await
+        .unwrap();
+
+    assert_eq!(approval.skipped_duplicates, 2);
+    assert_eq!(approval.approved.len(), 1);
+    assert_eq!(
+        _transactions(db).await,
+        [
+            (1, "DEPOSIT 01/01/2023".to_string(), dec!(1)),
+            (2, "DEPOSIT 02/01/2023".to_string(), dec!(3)),
+            (3, "DEPOSIT 03/01/2023".to_string(), dec!(6)),
+        ]
+    );
+}
+
+async fn _approve_into_another_account(db: &DatabaseConnection) {
+    let account_id = _account(db).await;
+    let other_account_id = _account(db).await;
+    let statement = _statement("other", &[("01/01/2023", dec!(1))]);
+    let import = import_statement(db, &statement, Some(account_id))
+        .await
+        .unwrap();
+
+    let error = approve_import(db, import.import_id, other_account_id)
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.key(), "error.imports.approve_import.account_mismatch");
+    assert!(_transactions(db).await.is_empty());
+}
+
+async fn _import_same_file_again(db: &DatabaseConnection) {
+    let account_id = _account(db).await;
+    let statement = _statement("same", &[("01/01/2023", dec!(1))]);
+
+    let first = import_statement(db, &statement, Some(account_id))
+        .await
+        .unwrap();
+    let error = import_statement(db, &statement, Some(account_id))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        Error::AlreadyImported { import_id, .. } if import_id == first.import_id
+    ));
+
+    // A rejected file can be imported again
+    reject_import(db, first.import_id).await.unwrap();
+    import_statement(db, &statement, Some(account_id))
+        .await
+        .unwrap();
+    assert_eq!(imports::Entity::find().all(db).await.unwrap().len(), 2);
+}
+
+async fn _import_into_missing_account(db: &DatabaseConnection) {
+    let statement = _statement("missing", &[("01/01/2023", dec!(1))]);
+
+    let error = create_import(db, &statement, &Uuid::new_v4())
+        .await
+        .unwrap_err();
+
+    assert!(matches!(error, Error::NotFound { .. }));
+    assert!(imports::Entity::find().all(db).await.unwrap().is_empty());
+    assert!(staged_transactions::Entity::find()
+        .all(db)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+async fn _amounts(db: &DatabaseConnection) {
+    let account_id = _account(db).await;
+
+    // Postgres stores the amounts as numeric(19, 4), while SQLite stores them
+    // as floating point numbers, which keep four decimal places exact up to
+    // about a hundred billion
+    let statement = _statement(
+        "amounts",
+        &[
+            ("01/01/2023", dec!(0.1)),
+            ("02/01/2023", dec!(0.2)),
+            ("03/01/2023", dec!(99999999999.6999)),
+        ],
+    );
+    let import = import_statement(db, &statement, Some(account_id))
+        .await
+        .unwrap();
+    approve_import(db, import.import_id, account_id)
+        .await
+        .unwrap();
+    let amounts: Vec<Decimal> = transactions::Entity::find()
+        .order_by_asc(transactions::Column::SequenceNumber)
+        .all(db)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|transaction| transaction.amount)
+        .collect();
+
+    assert_eq!(amounts, [dec!(0.1), dec!(0.2), dec!(99999999999.6999)]);
+    assert_eq!(
+        _transactions(db).await,
+        [
+            (1, "DEPOSIT 01/01/2023".to_string(), dec!(0.1)),
+            (2, "DEPOSIT 02/01/2023".to_string(), dec!(0.3)),
+            (3, "DEPOSIT 03/01/2023".to_string(), dec!(99999999999.9999)),
+        ]
+    );
+}
+
+async fn _rollback_import(db: &DatabaseConnection) {
+    let account_id = _account(db).await;
+
+    let first = _statement("first", &[("01/01/2023", dec!(1)), ("03/01/2023", dec!(3))]);
+    let second = _statement(
+        "second",
+        &[("02/01/2023", dec!(2)), ("04/01/2023", dec!(4))],
+    );
+    let first = import_statement(db, &first, Some(account_id))
+        .await
+        .unwrap();
+    let second = import_statement(db, &second, Some(account_id))
+        .await
+        .unwrap();
+    approve_import(db, first.import_id, account_id)
+        .await
+        .unwrap();
+    approve_import(db, second.import_id, account_id)
+        .await
+        .unwrap();
+
+    let deleted = rollback_import(db, first.import_id).await.unwrap();
+
+    assert_eq!(deleted, 2);
+    assert_eq!(
+        _transactions(db).await,
+        [
+            (2, "DEPOSIT 02/01/2023".to_string(), dec!(2)),
+            (4, "DEPOSIT 04/01/2023".to_string(), dec!(6)),
+        ]
+    );
+    let error = rollback_import(db, first.import_id).await.unwrap_err();
+    assert!(matches!(error, Error::InvalidImportStatus { .. }));
+}
+
+async fn _approve_interleaved_imports(db: &DatabaseConnection) {
+    let account_id = _account(db).await;
+
+    let first = _statement("first", &[("01/01/2023", dec!(1)), ("03/01/2023", dec!(3))]);
+    let second = _statement(
+        "second",
+        &[("02/01/2023", dec!(2)), ("04/01/2023", dec!(4))],
+    );
+    let first = import_statement(db, &first, Some(account_id))
+        .await
+        .unwrap();
+    let second = import_statement(db, &second, Some(account_id))
+        .await
+        .unwrap();
+
+    approve_import(db, first.import_id, account_id)
+        .await
+        .unwrap();
+    approve_import(db, second.import_id, account_id)
+        .await
+        .unwrap();
+
+    // The second import was placed around the first
+    assert_eq!(
+        _transactions(db).await,
+        [
+            (1, "DEPOSIT 01/01/2023".to_string(), dec!(1)),
+            (2, "DEPOSIT 02/01/2023".to_string(), dec!(3)),
+            (3, "DEPOSIT 03/01/2023".to_string(), dec!(6)),
+            (4, "DEPOSIT 04/01/2023".to_string(), dec!(10)),
+        ]
+    );
+}
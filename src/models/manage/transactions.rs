@@ -1,13 +1,20 @@
+use std::collections::BTreeMap;
+
+use crate::error::{Entity, Error};
 use crate::models::{
     entities::transactions,
     helpers::{
         apply_date_filter, apply_number_filter, apply_string_filter,
+        balances::{allocate_sequence_numbers, shift_sequence_numbers},
         transactions::{TransactionFilter, TransactionSort, TransactionsQueryOptions},
         NumberFilterType,
     },
 };
 use prelude::DateTime;
-use sea_orm::{entity::*, query::*, DatabaseConnection, DatabaseTransaction, DeleteResult};
+use sea_orm::{
+    entity::*, prelude::Decimal, query::*, sea_query::Expr, DatabaseConnection,
+    DatabaseTransaction, DbBackend, DeleteResult, Statement,
+};
 use uuid::Uuid;
 
 /// Insert a transaction into the database
@@ -17,11 +24,11 @@ use uuid::Uuid;
 /// * `transaction` - Transaction object to be inserted
 ///
 /// # Returns
-/// * `Result<Uuid, sea_orm::DbErr>` - UUID of the created transaction or error
+/// * `Result<Uuid, Error>` - UUID of the created transaction or error
 pub async fn create_transaction(
     db: &DatabaseConnection,
     mut transaction: transactions::ActiveModel,
-) -> Result<transactions::Model, sea_orm::DbErr> {
+) -> Result<transactions::Model, Error> {
     let txn = db.begin().await?;
     let inserted_transaction_id = txn_create_transaction(&txn, &mut transaction).await?;
     txn.commit().await?;
@@ -37,8 +44,9 @@ pub async fn create_transaction(
         },
     )
     .await?
-    .ok_or(sea_orm::DbErr::RecordNotFound(
-        "error.staged_transactions.create_staged_transaction.not_found".to_string(),
+    .ok_or(Error::not_found(
+        "error.staged_transactions.create_staged_transaction.not_found",
+        Entity::StagedTransaction,
     ))?;
 
     Ok(inserted_transaction)
@@ -47,7 +55,7 @@ pub async fn create_transaction(
 pub(super) async fn txn_create_transaction(
     txn: &DatabaseTransaction,
     transaction: &mut transactions::ActiveModel,
-) -> Result<Uuid, sea_orm::DbErr> {
+) -> Result<Uuid, Error> {
     // Calculate the correct balance after current transaction
     transaction.balance = Set(build_query(TransactionsQueryOptions {
         filter: Some(TransactionFilter {
@@ -58,6 +66,11 @@ pub(super) async fn txn_create_transaction(
             )),
             ..Default::default()
         }),
+        // The closest earlier transaction, not whichever the database returns first
+        sort: Some(TransactionSort {
+            column: transactions::Column::SequenceNumber,
+            direction: Order::Desc,
+        }),
         ..Default::default()
     })
     .one(txn)
@@ -74,8 +87,10 @@ pub(super) async fn txn_create_transaction(
     )
     .await?;
 
-    let mut curr_sequence_number = transaction.sequence_number.clone().unwrap();
-    for dependant in dependent_transactions.iter_mut() {
+    let first_sequence_number = transaction.sequence_number.clone().unwrap();
+    for (curr_sequence_number, dependant) in
+        (first_sequence_number..).zip(dependent_transactions.iter_mut())
+    {
         let dependant_seq_number = dependant.sequence_number.clone().unwrap();
         // This means there is a gap in the sequence numbers, so we
         // don't need to add +1 to remaining transactions
@@ -83,7 +98,6 @@ pub(super) async fn txn_create_transaction(
             break;
         }
         dependant.sequence_number = Set(dependant_seq_number + 1);
-        curr_sequence_number += 1;
     }
 
     for dependant in dependent_transactions.iter_mut().rev() {
@@ -98,6 +112,237 @@ pub(super) async fn txn_create_transaction(
     Ok(inserted_transaction_id)
 }
 
+/// Insert transactions into the database in bulk
+///
+/// Unlike [`create_transaction`] called for each of them, the rows are
+/// inserted a batch at a time and the running balances are recalculated once
+/// per account, in a single statement, so the cost does not grow with the
+/// number of transactions after them.
+///
+/// # Arguments
+/// * `db` - Database connection handle
+/// * `transactions` - Transaction objects to be inserted, of any accounts
+///
+/// # Returns
+/// * `Result<Vec<transactions::Model>, Error>` - The created transactions, in order, or error
+pub async fn create_transactions(
+    db: &DatabaseConnection,
+    transactions: Vec<transactions::ActiveModel>,
+) -> Result<Vec<transactions::Model>, Error> {
+    let txn = db.begin().await?;
+    let inserted_transaction_ids = txn_create_transactions(&txn, transactions).await?;
+    txn.commit().await?;
+
+    let mut inserted_transactions = transactions::Entity::find()
+        .filter(transactions::Column::Id.is_in(inserted_transaction_ids.clone()))
+        .all(db)
+        .await?;
+    inserted_transactions.sort_by_key(|transaction| {
+        inserted_transaction_ids
+            .iter()
+            .position(|id| *id == transaction.id)
+    });
+
+    Ok(inserted_transactions)
+}
+
+/// Rows inserted per statement, well under the bound parameter limits of
+/// SQLite and Postgres
+const INSERT_BATCH_SIZE: usize = 1000;
+
+pub(super) async fn txn_create_transactions(
+    txn: &DatabaseTransaction,
+    transactions: Vec<transactions::ActiveModel>,
+) -> Result<Vec<Uuid>, Error> {
+    let inserted_transaction_ids: Vec<Uuid> = transactions
+        .iter()
+        .map(|transaction| transaction.id.clone().unwrap())
+        .collect();
+
+    let mut accounts: BTreeMap<Uuid, Vec<transactions::ActiveModel>> = BTreeMap::new();
+    for transaction in transactions {
+        accounts
+            .entry(transaction.account_id.clone().unwrap())
+            .or_default()
+            .push(transaction);
+    }
+
+    for (account_id, mut transactions) in accounts {
+        let requested: Vec<i64> = transactions
+            .iter()
+            .map(|transaction| transaction.sequence_number.clone().unwrap())
+            .collect();
+        for (transaction, sequence_number) in transactions
+            .iter_mut()
+            .zip(allocate_sequence_numbers(&requested))
+        {
+            transaction.sequence_number = Set(sequence_number);
+        }
+        transactions.sort_by_key(|transaction| transaction.sequence_number.clone().unwrap());
+
+        let inserted: Vec<i64> = transactions
+            .iter()
+            .map(|transaction| transaction.sequence_number.clone().unwrap())
+            .collect();
+        let first_sequence_number = inserted[0];
+
+        // Make room for the new transactions
+        let existing: Vec<i64> = transactions::Entity::find()
+            .select_only()
+            .column(transactions::Column::SequenceNumber)
+            .filter(transactions::Column::AccountId.eq(account_id))
+            .filter(transactions::Column::SequenceNumber.gte(first_sequence_number))
+            .order_by_asc(transactions::Column::SequenceNumber)
+            .into_tuple()
+            .all(txn)
+            .await?;
+        txn_shift_sequence_numbers(
+            txn,
+            account_id,
+            &shift_sequence_numbers(&existing, &inserted),
+        )
+        .await?;
+
+        for batch in transactions.chunks(INSERT_BATCH_SIZE) {
+            transactions::Entity::insert_many(batch.to_vec())
+                .exec_without_returning(txn)
+                .await?;
+        }
+
+        txn_recalculate_balances(txn, account_id, first_sequence_number).await?;
+    }
+
+    Ok(inserted_transaction_ids)
+}
+
+/// Move transactions to new sequence numbers without ever having two on
+/// the same one
+///
+/// The moved transactions first go to the negated new sequence number, which
+/// no transaction uses, then all of them are flipped back at once. Runs of
+/// consecutive transactions moving by the same amount are moved together.
+async fn txn_shift_sequence_numbers(
+    txn: &DatabaseTransaction,
+    account_id: Uuid,
+    shifts: &[(i64, i64)],
+) -> Result<(), Error> {
+    if shifts.is_empty() {
+        return Ok(());
+    }
+
+    // A run holds consecutive sequence numbers, so no transaction that stays
+    // in place falls within it
+    for run in
+        shifts.chunk_by(|(old_a, new_a), (old_b, new_b)| old_b - old_a == 1 && new_b - new_a == 1)
+    {
+        let (first, _) = run[0];
+        let (last, _) = run[run.len() - 1];
+        let shift = run[0].1 - run[0].0;
+
+        transactions::Entity::update_many()
+            .col_expr(
+                transactions::Column::SequenceNumber,
+                Expr::value(-shift).sub(Expr::col(transactions::Column::SequenceNumber)),
+            )
+            .filter(transactions::Column::AccountId.eq(account_id))
+            .filter(transactions::Column::SequenceNumber.between(first, last))
+            .exec(txn)
+            .await?;
+    }
+
+    transactions::Entity::update_many()
+        .col_expr(
+            transactions::Column::SequenceNumber,
+            Expr::value(0).sub(Expr::col(transactions::Column::SequenceNumber)),
+        )
+        .filter(transactions::Column::AccountId.eq(account_id))
+        .filter(transactions::Column::SequenceNumber.lt(0))
+        .exec(txn)
+        .await?;
+
+    Ok(())
+}
+
+/// Recalculate the running balances of an account
+///
+/// All the balances are recalculated in a single statement, from the amounts
+/// of the transactions.
+///
+/// # Arguments
+/// * `db` - Database connection handle
+/// * `account_id` - UUID of the account
+///
+/// # Returns
+/// * `Result<(), Error>` - An empty result or error
+pub async fn recalculate_balances(db: &DatabaseConnection, account_id: Uuid) -> Result<(), Error> {
+    let txn = db.begin().await?;
+    txn_recalculate_balances(&txn, account_id, i64::MIN).await?;
+    txn.commit().await?;
+    Ok(())
+}
+
+/// Recalculate the running balances of an account from a sequence number on
+///
+/// The balances are the balance of the transaction before
+/// `sequence_number`, plus a running sum of the amounts computed with a
+/// window function.
+pub(super) async fn txn_recalculate_balances(
+    txn: &DatabaseTransaction,
+    account_id: Uuid,
+    sequence_number: i64,
+) -> Result<(), Error> {
+    let opening = transactions::Entity::find()
+        .filter(transactions::Column::AccountId.eq(account_id))
+        .filter(transactions::Column::SequenceNumber.lt(sequence_number))
+        .order_by_desc(transactions::Column::SequenceNumber)
+        .one(txn)
+        .await?
+        .map(|previous| previous.balance)
+        .unwrap_or(Decimal::ZERO);
+
+    let backend = txn.get_database_backend();
+    let sql = match backend {
+        DbBackend::Postgres => {
+            r#"UPDATE transactions SET balance = running.balance
+               FROM (
+                   SELECT id, $1 + SUM(amount) OVER (ORDER BY sequence_number) AS balance
+                   FROM transactions
+                   WHERE account_id = $2 AND sequence_number >= $3
+               ) AS running
+               WHERE transactions.id = running.id"#
+        }
+        // SQLite keeps the amounts as floating point numbers (see the
+        // decimal amounts migration), so the sum is rounded back to the
+        // scale of the column
+        DbBackend::Sqlite => {
+            r#"UPDATE transactions SET balance = running.balance
+               FROM (
+                   SELECT id, ROUND(? + SUM(amount) OVER (ORDER BY sequence_number), 4) AS balance
+                   FROM transactions
+                   WHERE account_id = ? AND sequence_number >= ?
+               ) AS running
+               WHERE transactions.id = running.id"#
+        }
+        DbBackend::MySql => {
+            r#"UPDATE transactions JOIN (
+                   SELECT id, ? + SUM(amount) OVER (ORDER BY sequence_number) AS balance
+                   FROM transactions
+                   WHERE account_id = ? AND sequence_number >= ?
+               ) AS running ON transactions.id = running.id
+               SET transactions.balance = running.balance"#
+        }
+    };
+
+    txn.execute(Statement::from_sql_and_values(
+        backend,
+        sql,
+        [opening.into(), account_id.into(), sequence_number.into()],
+    ))
+    .await?;
+
+    Ok(())
+}
+
 /// Update a transaction
 ///
 /// # Arguments
@@ -111,22 +356,24 @@ pub(super) async fn txn_create_transaction(
 /// * `sequence_number` - Optional new sequence number for the transaction
 ///
 /// # Returns
-/// * `Result<transactions::Model, sea_orm::DbErr>` - The updated transaction record or error
+/// * `Result<transactions::Model, Error>` - The updated transaction record or error
+#[allow(clippy::too_many_arguments)]
 pub async fn update_transaction(
     db: &DatabaseConnection,
     id: Uuid,
     account_id: Option<Uuid>,
-    amount: Option<f32>,
+    amount: Option<Decimal>,
     date: Option<DateTime>,
     ref_no: Option<String>,
     description: Option<String>,
     sequence_number: Option<i64>,
-) -> Result<transactions::Model, sea_orm::DbErr> {
+) -> Result<transactions::Model, Error> {
     let mut transaction: transactions::ActiveModel = transactions::Entity::find_by_id(id)
         .one(db)
         .await?
-        .ok_or(sea_orm::DbErr::RecordNotFound(
-            "error.transactions.update_transaction.not_found".to_string(),
+        .ok_or(Error::not_found(
+            "error.transactions.update_transaction.not_found",
+            Entity::Transaction,
         ))?
         .into();
 
@@ -171,8 +418,9 @@ pub async fn update_transaction(
         },
     )
     .await?
-    .ok_or(sea_orm::DbErr::RecordNotFound(
-        "error.transactions.update_transaction.not_found".to_string(),
+    .ok_or(Error::not_found(
+        "error.transactions.update_transaction.not_found",
+        Entity::Transaction,
     ))?;
 
     Ok(updated_transaction)
@@ -185,17 +433,15 @@ pub async fn update_transaction(
 /// * `id` - UUID of the transaction record to delete
 ///
 /// # Returns
-/// * `Result<DeleteResult, sea_orm::DbErr>` - The result of the delete operation or error
-pub async fn delete_transaction(
-    db: &DatabaseConnection,
-    id: Uuid,
-) -> Result<DeleteResult, sea_orm::DbErr> {
+/// * `Result<DeleteResult, Error>` - The result of the delete operation or error
+pub async fn delete_transaction(db: &DatabaseConnection, id: Uuid) -> Result<DeleteResult, Error> {
     let transaction = transactions::Entity::find_by_id(id)
         .one(db)
         .await?
         .ok_or_else(|| {
-            sea_orm::DbErr::RecordNotFound(
-                "error.transactions.delete_transaction_by_id.not_found".to_string(),
+            Error::not_found(
+                "error.transactions.delete_transaction_by_id.not_found",
+                Entity::Transaction,
             )
         })?;
 
@@ -210,7 +456,7 @@ pub async fn delete_transaction(
 pub(super) async fn txn_delete_transaction(
     txn: &DatabaseTransaction,
     transaction: &transactions::ActiveModel,
-) -> Result<DeleteResult, sea_orm::DbErr> {
+) -> Result<DeleteResult, Error> {
     let mut dependent_transactions = recalculate_balance(
         txn,
         transaction.account_id.clone().unwrap(),
@@ -237,11 +483,11 @@ pub(super) async fn txn_delete_transaction(
 /// * `options` - Query options including filters, sorting, and pagination
 ///
 /// # Returns
-/// * `Result<Vec<transactions::Model>, sea_orm::DbErr>` - List of transaction records or error
+/// * `Result<Vec<transactions::Model>, Error>` - List of transaction records or error
 pub async fn get_transactions(
     db: &DatabaseConnection,
     options: TransactionsQueryOptions,
-) -> Result<Vec<transactions::Model>, sea_orm::DbErr> {
+) -> Result<Vec<transactions::Model>, Error> {
     let query = build_query(options);
     let transactions_list = query.all(db).await?;
     Ok(transactions_list)
@@ -254,14 +500,14 @@ pub async fn get_transactions(
 /// * `options` - TransactionsQueryOptions struct containing filter parameters
 ///
 /// # Returns
-/// * `Result<Option<transactions::Model>, sea_orm::DbErr>` - The transaction record or error
+/// * `Result<Option<transactions::Model>, Error>` - The transaction record or error
 pub async fn get_transaction(
     db: &DatabaseConnection,
     options: TransactionsQueryOptions,
-) -> Result<Option<transactions::Model>, sea_orm::DbErr> {
+) -> Result<Option<transactions::Model>, Error> {
     if let Some(filter) = &options.filter {
         if let Some(id) = &filter.id {
-            return transactions::Entity::find_by_id(id.clone()).one(db).await;
+            return Ok(transactions::Entity::find_by_id(*id).one(db).await?);
         }
     }
 
@@ -289,6 +535,10 @@ fn build_query(options: TransactionsQueryOptions) -> Select<transactions::Entity
             query = query.filter(transactions::Column::AccountId.eq(account_id));
         }
 
+        if let Some(import_id) = filter.import_id {
+            query = query.filter(transactions::Column::ImportId.eq(import_id));
+        }
+
         query = apply_number_filter(
             query,
             filter.sequence_number,
@@ -320,8 +570,8 @@ async fn recalculate_balance(
     txn: &DatabaseTransaction,
     account_id: Uuid,
     sequence_number: i64,
-    mut current_balance: f32,
-) -> Result<Vec<transactions::ActiveModel>, sea_orm::DbErr> {
+    mut current_balance: Decimal,
+) -> Result<Vec<transactions::ActiveModel>, Error> {
     // Get all transactions with sequence number higher than the given transaction
     let query = build_query(TransactionsQueryOptions {
         filter: Some(TransactionFilter {
@@ -0,0 +1,117 @@
+use sea_orm::DeriveIden;
+use sea_orm_migration::{prelude::*, sea_orm::DbBackend};
+
+/// Money columns, which were created as single precision floats
+const COLUMNS: [(&str, &str); 4] = [
+    ("transactions", "amount"),
+    ("transactions", "balance"),
+    ("staged_transactions", "amount"),
+    ("staged_transactions", "balance"),
+];
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        match db.get_database_backend() {
+            DbBackend::Postgres => {
+                // Going through double precision keeps all the digits of the
+                // stored value, which are then rounded to whole paise/cents
+                for (table, column) in COLUMNS {
+                    db.execute_unprepared(&format!(
+                        "ALTER TABLE {table} ALTER COLUMN {column} TYPE numeric(19, 4) \
+                         USING round({column}::double precision::numeric, 2)"
+                    ))
+                    .await?;
+                }
+            }
+            DbBackend::MySql => {
+                for table in [
+                    Table::alter()
+                        .table(Transactions::Table)
+                        .modify_column(ColumnDef::new(Transactions::Amount).decimal_len(19, 4))
+                        .modify_column(ColumnDef::new(Transactions::Balance).decimal_len(19, 4))
+                        .to_owned(),
+                    Table::alter()
+                        .table(StagedTransactions::Table)
+                        .modify_column(
+                            ColumnDef::new(StagedTransactions::Amount).decimal_len(19, 4),
+                        )
+                        .modify_column(
+                            ColumnDef::new(StagedTransactions::Balance).decimal_len(19, 4),
+                        )
+                        .to_owned(),
+                ] {
+                    manager.alter_table(table).await?;
+                }
+            }
+            DbBackend::Sqlite => {
+                // SQLite cannot change the type of a column, and SeaORM
+                // reads its decimals through a double whatever the column
+                // type, so the amounts stay floating point numbers there.
+                // They keep four decimal places exact up to about a hundred
+                // billion. Only drop the float noise already stored.
+                for (table, column) in COLUMNS {
+                    db.execute_unprepared(&format!(
+                        "UPDATE {table} SET {column} = round({column}, 2)"
+                    ))
+                    .await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        match db.get_database_backend() {
+            DbBackend::Postgres => {
+                for (table, column) in COLUMNS {
+                    db.execute_unprepared(&format!(
+                        "ALTER TABLE {table} ALTER COLUMN {column} TYPE real"
+                    ))
+                    .await?;
+                }
+            }
+            DbBackend::MySql => {
+                for table in [
+                    Table::alter()
+                        .table(Transactions::Table)
+                        .modify_column(ColumnDef::new(Transactions::Amount).float())
+                        .modify_column(ColumnDef::new(Transactions::Balance).float())
+                        .to_owned(),
+                    Table::alter()
+                        .table(StagedTransactions::Table)
+                        .modify_column(ColumnDef::new(StagedTransactions::Amount).float())
+                        .modify_column(ColumnDef::new(StagedTransactions::Balance).float())
+                        .to_owned(),
+                ] {
+                    manager.alter_table(table).await?;
+                }
+            }
+            DbBackend::Sqlite => {}
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Transactions {
+    Table,
+    Amount,
+    Balance,
+}
+
+#[derive(DeriveIden)]
+enum StagedTransactions {
+    Table,
+    Amount,
+    Balance,
+}
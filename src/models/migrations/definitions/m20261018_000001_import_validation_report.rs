@@ -0,0 +1,42 @@
+use sea_orm::DeriveIden;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Checks run over the statement when it was imported, see
+        // `readers::validation`
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Imports::Table)
+                    .add_column(
+                        ColumnDef::new(Imports::ValidationReport)
+                            .json_binary()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Imports::Table)
+                    .drop_column(Imports::ValidationReport)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Imports {
+    Table,
+    ValidationReport,
+}
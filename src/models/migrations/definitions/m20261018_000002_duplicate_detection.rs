@@ -0,0 +1,147 @@
+use sea_orm::DeriveIden;
+use sea_orm_migration::{
+    prelude::extension::postgres::Type,
+    prelude::*,
+    sea_orm::{ActiveEnum, DbBackend, DeriveActiveEnum, EnumIter, Schema},
+};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let schema = manager.get_connection().get_database_backend();
+
+        match schema {
+            DbBackend::Postgres => {
+                manager
+                    .create_type(
+                        Schema::new(DbBackend::Postgres)
+                            .create_enum_from_active_enum::<MatchStatus>(),
+                    )
+                    .await?;
+            }
+            DbBackend::MySql | DbBackend::Sqlite => {}
+        }
+
+        // SHA-256 of the file a statement was read from, so the same file
+        // cannot be imported twice
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Imports::Table)
+                    .add_column(ColumnDef::new(Imports::SourceFileHash).string().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_import_source_file_hash")
+                    .table(Imports::Table)
+                    .col(Imports::SourceFileHash)
+                    .to_owned(),
+            )
+            .await?;
+
+        // How a staged transaction compares to the transactions already in
+        // the account. SQLite can only add one column per statement.
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(StagedTransactions::Table)
+                    .add_column(
+                        ColumnDef::new(StagedTransactions::MatchStatus)
+                            .custom(MatchStatus::name())
+                            .not_null()
+                            .default("new"),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(StagedTransactions::Table)
+                    .add_column(
+                        ColumnDef::new(StagedTransactions::MatchedTransactionId)
+                            .uuid()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [
+            StagedTransactions::MatchedTransactionId,
+            StagedTransactions::MatchStatus,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(StagedTransactions::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_import_source_file_hash")
+                    .table(Imports::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Imports::Table)
+                    .drop_column(Imports::SourceFileHash)
+                    .to_owned(),
+            )
+            .await?;
+
+        match manager.get_connection().get_database_backend() {
+            DbBackend::Postgres => {
+                manager
+                    .drop_type(Type::drop().name(MatchStatus::name()).to_owned())
+                    .await?;
+            }
+            DbBackend::MySql | DbBackend::Sqlite => {}
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, DeriveIden)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "match_status")]
+pub enum MatchStatus {
+    #[sea_orm(string_value = "new")]
+    New,
+    #[sea_orm(string_value = "duplicate")]
+    Duplicate,
+    #[sea_orm(string_value = "conflicting")]
+    Conflicting,
+}
+
+#[derive(DeriveIden)]
+enum Imports {
+    Table,
+    SourceFileHash,
+}
+
+#[derive(DeriveIden)]
+enum StagedTransactions {
+    Table,
+    MatchStatus,
+    MatchedTransactionId,
+}
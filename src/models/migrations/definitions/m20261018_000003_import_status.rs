@@ -0,0 +1,166 @@
+use sea_orm::DeriveIden;
+use sea_orm_migration::{
+    prelude::extension::postgres::Type,
+    prelude::*,
+    sea_orm::{ActiveEnum, DbBackend, DeriveActiveEnum, EnumIter, Schema},
+};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let backend = manager.get_connection().get_database_backend();
+
+        match backend {
+            DbBackend::Postgres => {
+                manager
+                    .create_type(
+                        Schema::new(DbBackend::Postgres)
+                            .create_enum_from_active_enum::<ImportStatus>(),
+                    )
+                    .await?;
+            }
+            DbBackend::MySql | DbBackend::Sqlite => {}
+        }
+
+        // Imports used to be deleted once approved, so the ones left are
+        // still waiting for review
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Imports::Table)
+                    .add_column(
+                        ColumnDef::new(Imports::Status)
+                            .custom(ImportStatus::name())
+                            .not_null()
+                            .default("pending"),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // The import a transaction was approved from, if any
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Transactions::Table)
+                    .add_column(ColumnDef::new(Transactions::ImportId).uuid().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_txn_import_id")
+                    .table(Transactions::Table)
+                    .col(Transactions::ImportId)
+                    .to_owned(),
+            )
+            .await?;
+
+        match backend {
+            DbBackend::Postgres | DbBackend::MySql => {
+                manager
+                    .create_foreign_key(
+                        ForeignKey::create()
+                            .name("fk_txn_import_id")
+                            .from(Transactions::Table, Transactions::ImportId)
+                            .to(Imports::Table, Imports::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade)
+                            .to_owned(),
+                    )
+                    .await?;
+            }
+            // SQLite cannot add a constraint to an existing table
+            DbBackend::Sqlite => {}
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let backend = manager.get_connection().get_database_backend();
+
+        match backend {
+            DbBackend::Postgres | DbBackend::MySql => {
+                manager
+                    .drop_foreign_key(
+                        ForeignKey::drop()
+                            .name("fk_txn_import_id")
+                            .table(Transactions::Table)
+                            .to_owned(),
+                    )
+                    .await?;
+            }
+            DbBackend::Sqlite => {}
+        }
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_txn_import_id")
+                    .table(Transactions::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Transactions::Table)
+                    .drop_column(Transactions::ImportId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Imports::Table)
+                    .drop_column(Imports::Status)
+                    .to_owned(),
+            )
+            .await?;
+
+        match backend {
+            DbBackend::Postgres => {
+                manager
+                    .drop_type(Type::drop().name(ImportStatus::name()).to_owned())
+                    .await?;
+            }
+            DbBackend::MySql | DbBackend::Sqlite => {}
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, DeriveIden)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "import_status")]
+pub enum ImportStatus {
+    #[sea_orm(string_value = "pending")]
+    Pending,
+    #[sea_orm(string_value = "approved")]
+    Approved,
+    #[sea_orm(string_value = "rejected")]
+    Rejected,
+    #[sea_orm(string_value = "partially_approved")]
+    PartiallyApproved,
+}
+
+#[derive(DeriveIden)]
+enum Imports {
+    Table,
+    Id,
+    Status,
+}
+
+#[derive(DeriveIden)]
+enum Transactions {
+    Table,
+    ImportId,
+}
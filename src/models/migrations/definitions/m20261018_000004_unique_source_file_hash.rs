@@ -0,0 +1,79 @@
+use sea_orm_migration::{prelude::*, sea_orm::DbBackend};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_import_source_file_hash")
+                    .table(Imports::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        // A file can only be imported once into an account, unless the
+        // import was rejected
+        match manager.get_connection().get_database_backend() {
+            DbBackend::Postgres | DbBackend::Sqlite => {
+                manager
+                    .get_connection()
+                    .execute_unprepared(
+                        r#"
+                        CREATE UNIQUE INDEX idx_import_source_file_hash
+                        ON imports (account_id, source_file_hash)
+                        WHERE status <> 'rejected'
+                        "#,
+                    )
+                    .await?;
+            }
+            // MySQL has no partial indexes, the check is left to the
+            // application
+            DbBackend::MySql => {
+                manager
+                    .create_index(
+                        Index::create()
+                            .name("idx_import_source_file_hash")
+                            .table(Imports::Table)
+                            .col(Imports::AccountId)
+                            .col(Imports::SourceFileHash)
+                            .to_owned(),
+                    )
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_import_source_file_hash")
+                    .table(Imports::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_import_source_file_hash")
+                    .table(Imports::Table)
+                    .col(Imports::SourceFileHash)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Imports {
+    Table,
+    AccountId,
+    SourceFileHash,
+}
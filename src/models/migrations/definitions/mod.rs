@@ -1 +1,6 @@
 pub mod m20220101_000001_init;
+pub mod m20261017_000001_decimal_amounts;
+pub mod m20261018_000001_import_validation_report;
+pub mod m20261018_000002_duplicate_detection;
+pub mod m20261018_000003_import_status;
+pub mod m20261018_000004_unique_source_file_hash;
@@ -7,6 +7,13 @@ pub struct Migrator;
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![Box::new(definitions::m20220101_000001_init::Migration)]
+        vec![
+            Box::new(definitions::m20220101_000001_init::Migration),
+            Box::new(definitions::m20261017_000001_decimal_amounts::Migration),
+            Box::new(definitions::m20261018_000001_import_validation_report::Migration),
+            Box::new(definitions::m20261018_000002_duplicate_detection::Migration),
+            Box::new(definitions::m20261018_000003_import_status::Migration),
+            Box::new(definitions::m20261018_000004_unique_source_file_hash::Migration),
+        ]
     }
 }
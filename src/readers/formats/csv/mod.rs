@@ -0,0 +1,131 @@
+use super::encoding::decode;
+use crate::error::{Error, Location};
+
+/// Delimiters we try, in order of preference when they score equally
+const DELIMITERS: [u8; 4] = [b',', b';', b'\t', b'|'];
+
+/// Number of lines looked at while detecting the delimiter and quote character
+const SAMPLE_LINES: usize = 50;
+
+/// Reads a CSV/TSV file into a table
+///
+/// CSV carries no magic bytes or dialect information, so the encoding,
+/// delimiter and quote character are all detected from the content.
+pub(crate) fn read_csv_content(file_content: Vec<u8>) -> Result<Vec<Vec<String>>, Error> {
+    let text = decode(&file_content);
+
+    if text
+        .chars()
+        .any(|c| c.is_control() && !matches!(c, '\t' | '\r' | '\n'))
+    {
+        return Err(Error::unsupported_format(
+            "error.reader.read_csv.not_a_text_file",
+        ));
+    }
+
+    let delimiter = detect_delimiter(&text).ok_or(Error::unsupported_format(
+        "error.reader.read_csv.delimiter_not_found",
+    ))?;
+    let quote = detect_quote(&text, delimiter);
+
+    let mut reader = ::csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .delimiter(delimiter)
+        .quote(quote)
+        .from_reader(text.as_bytes());
+
+    let mut data: Vec<Vec<String>> = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|error| {
+            let failure = Error::parse("error.reader.read_csv.cannot_read_record");
+            match error.position() {
+                Some(position) => failure.at(Location::Line(position.line() as usize)),
+                None => failure,
+            }
+            .with_reason(error)
+        })?;
+        data.push(record.iter().map(|cell| cell.to_string()).collect());
+    }
+
+    Ok(data)
+}
+
+/// Counts the delimiter on each sample line, ignoring anything inside quotes
+fn count_per_line(text: &str, delimiter: u8) -> Vec<usize> {
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .take(SAMPLE_LINES)
+        .map(|line| {
+            let mut in_quotes = false;
+            line.bytes()
+                .filter(|byte| {
+                    if *byte == b'"' {
+                        in_quotes = !in_quotes;
+                    }
+                    !in_quotes && *byte == delimiter
+                })
+                .count()
+        })
+        .collect()
+}
+
+/// Picks the delimiter that splits the most lines into the same number of cells
+fn detect_delimiter(text: &str) -> Option<u8> {
+    let mut best: Option<(u8, usize, usize)> = None;
+
+    for delimiter in DELIMITERS {
+        let counts = count_per_line(text, delimiter);
+
+        let mut frequency: Vec<(usize, usize)> = Vec::new();
+        for count in counts.into_iter().filter(|count| *count > 0) {
+            match frequency.iter_mut().find(|(value, _)| *value == count) {
+                Some((_, lines)) => *lines += 1,
+                None => frequency.push((count, 1)),
+            }
+        }
+
+        // Most common delimiter count, preferring the wider row on a tie
+        let Some((columns, lines)) = frequency
+            .into_iter()
+            .max_by_key(|(value, lines)| (*lines, *value))
+        else {
+            continue;
+        };
+
+        let is_better = match best {
+            Some((_, best_lines, best_columns)) => (lines, columns) > (best_lines, best_columns),
+            None => true,
+        };
+        if is_better {
+            best = Some((delimiter, lines, columns));
+        }
+    }
+
+    best.map(|(delimiter, _, _)| delimiter)
+}
+
+/// Picks the quote character that opens the most cells, defaulting to `"`
+fn detect_quote(text: &str, delimiter: u8) -> u8 {
+    let delimiter = delimiter as char;
+    let (mut double, mut single) = (0, 0);
+
+    for line in text.lines().take(SAMPLE_LINES) {
+        for cell in line.split(delimiter) {
+            match cell.trim_start().chars().next() {
+                Some('"') => double += 1,
+                Some('\'') => single += 1,
+                _ => {}
+            }
+        }
+    }
+
+    if single > double {
+        b'\''
+    } else {
+        b'"'
+    }
+}
+
+#[cfg(test)]
+mod tests;
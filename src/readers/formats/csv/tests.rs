@@ -0,0 +1,116 @@
+#[cfg(test)]
+use crate::readers::formats::csv::read_csv_content;
+
+#[test]
+fn test_read_comma_separated() {
+    let content =
+        b"Date,Narration,Amount\n01/01/2023,UPI-TEST,100.00\n02/01/2023,NEFT-TEST,200.00\n";
+
+    let table = read_csv_content(content.to_vec()).unwrap();
+
+    assert_eq!(
+        table,
+        vec![
+            vec!["Date", "Narration", "Amount"],
+            vec!["01/01/2023", "UPI-TEST", "100.00"],
+            vec!["02/01/2023", "NEFT-TEST", "200.00"],
+        ]
+    );
+}
+
+#[test]
+fn test_read_semicolon_separated_with_quoted_commas() {
+    let content = b"Date;Narration;Amount\r\n01/01/2023;\"SHOP, CITY\";1.000,50\r\n";
+
+    let table = read_csv_content(content.to_vec()).unwrap();
+
+    assert_eq!(
+        table,
+        vec![
+            vec!["Date", "Narration", "Amount"],
+            vec!["01/01/2023", "SHOP, CITY", "1.000,50"],
+        ]
+    );
+}
+
+#[test]
+fn test_read_tab_separated() {
+    let content = b"Date\tNarration\tAmount\n01/01/2023\tUPI, TEST\t100.00\n";
+
+    let table = read_csv_content(content.to_vec()).unwrap();
+
+    assert_eq!(table[1], vec!["01/01/2023", "UPI, TEST", "100.00"]);
+}
+
+#[test]
+fn test_read_single_quoted() {
+    let content = b"'Date','Narration'\n'01/01/2023','SHOP, CITY'\n";
+
+    let table = read_csv_content(content.to_vec()).unwrap();
+
+    assert_eq!(table[1], vec!["01/01/2023", "SHOP, CITY"]);
+}
+
+#[test]
+fn test_read_utf8_with_bom() {
+    let mut content = vec![0xEF, 0xBB, 0xBF];
+    content.extend_from_slice("Date,Narration\n01/01/2023,Café\n".as_bytes());
+
+    let table = read_csv_content(content).unwrap();
+
+    assert_eq!(table[0], vec!["Date", "Narration"]);
+    assert_eq!(table[1], vec!["01/01/2023", "Café"]);
+}
+
+#[test]
+fn test_read_windows_1252() {
+    let content = b"Date,Narration\n01/01/2023,Caf\xe9 \x80\n";
+
+    let table = read_csv_content(content.to_vec()).unwrap();
+
+    assert_eq!(table[1], vec!["01/01/2023", "Café €"]);
+}
+
+#[test]
+fn test_read_utf16_with_bom() {
+    let mut content = vec![0xFF, 0xFE];
+    for unit in "Date\tNarration\n01/01/2023\tCafé\n".encode_utf16() {
+        content.extend_from_slice(&unit.to_le_bytes());
+    }
+
+    let table = read_csv_content(content).unwrap();
+
+    assert_eq!(table[1], vec!["01/01/2023", "Café"]);
+}
+
+#[test]
+fn test_read_utf16_without_bom() {
+    let mut content = Vec::new();
+    for unit in "Date,Narration\n01/01/2023,UPI-TEST\n".encode_utf16() {
+        content.extend_from_slice(&unit.to_be_bytes());
+    }
+
+    let table = read_csv_content(content).unwrap();
+
+    assert_eq!(table[1], vec!["01/01/2023", "UPI-TEST"]);
+}
+
+#[test]
+fn test_read_binary_content() {
+    let content = vec![0x00, 0x01, 0x02, 0x03, 0xFF, 0x10];
+
+    assert_eq!(
+        read_csv_content(content).unwrap_err().key(),
+        "error.reader.read_csv.not_a_text_file"
+    );
+}
+
+#[test]
+fn test_read_without_delimiter() {
+    let content = b"just some text\nwithout any cells\n";
+
+    assert_eq!(
+        read_csv_content(content.to_vec()).unwrap_err().key(),
+        "error.reader.read_csv.delimiter_not_found"
+    );
+}
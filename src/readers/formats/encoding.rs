@@ -0,0 +1,40 @@
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8, WINDOWS_1252};
+
+/// Decodes the file to a string, honouring a BOM if there is one
+pub(crate) fn decode(file_content: &[u8]) -> String {
+    let encoding = match Encoding::for_bom(file_content) {
+        Some((encoding, _)) => encoding,
+        None => detect_encoding(file_content),
+    };
+
+    // `decode` sniffs and strips the BOM on its own
+    let (text, _, _) = encoding.decode(file_content);
+    text.into_owned()
+}
+
+fn detect_encoding(file_content: &[u8]) -> &'static Encoding {
+    // Mostly-ASCII UTF-16 without a BOM has every other byte set to zero.
+    // Those zeros are valid UTF-8 too, so this has to be checked first.
+    let half = file_content.len() / 2;
+    let even_zeros = file_content.iter().step_by(2).filter(|b| **b == 0).count();
+    let odd_zeros = file_content
+        .iter()
+        .skip(1)
+        .step_by(2)
+        .filter(|b| **b == 0)
+        .count();
+
+    if half > 0 && odd_zeros * 2 > half && even_zeros * 10 < odd_zeros {
+        return UTF_16LE;
+    }
+
+    if half > 0 && even_zeros * 2 > half && odd_zeros * 10 < even_zeros {
+        return UTF_16BE;
+    }
+
+    if std::str::from_utf8(file_content).is_ok() {
+        return UTF_8;
+    }
+
+    WINDOWS_1252
+}
@@ -1,38 +1,166 @@
+mod csv;
+mod encoding;
+mod mt940;
+mod ods;
+mod office_crypto;
+mod ofx;
 mod pdf;
+mod workbook;
 mod xls;
+mod xlsb;
+mod xlsx;
+mod xml;
 
-use crate::readers::types::{File, FileData, FileType};
+use crate::{
+    error::Error,
+    readers::{
+        secrets::Candidate,
+        types::{File, FileData, FileType},
+    },
+};
+use csv::read_csv_content;
+use mt940::{is_mt940_content, read_mt940_content};
+use ods::read_ods_content;
+use office_crypto::{is_office_encrypted, read_office_encrypted_content};
+use ofx::{is_ofx_content, read_ofx_content};
 use pdf::read_pdf_content;
 use xls::read_xls_content;
+use xlsb::read_xlsb_content;
+use xlsx::read_xlsx_content;
+use xml::read_xml_content;
 
-pub(super) fn load_file_content(file_path: &str) -> Result<Vec<u8>, String> {
-    std::fs::read(file_path)
-        .map_err(|_| "error.reader.formats.load_file_content.cannot_open_file".to_string())
+pub(super) fn load_file_content(file_path: &str) -> Result<Vec<u8>, Error> {
+    std::fs::read(file_path).map_err(|error| Error::Io {
+        key: "error.reader.formats.load_file_content.cannot_open_file",
+        reason: error.to_string(),
+    })
 }
 
-pub(super) fn read_file_content(file_content: Vec<u8>, file_secret: &str) -> Result<File, String> {
-    let file_type = infer::get(&file_content)
-        .map(|t| t.extension())
-        .or_else(|| None);
+/// Reads the file, decrypting it with the first candidate that unlocks it
+///
+/// The candidate is returned along with the file, or `None` when the file
+/// was not encrypted.
+pub(super) fn read_file_content(
+    file_content: Vec<u8>,
+    candidates: &[Candidate],
+) -> Result<(File, Option<Candidate>), Error> {
+    // Password protected workbooks are wrapped in a compound file, which
+    // `infer` would otherwise report as a legacy "xls" workbook
+    if is_office_encrypted(&file_content) {
+        let (package, candidate) = read_office_encrypted_content(file_content, candidates)?;
+        let (file, _) = read_file_content(package, &[])?;
+        return Ok((file, Some(candidate)));
+    }
+
+    let file_type = infer::get(&file_content).map(|t| t.extension());
 
-    if file_type == Some("xls") || file_type == Some("xlsx") {
+    if file_type == Some("xls") {
         let table_data = read_xls_content(file_content)?;
-        return Ok(File {
-            file_type: FileType::Xls,
-            data: FileData::Table(table_data),
-        });
+        return Ok((
+            File {
+                file_type: FileType::Xls,
+                data: FileData::Table(table_data),
+            },
+            None,
+        ));
+    }
+
+    // XLSX and XLSB workbooks share the same zip container, so `infer`
+    // reports both as "xlsx". Only the workbook part inside tells them apart.
+    if file_type == Some("xlsx") || file_type == Some("zip") {
+        return match read_xlsx_content(file_content.clone()) {
+            Ok(table_data) => Ok((
+                File {
+                    file_type: FileType::Xlsx,
+                    data: FileData::Table(table_data),
+                },
+                None,
+            )),
+            Err(xlsx_error) => {
+                let table_data = read_xlsb_content(file_content).map_err(|_| xlsx_error)?;
+                Ok((
+                    File {
+                        file_type: FileType::Xlsb,
+                        data: FileData::Table(table_data),
+                    },
+                    None,
+                ))
+            }
+        };
+    }
+
+    if file_type == Some("ods") {
+        let table_data = read_ods_content(file_content)?;
+        return Ok((
+            File {
+                file_type: FileType::Ods,
+                data: FileData::Table(table_data),
+            },
+            None,
+        ));
     }
 
     if file_type == Some("pdf") {
-        let text_data = read_pdf_content(file_content, file_secret)?;
-        return Ok(File {
-            file_type: FileType::Pdf,
-            data: FileData::Text(text_data),
-        });
+        let (layout_data, candidate) = read_pdf_content(file_content, candidates)?;
+        return Ok((
+            File {
+                file_type: FileType::Pdf,
+                data: FileData::Layout(layout_data),
+            },
+            candidate,
+        ));
+    }
+
+    // OFX 2.x is sniffed as XML, while OFX 1.x (SGML) is not recognised at all
+    if (file_type.is_none() || file_type == Some("xml")) && is_ofx_content(&file_content) {
+        let tree_data = read_ofx_content(file_content)?;
+        return Ok((
+            File {
+                file_type: FileType::Ofx,
+                data: FileData::Tree(tree_data),
+            },
+            None,
+        ));
+    }
+
+    if file_type == Some("xml") {
+        let tree_data = read_xml_content(file_content)?;
+        return Ok((
+            File {
+                file_type: FileType::Xml,
+                data: FileData::Tree(tree_data),
+            },
+            None,
+        ));
+    }
+
+    if file_type.is_none() && is_mt940_content(&file_content) {
+        let text_data = read_mt940_content(file_content)?;
+        return Ok((
+            File {
+                file_type: FileType::Mt940,
+                data: FileData::Text(text_data),
+            },
+            None,
+        ));
+    }
+
+    // Plain text has no magic bytes, so anything `infer` could not
+    // recognise is given a chance as a delimited text file
+    if file_type.is_none() {
+        if let Ok(table_data) = read_csv_content(file_content) {
+            return Ok((
+                File {
+                    file_type: FileType::Csv,
+                    data: FileData::Table(table_data),
+                },
+                None,
+            ));
+        }
     }
 
-    Err(format!(
-        "error.reader.read_file.unsupported_file_type: {:?}",
-        file_type
-    ))
+    Err(
+        Error::unsupported_format("error.reader.read_file.unsupported_file_type")
+            .with_reason(file_type.unwrap_or("unknown")),
+    )
 }
@@ -0,0 +1,16 @@
+use super::encoding::decode;
+use crate::error::Error;
+
+/// Whether the file looks like a SWIFT MT940 statement
+pub(crate) fn is_mt940_content(file_content: &[u8]) -> bool {
+    let prefix = decode(&file_content[..file_content.len().min(4096)]);
+
+    prefix.contains(":20:")
+        && prefix.contains(":25:")
+        && (prefix.contains(":60F:") || prefix.contains(":60M:"))
+}
+
+/// Reads an MT940 file as text, with line endings normalised to `\n`
+pub(crate) fn read_mt940_content(file_content: Vec<u8>) -> Result<String, Error> {
+    Ok(decode(&file_content).replace("\r\n", "\n"))
+}
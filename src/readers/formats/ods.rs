@@ -0,0 +1,7 @@
+use super::workbook::read_workbook_content;
+use crate::error::Error;
+use calamine::Ods;
+
+pub(crate) fn read_ods_content(file_content: Vec<u8>) -> Result<Vec<Vec<String>>, Error> {
+    read_workbook_content::<Ods<_>>(file_content, "error.reader.read_ods.cannot_open_file")
+}
@@ -0,0 +1,440 @@
+//! Decryption of password protected Office documents (MS-OFFCRYPTO)
+//!
+//! Encrypted workbooks are stored as a compound file holding an
+//! `EncryptionInfo` stream, describing how the key is derived from the
+//! password, and an `EncryptedPackage` stream with the encrypted workbook.
+//! Both the agile encryption of Office 2010 and later and the standard
+//! encryption of Office 2007 are supported.
+use std::io::{Cursor, Read};
+
+use aes::{
+    cipher::{generic_array::GenericArray, BlockDecrypt, KeyInit},
+    Aes128, Aes192, Aes256,
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+use crate::{
+    error::Error,
+    readers::{formats::xml::read_xml_content, secrets::Candidate, types::Node},
+};
+
+const COMPOUND_FILE_SIGNATURE: [u8; 8] = [0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1];
+
+/// Block keys used by agile encryption to derive its different keys
+const VERIFIER_INPUT_BLOCK_KEY: [u8; 8] = [0xfe, 0xa7, 0xd2, 0x76, 0x3b, 0x4b, 0x9e, 0x79];
+const VERIFIER_VALUE_BLOCK_KEY: [u8; 8] = [0xd7, 0xaa, 0x0f, 0x6d, 0x30, 0x61, 0x34, 0x4e];
+const KEY_VALUE_BLOCK_KEY: [u8; 8] = [0x14, 0x6e, 0x0b, 0xe7, 0xab, 0xac, 0xd0, 0xd6];
+
+/// Agile encryption encrypts the package in segments of this size
+const SEGMENT_LENGTH: usize = 4096;
+
+/// Spin count of the standard encryption key derivation
+const STANDARD_SPIN_COUNT: u32 = 50000;
+
+pub(crate) fn is_office_encrypted(file_content: &[u8]) -> bool {
+    if !file_content.starts_with(&COMPOUND_FILE_SIGNATURE) {
+        return false;
+    }
+
+    cfb::CompoundFile::open(Cursor::new(file_content))
+        .map(|compound_file| {
+            compound_file.is_stream("/EncryptionInfo")
+                && compound_file.is_stream("/EncryptedPackage")
+        })
+        .unwrap_or(false)
+}
+
+/// Decrypts the package with the first candidate that unlocks it
+pub(crate) fn read_office_encrypted_content(
+    file_content: Vec<u8>,
+    candidates: &[Candidate],
+) -> Result<(Vec<u8>, Candidate), Error> {
+    let mut compound_file =
+        cfb::CompoundFile::open(Cursor::new(file_content)).map_err(|error| {
+            Error::parse("error.reader.read_office_crypto.cannot_open_file").with_reason(error)
+        })?;
+
+    let encryption_info = read_stream(&mut compound_file, "/EncryptionInfo")?;
+    let encrypted_package = read_stream(&mut compound_file, "/EncryptedPackage")?;
+    let encryption = Encryption::parse(&encryption_info)?;
+
+    for candidate in candidates {
+        if let Some(key) = encryption.key(&candidate.password) {
+            let package = encryption.decrypt(&key, &encrypted_package)?;
+            return Ok((package, candidate.clone()));
+        }
+    }
+
+    Err(Error::decryption(
+        "error.reader.read_office_crypto.cannot_decrypt_file",
+    ))
+}
+
+fn read_stream(
+    compound_file: &mut cfb::CompoundFile<Cursor<Vec<u8>>>,
+    path: &str,
+) -> Result<Vec<u8>, Error> {
+    let mut content = Vec::new();
+    compound_file
+        .open_stream(path)
+        .and_then(|mut stream| stream.read_to_end(&mut content))
+        .map_err(|error| {
+            Error::parse("error.reader.read_office_crypto.cannot_read_stream").with_reason(error)
+        })?;
+    Ok(content)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum HashAlgorithm {
+    Sha1,
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl HashAlgorithm {
+    fn from_name(name: &str) -> Result<Self, Error> {
+        match name {
+            "SHA1" | "SHA-1" => Ok(HashAlgorithm::Sha1),
+            "SHA256" => Ok(HashAlgorithm::Sha256),
+            "SHA384" => Ok(HashAlgorithm::Sha384),
+            "SHA512" => Ok(HashAlgorithm::Sha512),
+            name => Err(Error::unsupported_format(
+                "error.reader.read_office_crypto.unsupported_hash_algorithm",
+            )
+            .with_reason(name)),
+        }
+    }
+
+    fn digest(&self, parts: &[&[u8]]) -> Vec<u8> {
+        fn digest_with<D: Digest>(parts: &[&[u8]]) -> Vec<u8> {
+            let mut hasher = D::new();
+            for part in parts {
+                hasher.update(part);
+            }
+            hasher.finalize().to_vec()
+        }
+
+        match self {
+            HashAlgorithm::Sha1 => digest_with::<Sha1>(parts),
+            HashAlgorithm::Sha256 => digest_with::<Sha256>(parts),
+            HashAlgorithm::Sha384 => digest_with::<Sha384>(parts),
+            HashAlgorithm::Sha512 => digest_with::<Sha512>(parts),
+        }
+    }
+}
+
+/// Parameters of a key derived from the password with agile encryption
+#[derive(Debug)]
+struct PasswordKeyEncryptor {
+    hash_algorithm: HashAlgorithm,
+    salt: Vec<u8>,
+    spin_count: u32,
+    key_bits: usize,
+    block_size: usize,
+    hash_size: usize,
+    encrypted_verifier_hash_input: Vec<u8>,
+    encrypted_verifier_hash_value: Vec<u8>,
+    encrypted_key_value: Vec<u8>,
+}
+
+#[derive(Debug)]
+enum Encryption {
+    Agile {
+        hash_algorithm: HashAlgorithm,
+        salt: Vec<u8>,
+        block_size: usize,
+        password: PasswordKeyEncryptor,
+    },
+    Standard {
+        key_bits: usize,
+        salt: Vec<u8>,
+        encrypted_verifier: Vec<u8>,
+        encrypted_verifier_hash: Vec<u8>,
+    },
+}
+
+impl Encryption {
+    fn parse(encryption_info: &[u8]) -> Result<Self, Error> {
+        let invalid =
+            || Error::decryption("error.reader.read_office_crypto.invalid_encryption_info");
+        let version = (
+            read_u16(encryption_info, 0).ok_or_else(invalid)?,
+            read_u16(encryption_info, 2).ok_or_else(invalid)?,
+        );
+
+        match version {
+            (4, 4) => Self::parse_agile(encryption_info.get(8..).ok_or_else(invalid)?),
+            (2..=4, 2) => Self::parse_standard(encryption_info).ok_or_else(invalid),
+            (major, minor) => Err(Error::unsupported_format(
+                "error.reader.read_office_crypto.unsupported_encryption",
+            )
+            .with_reason(format!("{}.{}", major, minor))),
+        }
+    }
+
+    fn parse_agile(descriptor: &[u8]) -> Result<Self, Error> {
+        let invalid =
+            || Error::decryption("error.reader.read_office_crypto.invalid_encryption_info");
+        let descriptor = read_xml_content(descriptor.to_vec())?;
+
+        let key_data = descriptor.child("keyData").ok_or_else(invalid)?;
+        let encrypted_key = descriptor
+            .child("keyEncryptors")
+            .into_iter()
+            .flat_map(|encryptors| encryptors.children_named("keyEncryptor"))
+            .filter(|encryptor| {
+                encryptor
+                    .attribute("uri")
+                    .is_some_and(|uri| uri.ends_with("keyEncryptor/password"))
+            })
+            .find_map(|encryptor| encryptor.child("encryptedKey"))
+            .ok_or_else(invalid)?;
+
+        for node in [key_data, encrypted_key] {
+            let cipher = node.attribute("cipherAlgorithm").unwrap_or_default();
+            let chaining = node.attribute("cipherChaining").unwrap_or_default();
+            if cipher != "AES" || chaining != "ChainingModeCBC" {
+                return Err(Error::unsupported_format(
+                    "error.reader.read_office_crypto.unsupported_cipher",
+                )
+                .with_reason(format!("{} {}", cipher, chaining)));
+            }
+        }
+
+        let number = |node: &Node, name: &str| {
+            node.attribute(name)
+                .and_then(|value| value.parse::<u32>().ok())
+                .ok_or_else(invalid)
+        };
+        let bytes = |node: &Node, name: &str| {
+            node.attribute(name)
+                .and_then(|value| BASE64.decode(value).ok())
+                .ok_or_else(invalid)
+        };
+        let hash_algorithm = |node: &Node| {
+            HashAlgorithm::from_name(node.attribute("hashAlgorithm").unwrap_or_default())
+        };
+
+        Ok(Encryption::Agile {
+            hash_algorithm: hash_algorithm(key_data)?,
+            salt: bytes(key_data, "saltValue")?,
+            block_size: number(key_data, "blockSize")? as usize,
+            password: PasswordKeyEncryptor {
+                hash_algorithm: hash_algorithm(encrypted_key)?,
+                salt: bytes(encrypted_key, "saltValue")?,
+                spin_count: number(encrypted_key, "spinCount")?,
+                key_bits: number(encrypted_key, "keyBits")? as usize,
+                block_size: number(encrypted_key, "blockSize")? as usize,
+                hash_size: number(encrypted_key, "hashSize")? as usize,
+                encrypted_verifier_hash_input: bytes(encrypted_key, "encryptedVerifierHashInput")?,
+                encrypted_verifier_hash_value: bytes(encrypted_key, "encryptedVerifierHashValue")?,
+                encrypted_key_value: bytes(encrypted_key, "encryptedKeyValue")?,
+            },
+        })
+    }
+
+    fn parse_standard(encryption_info: &[u8]) -> Option<Self> {
+        let header_size = read_u32(encryption_info, 8)? as usize;
+        let header = encryption_info.get(12..12 + header_size)?;
+        let verifier = encryption_info.get(12 + header_size..)?;
+
+        // Only AES is supported, not the RC4 of older CryptoAPI encryption
+        let algorithm = read_u32(header, 8)?;
+        if !(0x660E..=0x6610).contains(&algorithm) {
+            return None;
+        }
+
+        let salt_size = read_u32(verifier, 0)? as usize;
+        let salt = verifier.get(4..4 + salt_size)?;
+        let encrypted_verifier = verifier.get(4 + salt_size..20 + salt_size)?;
+        let encrypted_verifier_hash = verifier.get(24 + salt_size..56 + salt_size)?;
+
+        Some(Encryption::Standard {
+            key_bits: read_u32(header, 16)? as usize,
+            salt: salt.to_vec(),
+            encrypted_verifier: encrypted_verifier.to_vec(),
+            encrypted_verifier_hash: encrypted_verifier_hash.to_vec(),
+        })
+    }
+
+    /// Derives the key of the package, if the password is the right one
+    fn key(&self, password: &str) -> Option<Vec<u8>> {
+        let password: Vec<u8> = password
+            .encode_utf16()
+            .flat_map(|unit| unit.to_le_bytes())
+            .collect();
+
+        match self {
+            Encryption::Agile {
+                password: encryptor,
+                ..
+            } => {
+                let hash = encryptor.hash_algorithm;
+                let mut hashed = hash.digest(&[&encryptor.salt, &password]);
+                for iteration in 0..encryptor.spin_count {
+                    hashed = hash.digest(&[&iteration.to_le_bytes(), &hashed]);
+                }
+
+                let decrypt = |block_key: &[u8], data: &[u8]| {
+                    let key = fit(hash.digest(&[&hashed, block_key]), encryptor.key_bits / 8);
+                    let iv = fit(encryptor.salt.clone(), encryptor.block_size);
+                    decrypt_cbc(&key, &iv, data)
+                };
+
+                let verifier_input = decrypt(
+                    &VERIFIER_INPUT_BLOCK_KEY,
+                    &encryptor.encrypted_verifier_hash_input,
+                )?;
+                let verifier_hash = decrypt(
+                    &VERIFIER_VALUE_BLOCK_KEY,
+                    &encryptor.encrypted_verifier_hash_value,
+                )?;
+                let expected_hash = hash.digest(&[verifier_input.get(..encryptor.salt.len())?]);
+                if verifier_hash.get(..encryptor.hash_size)?
+                    != expected_hash.get(..encryptor.hash_size)?
+                {
+                    return None;
+                }
+
+                let key = decrypt(&KEY_VALUE_BLOCK_KEY, &encryptor.encrypted_key_value)?;
+                Some(key.get(..encryptor.key_bits / 8)?.to_vec())
+            }
+            Encryption::Standard {
+                key_bits,
+                salt,
+                encrypted_verifier,
+                encrypted_verifier_hash,
+            } => {
+                let hash = HashAlgorithm::Sha1;
+                let mut hashed = hash.digest(&[salt, &password]);
+                for iteration in 0..STANDARD_SPIN_COUNT {
+                    hashed = hash.digest(&[&iteration.to_le_bytes(), &hashed]);
+                }
+                let hashed = hash.digest(&[&hashed, &0u32.to_le_bytes()]);
+
+                let derive = |fill: u8| {
+                    let mut buffer = [fill; 64];
+                    for (byte, hashed) in buffer.iter_mut().zip(&hashed) {
+                        *byte ^= hashed;
+                    }
+                    hash.digest(&[&buffer])
+                };
+                let key = [derive(0x36), derive(0x5c)].concat();
+                let key = key.get(..key_bits / 8)?.to_vec();
+
+                let verifier = decrypt_ecb(&key, encrypted_verifier)?;
+                let verifier_hash = decrypt_ecb(&key, encrypted_verifier_hash)?;
+                if hash.digest(&[&verifier]) != verifier_hash.get(..20)? {
+                    return None;
+                }
+
+                Some(key)
+            }
+        }
+    }
+
+    fn decrypt(&self, key: &[u8], encrypted_package: &[u8]) -> Result<Vec<u8>, Error> {
+        let invalid =
+            || Error::decryption("error.reader.read_office_crypto.invalid_encrypted_package");
+        let size = encrypted_package
+            .get(..8)
+            .and_then(|size| size.try_into().ok())
+            .map(u64::from_le_bytes)
+            .ok_or_else(invalid)? as usize;
+        let data = &encrypted_package[8..];
+
+        let mut package = match self {
+            Encryption::Agile {
+                hash_algorithm,
+                salt,
+                block_size,
+                ..
+            } => {
+                let mut package = Vec::with_capacity(data.len());
+                for (index, segment) in (0u32..).zip(data.chunks(SEGMENT_LENGTH)) {
+                    let iv = fit(
+                        hash_algorithm.digest(&[salt, &index.to_le_bytes()]),
+                        *block_size,
+                    );
+                    package.extend(decrypt_cbc(key, &iv, segment).ok_or_else(invalid)?);
+                }
+                package
+            }
+            Encryption::Standard { .. } => decrypt_ecb(key, data).ok_or_else(invalid)?,
+        };
+
+        if package.len() < size {
+            return Err(invalid());
+        }
+        package.truncate(size);
+
+        Ok(package)
+    }
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    data.get(offset..offset + 2)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(u16::from_le_bytes)
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset + 4)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(u32::from_le_bytes)
+}
+
+/// Truncates, or pads with `0x36`, to the given length
+fn fit(mut bytes: Vec<u8>, length: usize) -> Vec<u8> {
+    bytes.resize(length, 0x36);
+    bytes
+}
+
+/// Decrypts every whole 16 byte block with AES, chaining each with the
+/// previous ciphertext block when an IV is given
+fn decrypt_aes(key: &[u8], iv: Option<&[u8]>, data: &[u8]) -> Option<Vec<u8>> {
+    fn decrypt_with<C: BlockDecrypt + KeyInit>(
+        key: &[u8],
+        iv: Option<&[u8]>,
+        data: &[u8],
+    ) -> Option<Vec<u8>> {
+        let cipher = C::new_from_slice(key).ok()?;
+        let mut previous = iv.map(|iv| iv.to_vec());
+        let mut plain = Vec::with_capacity(data.len());
+
+        for block in data.chunks_exact(16) {
+            let mut decrypted = GenericArray::clone_from_slice(block);
+            cipher.decrypt_block(&mut decrypted);
+            if let Some(previous) = &mut previous {
+                for (byte, chained) in decrypted.iter_mut().zip(previous.iter()) {
+                    *byte ^= chained;
+                }
+                *previous = block.to_vec();
+            }
+            plain.extend_from_slice(&decrypted);
+        }
+
+        Some(plain)
+    }
+
+    match key.len() {
+        16 => decrypt_with::<Aes128>(key, iv, data),
+        24 => decrypt_with::<Aes192>(key, iv, data),
+        32 => decrypt_with::<Aes256>(key, iv, data),
+        _ => None,
+    }
+}
+
+fn decrypt_cbc(key: &[u8], iv: &[u8], data: &[u8]) -> Option<Vec<u8>> {
+    decrypt_aes(key, Some(iv), data)
+}
+
+fn decrypt_ecb(key: &[u8], data: &[u8]) -> Option<Vec<u8>> {
+    decrypt_aes(key, None, data)
+}
+
+#[cfg(test)]
+mod tests;
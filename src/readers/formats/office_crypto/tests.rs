@@ -0,0 +1,243 @@
+#[cfg(test)]
+use crate::error::Error;
+#[cfg(test)]
+use crate::readers::{
+    formats::{office_crypto::is_office_encrypted, read_file_content},
+    get_statement_from_file_content_with_secrets,
+    secrets::{Candidate, Keyring},
+    types::{FileData, FileType},
+};
+#[cfg(test)]
+use aes::{
+    cipher::{generic_array::GenericArray, BlockEncrypt, KeyInit},
+    Aes128, Aes256,
+};
+#[cfg(test)]
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+#[cfg(test)]
+use sha1::Sha1;
+#[cfg(test)]
+use sha2::{Digest, Sha512};
+#[cfg(test)]
+use std::io::{Cursor, Write};
+
+#[cfg(test)]
+const _PAYLOAD: &str = "DETAILED STATEMENT\nAccount Type : Savings\n,S No.,Value Date,Transaction Date,Cheque Number,Transaction Remarks,Withdrawal Amount (INR ),Deposit Amount (INR ),Balance (INR )\n,1,01/01/2023,01/01/2023,-,UPI/123456789012/Payment,100.00,0.00,900.00\n";
+
+#[cfg(test)]
+fn _candidate(password: &str) -> Candidate {
+    Candidate {
+        institution: None,
+        label: password.to_string(),
+        password: password.to_string(),
+    }
+}
+
+#[cfg(test)]
+fn _utf16(password: &str) -> Vec<u8> {
+    password
+        .encode_utf16()
+        .flat_map(|unit| unit.to_le_bytes())
+        .collect()
+}
+
+/// Encrypts with AES, in CBC mode when an IV is given and ECB otherwise,
+/// padding the data with zeros to whole blocks
+#[cfg(test)]
+fn _encrypt<C: BlockEncrypt + KeyInit>(key: &[u8], iv: Option<&[u8]>, data: &[u8]) -> Vec<u8> {
+    let cipher = C::new_from_slice(key).unwrap();
+    let mut data = data.to_vec();
+    data.resize(data.len().div_ceil(16) * 16, 0);
+
+    let mut previous = iv.map(|iv| iv.to_vec());
+    let mut encrypted = Vec::new();
+    for block in data.chunks(16) {
+        let mut block = GenericArray::clone_from_slice(block);
+        if let Some(previous) = &previous {
+            for (byte, chained) in block.iter_mut().zip(previous) {
+                *byte ^= chained;
+            }
+        }
+        cipher.encrypt_block(&mut block);
+        if let Some(previous) = &mut previous {
+            *previous = block.to_vec();
+        }
+        encrypted.extend_from_slice(&block);
+    }
+    encrypted
+}
+
+#[cfg(test)]
+fn _compound_file(encryption_info: &[u8], encrypted_package: &[u8]) -> Vec<u8> {
+    let mut compound_file = cfb::CompoundFile::create(Cursor::new(Vec::new())).unwrap();
+    compound_file
+        .create_stream("/EncryptionInfo")
+        .unwrap()
+        .write_all(encryption_info)
+        .unwrap();
+    compound_file
+        .create_stream("/EncryptedPackage")
+        .unwrap()
+        .write_all(encrypted_package)
+        .unwrap();
+    compound_file.flush().unwrap();
+    compound_file.into_inner().into_inner()
+}
+
+/// Encrypts the payload with agile encryption (SHA-512, AES-256)
+#[cfg(test)]
+fn _agile_encrypted(payload: &[u8], password: &str) -> Vec<u8> {
+    let spin_count = 1000u32;
+    let key_data_salt = [0x11u8; 16];
+    let password_salt = [0x22u8; 16];
+    let secret_key = [0x33u8; 32];
+    let verifier_input = [0x44u8; 16];
+
+    let mut hashed = Sha512::digest([&password_salt[..], &_utf16(password)].concat()).to_vec();
+    for iteration in 0..spin_count {
+        hashed = Sha512::digest([&iteration.to_le_bytes()[..], &hashed].concat()).to_vec();
+    }
+    let encrypt_with = |block_key: &[u8], data: &[u8]| {
+        let key = Sha512::digest([&hashed[..], block_key].concat());
+        _encrypt::<Aes256>(&key[..32], Some(&password_salt), data)
+    };
+
+    let verifier_hash = Sha512::digest(verifier_input);
+    let encrypted_input = encrypt_with(
+        &[0xfe, 0xa7, 0xd2, 0x76, 0x3b, 0x4b, 0x9e, 0x79],
+        &verifier_input,
+    );
+    let encrypted_hash = encrypt_with(
+        &[0xd7, 0xaa, 0x0f, 0x6d, 0x30, 0x61, 0x34, 0x4e],
+        &verifier_hash,
+    );
+    let encrypted_key = encrypt_with(
+        &[0x14, 0x6e, 0x0b, 0xe7, 0xab, 0xac, 0xd0, 0xd6],
+        &secret_key,
+    );
+
+    let descriptor = format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<encryption xmlns="http://schemas.microsoft.com/office/2006/encryption" xmlns:p="http://schemas.microsoft.com/office/2006/keyEncryptor/password">
+<keyData saltSize="16" blockSize="16" keyBits="256" hashSize="64" cipherAlgorithm="AES" cipherChaining="ChainingModeCBC" hashAlgorithm="SHA512" saltValue="{}"/>
+<keyEncryptors><keyEncryptor uri="http://schemas.microsoft.com/office/2006/keyEncryptor/password">
+<p:encryptedKey spinCount="{}" saltSize="16" blockSize="16" keyBits="256" hashSize="64" cipherAlgorithm="AES" cipherChaining="ChainingModeCBC" hashAlgorithm="SHA512" saltValue="{}" encryptedVerifierHashInput="{}" encryptedVerifierHashValue="{}" encryptedKeyValue="{}"/>
+</keyEncryptor></keyEncryptors>
+</encryption>"#,
+        BASE64.encode(key_data_salt),
+        spin_count,
+        BASE64.encode(password_salt),
+        BASE64.encode(encrypted_input),
+        BASE64.encode(encrypted_hash),
+        BASE64.encode(encrypted_key),
+    );
+    let encryption_info = [&[4u8, 0, 4, 0, 0x40, 0, 0, 0][..], descriptor.as_bytes()].concat();
+
+    let mut encrypted_package = (payload.len() as u64).to_le_bytes().to_vec();
+    for (index, segment) in (0u32..).zip(payload.chunks(4096)) {
+        let iv = Sha512::digest([&key_data_salt[..], &index.to_le_bytes()].concat());
+        encrypted_package.extend(_encrypt::<Aes256>(&secret_key, Some(&iv[..16]), segment));
+    }
+
+    _compound_file(&encryption_info, &encrypted_package)
+}
+
+/// Encrypts the payload with standard encryption (SHA-1, AES-128)
+#[cfg(test)]
+fn _standard_encrypted(payload: &[u8], password: &str) -> Vec<u8> {
+    let salt = [0x55u8; 16];
+    let verifier = [0x66u8; 16];
+
+    let mut hashed = Sha1::digest([&salt[..], &_utf16(password)].concat()).to_vec();
+    for iteration in 0..50000u32 {
+        hashed = Sha1::digest([&iteration.to_le_bytes()[..], &hashed].concat()).to_vec();
+    }
+    let hashed = Sha1::digest([&hashed[..], &0u32.to_le_bytes()].concat());
+    let mut buffer = [0x36u8; 64];
+    for (byte, hashed) in buffer.iter_mut().zip(hashed) {
+        *byte ^= hashed;
+    }
+    let key = Sha1::digest(buffer)[..16].to_vec();
+
+    let mut header = Vec::new();
+    for field in [0x24u32, 0, 0x660E, 0x8004, 128, 0x18, 0, 0] {
+        header.extend(field.to_le_bytes());
+    }
+    let mut encryption_info = vec![3u8, 0, 2, 0, 0x24, 0, 0, 0];
+    encryption_info.extend((header.len() as u32).to_le_bytes());
+    encryption_info.extend(&header);
+    encryption_info.extend(16u32.to_le_bytes());
+    encryption_info.extend(salt);
+    encryption_info.extend(_encrypt::<Aes128>(&key, None, &verifier));
+    encryption_info.extend(20u32.to_le_bytes());
+    encryption_info.extend(_encrypt::<Aes128>(&key, None, &Sha1::digest(verifier)));
+
+    let mut encrypted_package = (payload.len() as u64).to_le_bytes().to_vec();
+    encrypted_package.extend(_encrypt::<Aes128>(&key, None, payload));
+
+    _compound_file(&encryption_info, &encrypted_package)
+}
+
+#[test]
+fn test_read_agile_encrypted_file() {
+    let file_content = _agile_encrypted(_PAYLOAD.as_bytes(), "secret");
+    assert!(is_office_encrypted(&file_content));
+
+    let (file, candidate) =
+        read_file_content(file_content, &[_candidate("wrong"), _candidate("secret")]).unwrap();
+
+    assert!(matches!(file.file_type, FileType::Csv));
+    assert_eq!(candidate, Some(_candidate("secret")));
+    match file.data {
+        FileData::Table(table) => assert_eq!(table[3][5], "UPI/123456789012/Payment"),
+        _ => panic!("expected a table"),
+    }
+}
+
+#[test]
+fn test_read_standard_encrypted_file() {
+    let file_content = _standard_encrypted(_PAYLOAD.as_bytes(), "secret");
+
+    let (file, candidate) = read_file_content(file_content, &[_candidate("secret")]).unwrap();
+
+    assert!(matches!(file.file_type, FileType::Csv));
+    assert_eq!(candidate, Some(_candidate("secret")));
+}
+
+#[test]
+fn test_read_with_wrong_passwords() {
+    let file_content = _agile_encrypted(_PAYLOAD.as_bytes(), "secret");
+
+    let error = read_file_content(file_content, &[_candidate("wrong")])
+        .map(|_| ())
+        .unwrap_err();
+
+    assert!(matches!(error, Error::Decryption { .. }));
+    assert_eq!(
+        error.key(),
+        "error.reader.read_office_crypto.cannot_decrypt_file"
+    );
+}
+
+#[test]
+fn test_parse_with_keyring() {
+    let file_content = _agile_encrypted(_PAYLOAD.as_bytes(), "TEST0703");
+    let mut keyring = Keyring::new();
+    keyring
+        .set_value("name", "Tester")
+        .set_value("dob", "07/03/1990")
+        .add_password(None, "secret")
+        .add_template(None, "{name_first4_upper}{dob_ddmm}");
+
+    let (statement, candidate) =
+        get_statement_from_file_content_with_secrets(file_content, &keyring).unwrap();
+
+    assert_eq!(statement.transactions.len(), 1);
+    assert_eq!(candidate.unwrap().label, "{name_first4_upper}{dob_ddmm}");
+}
+
+#[test]
+fn test_identify_unencrypted_content() {
+    assert!(!is_office_encrypted(_PAYLOAD.as_bytes()));
+    assert!(!is_office_encrypted(&_compound_file(b"", b"")[..16]));
+}
@@ -0,0 +1,129 @@
+use super::encoding::decode;
+use crate::{error::Error, readers::types::Node};
+
+/// Whether the file looks like OFX/QFX, either the SGML (1.x) or XML (2.x) flavour
+pub(crate) fn is_ofx_content(file_content: &[u8]) -> bool {
+    let prefix = &file_content[..file_content.len().min(4096)];
+    let text = decode(prefix);
+    let text = text.trim_start();
+
+    text.starts_with("OFXHEADER") || text.contains("<?OFX") || text.contains("<OFX>")
+}
+
+/// Reads an OFX/QFX file into a tree of elements, rooted at the `OFX` element
+///
+/// OFX 1.x is SGML, where elements holding a value are never closed
+/// (`<TRNAMT>-50.00`), while OFX 2.x is well-formed XML. Both are read with
+/// the same rule: an element that already has text is closed by the next tag.
+pub(crate) fn read_ofx_content(file_content: Vec<u8>) -> Result<Node, Error> {
+    let text = decode(&file_content);
+
+    // Everything before the OFX element is header (key:value pairs for
+    // 1.x, processing instructions for 2.x)
+    let start = text
+        .find("<OFX>")
+        .ok_or(Error::parse("error.reader.read_ofx.ofx_element_not_found"))?;
+
+    let mut stack: Vec<Node> = vec![Node::default()];
+    let mut rest = &text[start..];
+
+    while let Some(open) = rest.find('<') {
+        push_text(&mut stack, &rest[..open]);
+
+        let close = rest[open..]
+            .find('>')
+            .ok_or(Error::parse("error.reader.read_ofx.unterminated_tag"))?
+            + open;
+        let tag = rest[open + 1..close].trim();
+        rest = &rest[close + 1..];
+
+        if tag.starts_with('?') || tag.starts_with('!') {
+            continue;
+        }
+
+        if let Some(name) = tag.strip_prefix('/') {
+            let name = name.trim();
+            // Closing an aggregate also closes the value elements inside it.
+            // Closing tags without a matching open element are ignored.
+            if stack.iter().skip(1).any(|node| node.name == name) {
+                loop {
+                    let closed = close_element(&mut stack);
+                    if closed == name {
+                        break;
+                    }
+                }
+            }
+            continue;
+        }
+
+        if stack.len() > 1 && !stack[stack.len() - 1].text.is_empty() {
+            close_element(&mut stack);
+        }
+
+        let (tag, self_closing) = match tag.strip_suffix('/') {
+            Some(tag) => (tag, true),
+            None => (tag, false),
+        };
+        let name = tag
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .to_string();
+
+        stack.push(Node {
+            name,
+            ..Default::default()
+        });
+        if self_closing {
+            close_element(&mut stack);
+        }
+    }
+    push_text(&mut stack, rest);
+
+    while stack.len() > 1 {
+        close_element(&mut stack);
+    }
+
+    stack
+        .pop()
+        .and_then(|document| {
+            document
+                .children
+                .into_iter()
+                .find(|node| node.name == "OFX")
+        })
+        .ok_or(Error::parse("error.reader.read_ofx.ofx_element_not_found"))
+}
+
+fn push_text(stack: &mut [Node], text: &str) {
+    let text = text.trim();
+    if text.is_empty() {
+        return;
+    }
+
+    if let Some(node) = stack.last_mut() {
+        node.text.push_str(&unescape(text));
+    }
+}
+
+/// Pops the innermost open element into its parent, returning its name
+fn close_element(stack: &mut Vec<Node>) -> String {
+    let node = stack.pop().unwrap_or_default();
+    let name = node.name.clone();
+    if let Some(parent) = stack.last_mut() {
+        parent.children.push(node);
+    }
+    name
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests;
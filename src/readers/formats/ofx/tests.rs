@@ -0,0 +1,85 @@
+#[cfg(test)]
+use crate::readers::formats::ofx::{is_ofx_content, read_ofx_content};
+
+#[cfg(test)]
+const SGML: &str = "OFXHEADER:100\r\nDATA:OFXSGML\r\nVERSION:102\r\nCHARSET:1252\r\n\r\n<OFX>\r\n<SIGNONMSGSRSV1><SONRS><STATUS><CODE>0<SEVERITY>INFO</STATUS><DTSERVER>20230131120000</SONRS></SIGNONMSGSRSV1>\r\n<BANKMSGSRSV1><STMTTRNRS><STMTRS><CURDEF>INR\r\n<BANKACCTFROM><BANKID>HDFC0000001<ACCTID>123456789<ACCTTYPE>SAVINGS</BANKACCTFROM>\r\n<BANKTRANLIST><DTSTART>20230101<DTEND>20230131\r\n<STMTTRN><TRNTYPE>DEBIT<DTPOSTED>20230102<TRNAMT>-50.00<FITID>1001<NAME>Tom &amp; Jerry</STMTTRN>\r\n</BANKTRANLIST>\r\n<LEDGERBAL><BALAMT>950.00<DTASOF>20230131</LEDGERBAL>\r\n</STMTRS></STMTTRNRS></BANKMSGSRSV1>\r\n</OFX>\r\n";
+
+#[test]
+fn test_is_ofx_content() {
+    assert!(is_ofx_content(SGML.as_bytes()));
+    assert!(is_ofx_content(
+        b"<?xml version=\"1.0\"?><?OFX OFXHEADER=\"200\" VERSION=\"211\"?><OFX></OFX>"
+    ));
+    assert!(!is_ofx_content(b"Date,Narration\n01/01/2023,OFX\n"));
+}
+
+#[test]
+fn test_read_sgml() {
+    let ofx = read_ofx_content(SGML.as_bytes().to_vec()).unwrap();
+
+    assert_eq!(ofx.name, "OFX");
+    assert_eq!(
+        ofx.text_at(&["SIGNONMSGSRSV1", "SONRS", "STATUS", "CODE"]),
+        Some("0")
+    );
+
+    let statement = ofx.path(&["BANKMSGSRSV1", "STMTTRNRS", "STMTRS"]).unwrap();
+    assert_eq!(statement.text_at(&["CURDEF"]), Some("INR"));
+    assert_eq!(
+        statement.text_at(&["BANKACCTFROM", "ACCTID"]),
+        Some("123456789")
+    );
+    assert_eq!(
+        statement.text_at(&["BANKACCTFROM", "ACCTTYPE"]),
+        Some("SAVINGS")
+    );
+    assert_eq!(
+        statement.text_at(&["BANKTRANLIST", "DTEND"]),
+        Some("20230131")
+    );
+    assert_eq!(statement.text_at(&["LEDGERBAL", "BALAMT"]), Some("950.00"));
+
+    let transactions: Vec<_> = statement
+        .child("BANKTRANLIST")
+        .unwrap()
+        .children_named("STMTTRN")
+        .collect();
+    assert_eq!(transactions.len(), 1);
+    assert_eq!(transactions[0].text_at(&["TRNAMT"]), Some("-50.00"));
+    assert_eq!(transactions[0].text_at(&["NAME"]), Some("Tom & Jerry"));
+}
+
+#[test]
+fn test_read_xml() {
+    let content = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<?OFX OFXHEADER=\"200\" VERSION=\"211\" SECURITY=\"NONE\"?>\n<OFX>\n  <CREDITCARDMSGSRSV1>\n    <CCSTMTTRNRS>\n      <CCSTMTRS>\n        <CCACCTFROM><ACCTID>4111111111111111</ACCTID></CCACCTFROM>\n        <BANKTRANLIST>\n          <STMTTRN><TRNAMT>-10.50</TRNAMT><MEMO/><NAME>Coffee</NAME></STMTTRN>\n          <STMTTRN><TRNAMT>20.00</TRNAMT><NAME>Refund</NAME></STMTTRN>\n        </BANKTRANLIST>\n      </CCSTMTRS>\n    </CCSTMTTRNRS>\n  </CREDITCARDMSGSRSV1>\n</OFX>\n";
+
+    let ofx = read_ofx_content(content.as_bytes().to_vec()).unwrap();
+
+    let statement = ofx
+        .path(&["CREDITCARDMSGSRSV1", "CCSTMTTRNRS", "CCSTMTRS"])
+        .unwrap();
+    assert_eq!(
+        statement.text_at(&["CCACCTFROM", "ACCTID"]),
+        Some("4111111111111111")
+    );
+
+    let transactions: Vec<_> = statement
+        .child("BANKTRANLIST")
+        .unwrap()
+        .children_named("STMTTRN")
+        .collect();
+    assert_eq!(transactions.len(), 2);
+    assert_eq!(transactions[0].text_at(&["MEMO"]), None);
+    assert_eq!(transactions[0].text_at(&["NAME"]), Some("Coffee"));
+    assert_eq!(transactions[1].text_at(&["TRNAMT"]), Some("20.00"));
+}
+
+#[test]
+fn test_read_without_ofx_element() {
+    assert_eq!(
+        read_ofx_content(b"OFXHEADER:100\r\n\r\n".to_vec())
+            .unwrap_err()
+            .key(),
+        "error.reader.read_ofx.ofx_element_not_found"
+    );
+}
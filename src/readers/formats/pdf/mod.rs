@@ -0,0 +1,564 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    rc::Rc,
+};
+
+use lopdf::{
+    content::{Content, Operation},
+    Dictionary, Document, Encoding, Object, ObjectId, Stream,
+};
+
+use crate::{
+    error::Error,
+    readers::{
+        secrets::Candidate,
+        types::{Layout, TextRun},
+    },
+};
+
+/// Glyph width used when a font does not describe its own, in text space units
+const DEFAULT_WIDTH: f32 = 0.5;
+
+/// How deep forms drawn from within forms are followed, against cycles
+const MAX_FORM_DEPTH: usize = 8;
+
+/// Reads the PDF, decrypting it with the first candidate that unlocks it
+pub(crate) fn read_pdf_content(
+    file_content: Vec<u8>,
+    candidates: &[Candidate],
+) -> Result<(Layout, Option<Candidate>), Error> {
+    let mut pdf = Document::load_from(&file_content[..]).map_err(|error| {
+        Error::parse("error.reader.formats.read_pdf_content.cannot_open_file").with_reason(error)
+    })?;
+
+    // A wrong password is rejected before anything is decrypted, so every
+    // candidate can be tried on the same document
+    let mut unlocked_with = None;
+    if pdf.is_encrypted() {
+        let mut last_error = None;
+        for candidate in candidates {
+            match pdf.decrypt(&candidate.password) {
+                Ok(()) => {
+                    unlocked_with = Some(candidate.clone());
+                    break;
+                }
+                Err(error) => last_error = Some(error),
+            }
+        }
+
+        if unlocked_with.is_none() {
+            let error =
+                Error::decryption("error.reader.formats.read_pdf_content.cannot_decrypt_file");
+            return Err(match last_error {
+                Some(reason) => error.with_reason(reason),
+                None => error,
+            });
+        }
+    }
+
+    let mut layout = Layout::default();
+
+    for (page_number, page_id) in pdf.get_pages() {
+        let page_text = pdf.extract_text(&[page_number]);
+        match &page_text {
+            Ok(text) => layout.text.push_str(text),
+            Err(error) => tracing::info!(
+                "info.reader.formats.read_pdf_content.cannot_extract_text: {}",
+                error
+            ),
+        }
+
+        match read_page_runs(&pdf, page_number, page_id) {
+            Ok(runs) => layout.runs.extend(runs),
+            Err(error) => tracing::info!(
+                "info.reader.formats.read_pdf_content.cannot_extract_runs: {}",
+                error
+            ),
+        }
+    }
+
+    Ok((layout, unlocked_with))
+}
+
+/// How the codes of a font are decoded and how far each glyph advances
+struct Font<'a> {
+    encoding: Encoding<'a>,
+    code_length: usize,
+    widths: HashMap<u32, f32>,
+    default_width: f32,
+}
+
+impl<'a> Font<'a> {
+    fn load(pdf: &'a Document, font: &'a Dictionary) -> Result<Self, lopdf::Error> {
+        let encoding = font.get_font_encoding(pdf)?;
+        let code_length = match encoding {
+            Encoding::UnicodeMapEncoding(_) => 2,
+            _ => 1,
+        };
+
+        let mut widths = HashMap::new();
+        let mut default_width = DEFAULT_WIDTH;
+
+        let descendant = font
+            .get_deref(b"DescendantFonts", pdf)
+            .and_then(Object::as_array)
+            .ok()
+            .and_then(|fonts| fonts.first())
+            .and_then(|font| pdf.dereference(font).ok())
+            .and_then(|(_, font)| font.as_dict().ok());
+
+        if let Some(descendant) = descendant {
+            // CID fonts list widths as `first [w1 w2 ...]` or `first last w`
+            if let Ok(width) = descendant.get(b"DW").and_then(Object::as_float) {
+                default_width = width / 1000.0;
+            }
+            let entries = descendant
+                .get_deref(b"W", pdf)
+                .and_then(Object::as_array)
+                .map(|entries| entries.as_slice())
+                .unwrap_or_default();
+            let mut index = 0;
+            while index + 1 < entries.len() {
+                let first = entries[index].as_i64().unwrap_or_default() as u32;
+                match pdf.dereference(&entries[index + 1]) {
+                    Ok((_, Object::Array(list))) => {
+                        for (offset, width) in list.iter().enumerate() {
+                            let width = width.as_float().unwrap_or_default();
+                            widths.insert(first + offset as u32, width / 1000.0);
+                        }
+                        index += 2;
+                    }
+                    _ => {
+                        let last = entries[index + 1].as_i64().unwrap_or_default() as u32;
+                        let width = entries
+                            .get(index + 2)
+                            .and_then(|width| width.as_float().ok())
+                            .unwrap_or_default();
+                        for code in first..=last {
+                            widths.insert(code, width / 1000.0);
+                        }
+                        index += 3;
+                    }
+                }
+            }
+        } else {
+            let first_char = font
+                .get(b"FirstChar")
+                .and_then(Object::as_i64)
+                .unwrap_or_default() as u32;
+            if let Ok(list) = font.get_deref(b"Widths", pdf).and_then(Object::as_array) {
+                for (offset, width) in list.iter().enumerate() {
+                    let width = width.as_float().unwrap_or_default();
+                    widths.insert(first_char + offset as u32, width / 1000.0);
+                }
+            }
+            if let Ok(width) = font
+                .get_deref(b"FontDescriptor", pdf)
+                .and_then(Object::as_dict)
+                .and_then(|descriptor| descriptor.get(b"MissingWidth"))
+                .and_then(Object::as_float)
+            {
+                default_width = width / 1000.0;
+            }
+        }
+
+        Ok(Font {
+            encoding,
+            code_length,
+            widths,
+            default_width,
+        })
+    }
+
+    fn codes(&self, bytes: &[u8]) -> Vec<u32> {
+        bytes
+            .chunks(self.code_length)
+            .map(|chunk| chunk.iter().fold(0, |code, byte| code * 256 + *byte as u32))
+            .collect()
+    }
+
+    fn width(&self, code: u32) -> f32 {
+        self.widths
+            .get(&code)
+            .copied()
+            .filter(|width| *width > 0.0)
+            .unwrap_or(self.default_width)
+    }
+}
+
+/// The fonts and form XObjects that a content stream refers to by name
+#[derive(Clone, Default)]
+struct Resources<'a> {
+    fonts: BTreeMap<Vec<u8>, Rc<Font<'a>>>,
+    forms: BTreeMap<Vec<u8>, &'a Stream>,
+}
+
+impl<'a> Resources<'a> {
+    /// Reads the resources of a page, including those it inherits
+    fn of_page(pdf: &'a Document, page_id: ObjectId) -> Result<Self, lopdf::Error> {
+        let (own, inherited) = pdf.get_page_resources(page_id)?;
+
+        let mut resources = Resources::default();
+        for dictionary in own.into_iter().chain(
+            inherited
+                .into_iter()
+                .filter_map(|id| pdf.get_dictionary(id).ok()),
+        ) {
+            resources.add(pdf, dictionary);
+        }
+        Ok(resources)
+    }
+
+    /// Reads the resources of a form, which falls back on those of the
+    /// content drawing it when it has none of its own
+    fn of_form(pdf: &'a Document, form: &'a Stream, parent: &Self) -> Self {
+        match form
+            .dict
+            .get_deref(b"Resources", pdf)
+            .and_then(Object::as_dict)
+        {
+            Ok(dictionary) => {
+                let mut resources = Resources::default();
+                resources.add(pdf, dictionary);
+                resources
+            }
+            Err(_) => parent.clone(),
+        }
+    }
+
+    /// Adds the entries of a resource dictionary, keeping those already read
+    fn add(&mut self, pdf: &'a Document, dictionary: &'a Dictionary) {
+        let entries = |key: &[u8]| {
+            dictionary
+                .get_deref(key, pdf)
+                .and_then(Object::as_dict)
+                .into_iter()
+                .flat_map(|entries| entries.iter())
+                .filter_map(|(name, object)| {
+                    pdf.dereference(object)
+                        .ok()
+                        .map(|(_, object)| (name, object))
+                })
+        };
+
+        for (name, font) in entries(b"Font") {
+            if self.fonts.contains_key(name) {
+                continue;
+            }
+            if let Some(font) = font
+                .as_dict()
+                .ok()
+                .and_then(|font| Font::load(pdf, font).ok())
+            {
+                self.fonts.insert(name.clone(), Rc::new(font));
+            }
+        }
+
+        for (name, xobject) in entries(b"XObject") {
+            if let Ok(form) = xobject.as_stream() {
+                let subtype = form.dict.get(b"Subtype").and_then(Object::as_name);
+                if subtype.ok() == Some(b"Form".as_slice()) {
+                    self.forms.entry(name.clone()).or_insert(form);
+                }
+            }
+        }
+    }
+}
+
+/// An affine transformation `[a b c d e f]`, as used by PDF content streams
+#[derive(Clone, Copy)]
+struct Matrix([f32; 6]);
+
+impl Matrix {
+    const IDENTITY: Matrix = Matrix([1.0, 0.0, 0.0, 1.0, 0.0, 0.0]);
+
+    fn translation(x: f32, y: f32) -> Matrix {
+        Matrix([1.0, 0.0, 0.0, 1.0, x, y])
+    }
+
+    fn from_operands(operands: &[Object]) -> Option<Matrix> {
+        let values: Vec<f32> = operands
+            .iter()
+            .filter_map(|operand| operand.as_float().ok())
+            .collect();
+        <[f32; 6]>::try_from(values).ok().map(Matrix)
+    }
+
+    /// Applies `self` first, then `other`
+    fn then(&self, other: &Matrix) -> Matrix {
+        let [a, b, c, d, e, f] = self.0;
+        let [a2, b2, c2, d2, e2, f2] = other.0;
+        Matrix([
+            a * a2 + b * c2,
+            a * b2 + b * d2,
+            c * a2 + d * c2,
+            c * b2 + d * d2,
+            e * a2 + f * c2 + e2,
+            e * b2 + f * d2 + f2,
+        ])
+    }
+}
+
+/// The parts of the graphics state that decide where text lands, which
+/// `q` saves and `Q` restores, text state parameters included
+#[derive(Clone)]
+struct GraphicsState<'a> {
+    ctm: Matrix,
+    font: Option<Rc<Font<'a>>>,
+    font_size: f32,
+    character_spacing: f32,
+    word_spacing: f32,
+    horizontal_scaling: f32,
+    leading: f32,
+    rise: f32,
+}
+
+impl Default for GraphicsState<'_> {
+    fn default() -> Self {
+        GraphicsState {
+            ctm: Matrix::IDENTITY,
+            font: None,
+            font_size: 0.0,
+            character_spacing: 0.0,
+            word_spacing: 0.0,
+            horizontal_scaling: 1.0,
+            leading: 0.0,
+            rise: 0.0,
+        }
+    }
+}
+
+/// The graphics state with the text and line matrices, which only live
+/// within a text object and are not saved by `q`
+struct State<'a> {
+    graphics: GraphicsState<'a>,
+    saved: Vec<GraphicsState<'a>>,
+    text_matrix: Matrix,
+    line_matrix: Matrix,
+}
+
+impl<'a> State<'a> {
+    fn new_line(&mut self, x: f32, y: f32) {
+        self.line_matrix = Matrix::translation(x, y).then(&self.line_matrix);
+        self.text_matrix = self.line_matrix;
+    }
+
+    /// Moves the text position by `amount`, given in unscaled text space units
+    fn advance(&mut self, amount: f32) {
+        self.text_matrix = Matrix::translation(amount * self.graphics.horizontal_scaling, 0.0)
+            .then(&self.text_matrix);
+    }
+
+    /// Where the text position is on the page, raised by the text rise
+    fn position(&self) -> Matrix {
+        Matrix::translation(0.0, self.graphics.rise)
+            .then(&self.text_matrix)
+            .then(&self.graphics.ctm)
+    }
+
+    /// Lays out the operands of a text showing operator as a single run
+    fn show(&mut self, page: u32, operands: &[Object]) -> Option<TextRun> {
+        let font = self.graphics.font.clone()?;
+        let start = self.position();
+
+        let mut text = String::new();
+        for operand in operands {
+            let items = match operand {
+                Object::Array(items) => items.as_slice(),
+                operand => std::slice::from_ref(operand),
+            };
+
+            for item in items {
+                match item {
+                    Object::String(bytes, _) => {
+                        if let Ok(decoded) = Document::decode_text(&font.encoding, bytes) {
+                            text.push_str(&decoded);
+                        }
+                        for code in font.codes(bytes) {
+                            let mut advance = font.width(code) * self.graphics.font_size
+                                + self.graphics.character_spacing;
+                            if font.code_length == 1 && code == 32 {
+                                advance += self.graphics.word_spacing;
+                            }
+                            self.advance(advance);
+                        }
+                    }
+                    Object::Integer(_) | Object::Real(_) => {
+                        let adjustment = item.as_float().unwrap_or_default();
+                        // Large negative adjustments stand in for spaces
+                        if adjustment < -100.0 && !text.ends_with(' ') {
+                            text.push(' ');
+                        }
+                        self.advance(-adjustment / 1000.0 * self.graphics.font_size);
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let end = self.position();
+        let [_, _, c, d, x, y] = start.0;
+        let text = text.trim().to_string();
+        if text.is_empty() {
+            return None;
+        }
+
+        Some(TextRun {
+            page,
+            x,
+            y,
+            width: end.0[4] - x,
+            font_size: self.graphics.font_size * (c * c + d * d).sqrt(),
+            text,
+        })
+    }
+}
+
+fn read_page_runs(
+    pdf: &Document,
+    page_number: u32,
+    page_id: ObjectId,
+) -> Result<Vec<TextRun>, lopdf::Error> {
+    let resources = Resources::of_page(pdf, page_id)?;
+    let content = pdf.get_and_decode_page_content(page_id)?;
+
+    let mut state = State {
+        graphics: GraphicsState::default(),
+        saved: Vec::new(),
+        text_matrix: Matrix::IDENTITY,
+        line_matrix: Matrix::IDENTITY,
+    };
+
+    let mut runs = Vec::new();
+    read_content_runs(
+        pdf,
+        page_number,
+        &content.operations,
+        &resources,
+        &mut state,
+        0,
+        &mut runs,
+    );
+    Ok(runs)
+}
+
+/// Lays out the text of a content stream, following the forms it draws
+fn read_content_runs<'a>(
+    pdf: &'a Document,
+    page_number: u32,
+    operations: &[Operation],
+    resources: &Resources<'a>,
+    state: &mut State<'a>,
+    depth: usize,
+    runs: &mut Vec<TextRun>,
+) {
+    for Operation { operator, operands } in operations {
+        let number = |index: usize| {
+            operands
+                .get(index)
+                .and_then(|operand| operand.as_float().ok())
+                .unwrap_or_default()
+        };
+
+        match operator.as_str() {
+            "q" => state.saved.push(state.graphics.clone()),
+            "Q" => state.graphics = state.saved.pop().unwrap_or_default(),
+            "cm" => {
+                if let Some(matrix) = Matrix::from_operands(operands) {
+                    state.graphics.ctm = matrix.then(&state.graphics.ctm);
+                }
+            }
+            "Do" => {
+                let form = operands
+                    .first()
+                    .and_then(|name| name.as_name().ok())
+                    .and_then(|name| resources.forms.get(name));
+                let Some(form) = form.filter(|_| depth < MAX_FORM_DEPTH) else {
+                    continue;
+                };
+                let content = match form
+                    .get_plain_content()
+                    .and_then(|content| Content::decode(&content))
+                {
+                    Ok(content) => content,
+                    Err(error) => {
+                        tracing::info!(
+                            "info.reader.formats.read_content_runs.cannot_decode_form: {}",
+                            error
+                        );
+                        continue;
+                    }
+                };
+
+                // A form is drawn as if wrapped in `q` and `Q`, with its own
+                // matrix put in front of the current transformation
+                let graphics = state.graphics.clone();
+                let saved = state.saved.len();
+                if let Some(matrix) = form
+                    .dict
+                    .get(b"Matrix")
+                    .and_then(Object::as_array)
+                    .ok()
+                    .and_then(|matrix| Matrix::from_operands(matrix))
+                {
+                    state.graphics.ctm = matrix.then(&state.graphics.ctm);
+                }
+                read_content_runs(
+                    pdf,
+                    page_number,
+                    &content.operations,
+                    &Resources::of_form(pdf, form, resources),
+                    state,
+                    depth + 1,
+                    runs,
+                );
+                state.saved.truncate(saved);
+                state.graphics = graphics;
+            }
+            "BT" => {
+                state.text_matrix = Matrix::IDENTITY;
+                state.line_matrix = Matrix::IDENTITY;
+            }
+            "Tf" => {
+                state.graphics.font = operands
+                    .first()
+                    .and_then(|name| name.as_name().ok())
+                    .and_then(|name| resources.fonts.get(name))
+                    .cloned();
+                state.graphics.font_size = number(1);
+            }
+            "Tc" => state.graphics.character_spacing = number(0),
+            "Tw" => state.graphics.word_spacing = number(0),
+            "Tz" => state.graphics.horizontal_scaling = number(0) / 100.0,
+            "TL" => state.graphics.leading = number(0),
+            "Ts" => state.graphics.rise = number(0),
+            "Td" => state.new_line(number(0), number(1)),
+            "TD" => {
+                state.graphics.leading = -number(1);
+                state.new_line(number(0), number(1));
+            }
+            "Tm" => {
+                if let Some(matrix) = Matrix::from_operands(operands) {
+                    state.line_matrix = matrix;
+                    state.text_matrix = matrix;
+                }
+            }
+            "T*" => state.new_line(0.0, -state.graphics.leading),
+            "Tj" | "TJ" => runs.extend(state.show(page_number, operands)),
+            "'" => {
+                state.new_line(0.0, -state.graphics.leading);
+                runs.extend(state.show(page_number, operands));
+            }
+            "\"" => {
+                state.graphics.word_spacing = number(0);
+                state.graphics.character_spacing = number(1);
+                state.new_line(0.0, -state.graphics.leading);
+                runs.extend(state.show(page_number, &operands[operands.len().min(2)..]));
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,279 @@
+#[cfg(test)]
+use crate::readers::formats::pdf::read_pdf_content;
+#[cfg(test)]
+use lopdf::{
+    content::{Content, Operation},
+    dictionary, Document, Object, Stream,
+};
+
+/// Builds a single page PDF, drawing text with a monospaced font whose
+/// glyphs are all 600 units wide
+#[cfg(test)]
+fn _pdf_with(operations: Vec<Operation>) -> Vec<u8> {
+    let mut operations = operations;
+    operations.insert(0, Operation::new("BT", vec![]));
+    operations.insert(1, Operation::new("Tf", vec!["F1".into(), 10.into()]));
+    operations.push(Operation::new("ET", vec![]));
+    _pdf_drawing(operations, vec![])
+}
+
+/// Builds a single page PDF from the page's content and that of a form
+/// named `Fm1`, which uses the same font under the name `F2`
+#[cfg(test)]
+fn _pdf_drawing(operations: Vec<Operation>, form_operations: Vec<Operation>) -> Vec<u8> {
+    let mut pdf = Document::with_version("1.5");
+    let pages_id = pdf.new_object_id();
+    let font_id = pdf.add_object(dictionary! {
+        "Type" => "Font",
+        "Subtype" => "Type1",
+        "BaseFont" => "Courier",
+        "Encoding" => "WinAnsiEncoding",
+        "FirstChar" => 32,
+        "Widths" => vec![Object::Integer(600); 95],
+    });
+    let form_id = pdf.add_object(Stream::new(
+        dictionary! {
+            "Type" => "XObject",
+            "Subtype" => "Form",
+            "BBox" => vec![0.into(), 0.into(), 595.into(), 842.into()],
+            "Matrix" => vec![1.into(), 0.into(), 0.into(), 1.into(), 0.into(), 50.into()],
+            "Resources" => dictionary! {
+                "Font" => dictionary! { "F2" => font_id },
+            },
+        },
+        Content {
+            operations: form_operations,
+        }
+        .encode()
+        .unwrap(),
+    ));
+    let resources_id = pdf.add_object(dictionary! {
+        "Font" => dictionary! { "F1" => font_id },
+        "XObject" => dictionary! { "Fm1" => form_id },
+    });
+
+    let content = Content { operations };
+    let content_id = pdf.add_object(Stream::new(dictionary! {}, content.encode().unwrap()));
+
+    let page_id = pdf.add_object(dictionary! {
+        "Type" => "Page",
+        "Parent" => pages_id,
+        "Contents" => content_id,
+        "Resources" => resources_id,
+        "MediaBox" => vec![0.into(), 0.into(), 595.into(), 842.into()],
+    });
+    pdf.objects.insert(
+        pages_id,
+        Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => vec![page_id.into()],
+            "Count" => 1,
+        }),
+    );
+    let catalog_id = pdf.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id,
+    });
+    pdf.trailer.set("Root", catalog_id);
+
+    let mut file_content = Vec::new();
+    pdf.save_to(&mut file_content).unwrap();
+    file_content
+}
+
+#[cfg(test)]
+fn _text_at(x: i64, y: i64, text: &str) -> Vec<Operation> {
+    vec![
+        Operation::new(
+            "Tm",
+            vec![1.into(), 0.into(), 0.into(), 1.into(), x.into(), y.into()],
+        ),
+        Operation::new("Tj", vec![Object::string_literal(text)]),
+    ]
+}
+
+#[cfg(test)]
+fn _statement_pdf() -> Vec<u8> {
+    _pdf_with(
+        [
+            _text_at(50, 700, "Date"),
+            _text_at(120, 700, "Narration"),
+            _text_at(300, 700, "Amount"),
+            _text_at(50, 685, "01/01/2023"),
+            _text_at(120, 685, "UPI"),
+            _text_at(145, 686, "PAYMENT"),
+            // Right aligned, so it starts left of its heading
+            _text_at(292, 685, "1,000.00"),
+        ]
+        .concat(),
+    )
+}
+
+#[test]
+fn test_read_text_runs() {
+    let layout = read_pdf_content(_statement_pdf(), &[]).unwrap().0;
+
+    assert_eq!(layout.runs.len(), 7);
+
+    let run = &layout.runs[0];
+    assert_eq!(run.page, 1);
+    assert_eq!(run.text, "Date");
+    assert_eq!(run.x, 50.0);
+    assert_eq!(run.y, 700.0);
+    assert_eq!(run.width, 24.0);
+    assert_eq!(run.font_size, 10.0);
+
+    assert!(layout.text.contains("Narration"));
+}
+
+#[test]
+fn test_read_rows_and_cells() {
+    let layout = read_pdf_content(_statement_pdf(), &[]).unwrap().0;
+    let rows = layout.rows();
+
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].cells(&[]), vec!["Date Narration Amount"]);
+    assert_eq!(rows[1].cells(&[]), vec!["01/01/2023 UPI PAYMENT 1,000.00"]);
+
+    let boundaries = rows[0]
+        .boundaries(&["Date", "Narration", "Amount"])
+        .unwrap();
+    assert_eq!(
+        rows[1].cells(&boundaries),
+        vec!["01/01/2023", "UPI PAYMENT", "1,000.00"]
+    );
+    assert_eq!(rows[0].boundaries(&["Date", "Balance"]), None);
+}
+
+#[test]
+fn test_read_positioned_operators() {
+    let layout = read_pdf_content(
+        _pdf_with(vec![
+            // Text drawn at twice the size through the text matrix
+            Operation::new(
+                "Tm",
+                vec![
+                    2.into(),
+                    0.into(),
+                    0.into(),
+                    2.into(),
+                    100.into(),
+                    500.into(),
+                ],
+            ),
+            Operation::new(
+                "TJ",
+                vec![Object::Array(vec![
+                    Object::string_literal("Withdrawal"),
+                    Object::Integer(-500),
+                    Object::string_literal("Amt."),
+                ])],
+            ),
+            Operation::new("TL", vec![12.into()]),
+            Operation::new("T*", vec![]),
+            Operation::new("Tj", vec![Object::string_literal("Next line")]),
+        ]),
+        &[],
+    )
+    .unwrap()
+    .0;
+
+    assert_eq!(layout.runs.len(), 2);
+    assert_eq!(layout.runs[0].text, "Withdrawal Amt.");
+    assert_eq!(layout.runs[0].font_size, 20.0);
+    assert_eq!(layout.runs[0].width, 14.0 * 12.0 + 10.0);
+    assert_eq!(layout.runs[1].x, 100.0);
+    assert_eq!(layout.runs[1].y, 476.0);
+}
+
+#[test]
+fn test_restore_saved_text_state() {
+    let layout = read_pdf_content(
+        _pdf_with(
+            [
+                vec![
+                    Operation::new("q", vec![]),
+                    Operation::new("Tf", vec!["F1".into(), 20.into()]),
+                    Operation::new("Tc", vec![5.into()]),
+                    Operation::new("Ts", vec![4.into()]),
+                ],
+                _text_at(100, 500, "Up"),
+                vec![Operation::new("Q", vec![])],
+                _text_at(100, 480, "Down"),
+            ]
+            .concat(),
+        ),
+        &[],
+    )
+    .unwrap()
+    .0;
+
+    assert_eq!(layout.runs.len(), 2);
+    // Raised by the text rise, and spaced out by the character spacing
+    assert_eq!(layout.runs[0].y, 504.0);
+    assert_eq!(layout.runs[0].font_size, 20.0);
+    assert_eq!(layout.runs[0].width, 2.0 * (12.0 + 5.0));
+    // Back to the font, spacing and rise from before `q`
+    assert_eq!(layout.runs[1].y, 480.0);
+    assert_eq!(layout.runs[1].font_size, 10.0);
+    assert_eq!(layout.runs[1].width, 4.0 * 6.0);
+}
+
+#[test]
+fn test_read_text_of_forms() {
+    let layout = read_pdf_content(
+        _pdf_drawing(
+            [
+                vec![
+                    Operation::new("q", vec![]),
+                    Operation::new(
+                        "cm",
+                        vec![
+                            1.into(),
+                            0.into(),
+                            0.into(),
+                            1.into(),
+                            100.into(),
+                            200.into(),
+                        ],
+                    ),
+                    Operation::new("Do", vec!["Fm1".into()]),
+                    Operation::new("Q", vec![]),
+                    Operation::new("BT", vec![]),
+                    // The form's font is not the page's
+                    Operation::new("Tf", vec!["F2".into(), 10.into()]),
+                ],
+                _text_at(10, 10, "Page"),
+                vec![Operation::new("ET", vec![])],
+            ]
+            .concat(),
+            [
+                vec![
+                    Operation::new("BT", vec![]),
+                    Operation::new("Tf", vec!["F2".into(), 10.into()]),
+                ],
+                _text_at(20, 30, "Form"),
+                vec![Operation::new("ET", vec![])],
+            ]
+            .concat(),
+        ),
+        &[],
+    )
+    .unwrap()
+    .0;
+
+    assert_eq!(layout.runs.len(), 1);
+    assert_eq!(layout.runs[0].text, "Form");
+    // Placed by the form's matrix, then by the page's transformation
+    assert_eq!(layout.runs[0].x, 120.0);
+    assert_eq!(layout.runs[0].y, 280.0);
+}
+
+#[test]
+fn test_read_invalid_file() {
+    let error = read_pdf_content(b"not a pdf".to_vec(), &[]).unwrap_err();
+    assert_eq!(
+        error.key(),
+        "error.reader.formats.read_pdf_content.cannot_open_file"
+    );
+}
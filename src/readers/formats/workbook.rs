@@ -0,0 +1,34 @@
+use calamine::Reader;
+use std::io::Cursor;
+
+use crate::error::Error;
+
+/// Reads every sheet of a spreadsheet workbook into a single table
+///
+/// Rows of all the sheets are appended one after the other,
+/// in the order the sheets appear in the workbook.
+pub(crate) fn read_workbook_content<R>(
+    file_content: Vec<u8>,
+    open_error: &'static str,
+) -> Result<Vec<Vec<String>>, Error>
+where
+    R: Reader<Cursor<Vec<u8>>>,
+    R::Error: std::fmt::Display,
+{
+    let cursor = Cursor::new(file_content);
+    let mut workbook = match R::new(cursor) {
+        Ok(wb) => wb,
+        Err(error) => return Err(Error::parse(open_error).with_reason(error)),
+    };
+    let mut data: Vec<Vec<String>> = Vec::new();
+    for sheet in workbook.sheet_names().to_owned() {
+        if let Ok(range) = workbook.worksheet_range(&sheet) {
+            for row in range.rows() {
+                let row_data: Vec<String> = row.iter().map(|cell| cell.to_string()).collect();
+                data.push(row_data);
+            }
+        }
+    }
+
+    Ok(data)
+}
@@ -0,0 +1,7 @@
+use super::workbook::read_workbook_content;
+use crate::error::Error;
+use calamine::Xlsb;
+
+pub(crate) fn read_xlsb_content(file_content: Vec<u8>) -> Result<Vec<Vec<String>>, Error> {
+    read_workbook_content::<Xlsb<_>>(file_content, "error.reader.read_xlsb.cannot_open_file")
+}
@@ -0,0 +1,7 @@
+use super::workbook::read_workbook_content;
+use crate::error::Error;
+use calamine::Xlsx;
+
+pub(crate) fn read_xlsx_content(file_content: Vec<u8>) -> Result<Vec<Vec<String>>, Error> {
+    read_workbook_content::<Xlsx<_>>(file_content, "error.reader.read_xlsx.cannot_open_file")
+}
@@ -0,0 +1,92 @@
+use super::encoding::decode;
+use crate::{error::Error, readers::types::Node};
+use quick_xml::{
+    events::{BytesStart, Event},
+    Reader,
+};
+
+/// Reads an XML file into a tree of elements, returning the root element
+pub(crate) fn read_xml_content(file_content: Vec<u8>) -> Result<Node, Error> {
+    let text = decode(&file_content);
+    let mut reader = Reader::from_str(&text);
+    reader.config_mut().trim_text(true);
+
+    let mut stack: Vec<Node> = vec![Node::default()];
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(element)) => stack.push(to_node(&element)?),
+            Ok(Event::Empty(element)) => {
+                let node = to_node(&element)?;
+                if let Some(parent) = stack.last_mut() {
+                    parent.children.push(node);
+                }
+            }
+            Ok(Event::End(_)) => {
+                if stack.len() < 2 {
+                    return Err(Error::parse("error.reader.read_xml.unexpected_closing_tag"));
+                }
+                let node = stack.pop().unwrap_or_default();
+                if let Some(parent) = stack.last_mut() {
+                    parent.children.push(node);
+                }
+            }
+            Ok(Event::Text(text)) => {
+                let text = text.unescape().map_err(|error| {
+                    Error::parse("error.reader.read_xml.cannot_parse_file").with_reason(error)
+                })?;
+                if let Some(node) = stack.last_mut() {
+                    node.text.push_str(&text);
+                }
+            }
+            Ok(Event::CData(data)) => {
+                if let Some(node) = stack.last_mut() {
+                    node.text.push_str(&String::from_utf8_lossy(&data));
+                }
+            }
+            Ok(Event::Eof) => break,
+            Ok(_) => {}
+            Err(error) => {
+                return Err(
+                    Error::parse("error.reader.read_xml.cannot_parse_file").with_reason(error)
+                )
+            }
+        }
+    }
+
+    if stack.len() != 1 {
+        return Err(Error::parse("error.reader.read_xml.unclosed_element"));
+    }
+
+    stack
+        .pop()
+        .and_then(|document| document.children.into_iter().next())
+        .ok_or(Error::parse("error.reader.read_xml.root_element_not_found"))
+}
+
+/// Builds a node from a start tag, dropping namespace prefixes
+fn to_node(element: &BytesStart) -> Result<Node, Error> {
+    let mut attributes = Vec::new();
+    for attribute in element.attributes() {
+        let attribute = attribute.map_err(|error| {
+            Error::parse("error.reader.read_xml.cannot_parse_file").with_reason(error)
+        })?;
+        let key = String::from_utf8_lossy(attribute.key.local_name().as_ref()).to_string();
+        let value = attribute
+            .unescape_value()
+            .map_err(|error| {
+                Error::parse("error.reader.read_xml.cannot_parse_file").with_reason(error)
+            })?
+            .to_string();
+        attributes.push((key, value));
+    }
+
+    Ok(Node {
+        name: String::from_utf8_lossy(element.local_name().as_ref()).to_string(),
+        attributes,
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests;
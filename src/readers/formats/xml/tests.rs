@@ -0,0 +1,42 @@
+#[cfg(test)]
+use crate::readers::formats::xml::read_xml_content;
+
+#[test]
+fn test_read_xml() {
+    let content = b"<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<Document xmlns=\"urn:iso:std:iso:20022:tech:xsd:camt.053.001.02\">\n  <Stmt>\n    <Amt Ccy=\"EUR\">100.00</Amt>\n    <Nm>Tom &amp; Jerry</Nm>\n    <Empty/>\n  </Stmt>\n</Document>\n";
+
+    let document = read_xml_content(content.to_vec()).unwrap();
+
+    assert_eq!(document.name, "Document");
+    assert_eq!(
+        document.attribute("xmlns"),
+        Some("urn:iso:std:iso:20022:tech:xsd:camt.053.001.02")
+    );
+
+    let amount = document.path(&["Stmt", "Amt"]).unwrap();
+    assert_eq!(amount.text, "100.00");
+    assert_eq!(amount.attribute("Ccy"), Some("EUR"));
+    assert_eq!(document.text_at(&["Stmt", "Nm"]), Some("Tom & Jerry"));
+    assert!(document.path(&["Stmt", "Empty"]).is_some());
+}
+
+#[test]
+fn test_read_xml_with_namespace_prefix() {
+    let content =
+        b"<ns:Document xmlns:ns=\"urn:test\"><ns:Stmt><ns:Id>1</ns:Id></ns:Stmt></ns:Document>";
+
+    let document = read_xml_content(content.to_vec()).unwrap();
+
+    assert_eq!(document.name, "Document");
+    assert_eq!(document.text_at(&["Stmt", "Id"]), Some("1"));
+}
+
+#[test]
+fn test_read_malformed_xml() {
+    let content = b"<Document><Stmt></Document>";
+
+    assert!(
+        read_xml_content(content.to_vec()).unwrap_err().key()
+            == "error.reader.read_xml.cannot_parse_file"
+    );
+}
@@ -1,10 +1,22 @@
 //! Exposes functions that will allow you to read financial files like Statement pdf, xls etc
 //! It should take the file as input and return relevant data from the file.
 mod formats;
-pub(crate) mod parsers;
-mod types;
+pub mod parsers;
+pub mod secrets;
+pub mod types;
+pub mod validation;
 
-use crate::readers::parsers::types::Statement;
+use crate::{
+    error::Error,
+    readers::{
+        parsers::{
+            types::{Diagnosis, SkippedRow, Statement},
+            ParserRegistry,
+        },
+        secrets::{Candidate, SecretProvider},
+    },
+};
+use sha2::{Digest, Sha256};
 
 /// Reads and parses a financial file (PDF, XLS, etc.)
 ///
@@ -20,12 +32,62 @@ use crate::readers::parsers::types::Statement;
 /// * The file cannot be read
 /// * The file format is unsupported
 /// * The file cannot be parsed
-pub fn get_statement_from_file(
-    file_path: &String,
-    file_secret: &String,
-) -> Result<Statement, String> {
+pub fn get_statement_from_file(file_path: &str, file_secret: &str) -> Result<Statement, Error> {
+    get_statement_from_file_with_secrets(file_path, file_secret).map(|(statement, _)| statement)
+}
+
+/// Reads and parses a financial file, trying each password of a secret provider
+///
+/// # Arguments
+/// * `file_path` - Path to the file to read
+/// * `secrets` - Passwords to try, in order, if the file is encrypted
+///
+/// # Returns
+/// The parsed statement data, and the candidate that decrypted the file
+/// (`None` if the file was not encrypted)
+///
+/// # Errors
+/// Returns an error if:
+/// * The file cannot be read
+/// * None of the passwords decrypts the file
+/// * The file format is unsupported
+/// * The file cannot be parsed
+pub fn get_statement_from_file_with_secrets(
+    file_path: &str,
+    secrets: &(impl SecretProvider + ?Sized),
+) -> Result<(Statement, Option<Candidate>), Error> {
+    get_statement_from_file_with_parsers(file_path, secrets, &ParserRegistry::default(), None)
+}
+
+/// Reads and parses a financial file with a registry of parsers, or with one
+/// of its parsers picked by name
+///
+/// # Arguments
+/// * `file_path` - Path to the file to read
+/// * `secrets` - Passwords to try, in order, if the file is encrypted
+/// * `parsers` - Parsers to identify the statement with
+/// * `parser` - Name of the parser to read the statement with, instead of
+///   the one identifying it
+///
+/// # Returns
+/// The parsed statement data, and the candidate that decrypted the file
+/// (`None` if the file was not encrypted)
+///
+/// # Errors
+/// Returns an error if:
+/// * The file cannot be read
+/// * None of the passwords decrypts the file
+/// * The file format is unsupported
+/// * No parser is registered under the name, or none identifies the statement
+/// * The file cannot be parsed
+pub fn get_statement_from_file_with_parsers(
+    file_path: &str,
+    secrets: &(impl SecretProvider + ?Sized),
+    parsers: &ParserRegistry,
+    parser: Option<&str>,
+) -> Result<(Statement, Option<Candidate>), Error> {
     let file_content = formats::load_file_content(file_path)?;
-    get_statement_from_file_content(file_content, file_secret)
+    get_statement_from_file_content_with_parsers(file_content, secrets, parsers, parser)
 }
 
 /// Reads and parses a financial file (PDF, XLS, etc.) from raw file content
@@ -43,10 +105,152 @@ pub fn get_statement_from_file(
 /// * The file cannot be parsed
 pub fn get_statement_from_file_content(
     file_content: Vec<u8>,
-    file_secret: &String,
-) -> Result<Statement, String> {
-    let file = formats::read_file_content(file_content, file_secret)?;
-    let parser = parsers::get_parser(&file)?;
-    let parsed_data = parser.parse(&file)?;
-    Ok(parsed_data)
+    file_secret: &str,
+) -> Result<Statement, Error> {
+    get_statement_from_file_content_with_secrets(file_content, file_secret)
+        .map(|(statement, _)| statement)
+}
+
+/// Reads and parses a financial file from raw file content, trying each
+/// password of a secret provider
+///
+/// # Arguments
+/// * `file_content` - Raw bytes of the file content
+/// * `secrets` - Passwords to try, in order, if the file is encrypted
+///
+/// # Returns
+/// The parsed statement data, and the candidate that decrypted the file
+/// (`None` if the file was not encrypted)
+///
+/// # Errors
+/// Returns an error if:
+/// * None of the passwords decrypts the file
+/// * The file format is unsupported
+/// * The file cannot be parsed
+pub fn get_statement_from_file_content_with_secrets(
+    file_content: Vec<u8>,
+    secrets: &(impl SecretProvider + ?Sized),
+) -> Result<(Statement, Option<Candidate>), Error> {
+    get_statement_from_file_content_with_parsers(
+        file_content,
+        secrets,
+        &ParserRegistry::default(),
+        None,
+    )
+}
+
+/// Reads and parses a financial file from raw file content with a registry of
+/// parsers, or with one of its parsers picked by name
+///
+/// # Arguments
+/// * `file_content` - Raw bytes of the file content
+/// * `secrets` - Passwords to try, in order, if the file is encrypted
+/// * `parsers` - Parsers to identify the statement with
+/// * `parser` - Name of the parser to read the statement with, instead of
+///   the one identifying it
+///
+/// # Returns
+/// The parsed statement data, and the candidate that decrypted the file
+/// (`None` if the file was not encrypted)
+///
+/// # Errors
+/// Returns an error if:
+/// * None of the passwords decrypts the file
+/// * The file format is unsupported
+/// * No parser is registered under the name, or none identifies the statement
+/// * The file cannot be parsed
+pub fn get_statement_from_file_content_with_parsers(
+    file_content: Vec<u8>,
+    secrets: &(impl SecretProvider + ?Sized),
+    parsers: &ParserRegistry,
+    parser: Option<&str>,
+) -> Result<(Statement, Option<Candidate>), Error> {
+    let source_hash = hash_file_content(&file_content);
+    let (file, candidate) = formats::read_file_content(file_content, &secrets.candidates())?;
+    let parser = parsers.select(&file, parser)?;
+    let mut parsed_data = parser.parse(&file)?;
+    parsed_data.source_hash = Some(source_hash);
+    Ok((parsed_data, candidate))
+}
+
+/// Reads a financial file the way [`get_statement_from_file_with_parsers`]
+/// does, reporting why it could not be read instead of failing
+///
+/// # Errors
+/// Returns an error if:
+/// * The file cannot be read
+/// * None of the passwords decrypts the file
+/// * The file format is unsupported
+pub fn diagnose_file(
+    file_path: &str,
+    secrets: &(impl SecretProvider + ?Sized),
+    parsers: &ParserRegistry,
+    parser: Option<&str>,
+) -> Result<Diagnosis, Error> {
+    let file_content = formats::load_file_content(file_path)?;
+    diagnose_file_content(file_content, secrets, parsers, parser)
+}
+
+/// Reads a financial file from raw file content the way
+/// [`get_statement_from_file_content_with_parsers`] does, reporting why it
+/// could not be read instead of failing
+///
+/// The diagnosis tells what each registered parser made of the file, and
+/// which rows the chosen parser skipped or failed at, with their content.
+///
+/// # Errors
+/// Returns an error if:
+/// * None of the passwords decrypts the file
+/// * The file format is unsupported
+pub fn diagnose_file_content(
+    file_content: Vec<u8>,
+    secrets: &(impl SecretProvider + ?Sized),
+    parsers: &ParserRegistry,
+    parser: Option<&str>,
+) -> Result<Diagnosis, Error> {
+    let source_hash = hash_file_content(&file_content);
+    let (file, _) = formats::read_file_content(file_content, &secrets.candidates())?;
+    let mut diagnosis = Diagnosis {
+        file_type: file.file_type,
+        parsers: parsers.diagnose(&file),
+        parser: None,
+        statement: None,
+        error: None,
+        skipped_rows: Vec::new(),
+    };
+
+    let parser = match parsers.select(&file, parser) {
+        Ok(parser) => parser,
+        Err(error) => {
+            diagnosis.error = Some(error);
+            return Ok(diagnosis);
+        }
+    };
+    diagnosis.parser = Some(parser.name().to_string());
+
+    match parser.parse(&file) {
+        Ok(mut statement) => {
+            statement.source_hash = Some(source_hash);
+            diagnosis.skipped_rows = statement.skipped_rows.clone();
+            diagnosis.statement = Some(statement);
+        }
+        Err(error) => {
+            if let Some(location) = error.location() {
+                let content = file.content_at(location).unwrap_or_default();
+                diagnosis
+                    .skipped_rows
+                    .push(SkippedRow::from_error(&error, content));
+            }
+            diagnosis.error = Some(error);
+        }
+    }
+    Ok(diagnosis)
+}
+
+/// Hex encoded SHA-256 of the file as it was given, before any decryption
+fn hash_file_content(file_content: &[u8]) -> String {
+    Sha256::digest(file_content)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
 }
@@ -1,17 +1,199 @@
+//! Parsers reading the statement out of a loaded file
+//!
+//! Every parser reads the statements of one institution, or of one standard
+//! format, and tells how sure it is that a file is one of them. The parser of
+//! a file is the one of a [`ParserRegistry`] that is the most sure.
 mod sources;
-pub(crate) mod types;
+pub mod templates;
+pub mod types;
 
-use crate::readers::parsers::types::Parser;
-use crate::readers::types::File;
+use std::{fs, path::Path};
 
-pub(crate) fn get_parser(file: &File) -> Result<Parser, String> {
-    let parsers = sources::get_all_parsers();
+use sea_orm::sqlx::types::chrono::FixedOffset;
 
-    for parser in parsers {
-        if parser.identify(file)? {
-            return Ok(parser);
+use crate::{
+    error::Error,
+    readers::{
+        parsers::{
+            templates::TemplateParser,
+            types::{Confidence, ParserDiagnosis, StatementParser},
+        },
+        types::File,
+    },
+    utils::datetime::IST,
+};
+
+/// Parsers a file can be read with
+///
+/// The default registry holds the parsers of this crate, and parsers of
+/// other institutions can be added to it at runtime.
+pub struct ParserRegistry {
+    parsers: Vec<Box<dyn StatementParser>>,
+}
+
+impl Default for ParserRegistry {
+    fn default() -> Self {
+        ParserRegistry::with_timezone(IST)
+    }
+}
+
+impl ParserRegistry {
+    /// Makes a registry of the parsers of this crate, where the parsers of
+    /// standard formats (OFX, camt.053, MT940) read the dates that carry no
+    /// offset in `timezone`
+    pub fn with_timezone(timezone: FixedOffset) -> Self {
+        ParserRegistry {
+            parsers: sources::get_all_parsers(timezone),
+        }
+    }
+
+    /// Makes a registry without any parser
+    pub fn new() -> Self {
+        ParserRegistry {
+            parsers: Vec::new(),
+        }
+    }
+
+    /// Adds a parser, in place of the one registered under the same name
+    pub fn register(&mut self, parser: impl StatementParser + 'static) -> &mut Self {
+        let parser: Box<dyn StatementParser> = Box::new(parser);
+        match self
+            .parsers
+            .iter_mut()
+            .find(|registered| registered.name() == parser.name())
+        {
+            Some(registered) => *registered = parser,
+            None => self.parsers.push(parser),
+        }
+        self
+    }
+
+    /// Adds a parser for each template file (`.toml`, `.yaml` or `.yml`) of a
+    /// directory, in the order of their file names
+    ///
+    /// # Errors
+    /// Returns an error if the directory cannot be read, or if one of the
+    /// templates is invalid
+    pub fn register_templates(&mut self, directory: impl AsRef<Path>) -> Result<&mut Self, Error> {
+        let directory = directory.as_ref();
+        let cannot_read = |error: std::io::Error| Error::Io {
+            key: "error.reader.parsers.register_templates.cannot_read_directory",
+            reason: format!("{}: {}", directory.display(), error),
+        };
+
+        let mut paths = Vec::new();
+        for entry in fs::read_dir(directory).map_err(cannot_read)? {
+            let path = entry.map_err(cannot_read)?.path();
+            if matches!(
+                path.extension().and_then(|extension| extension.to_str()),
+                Some("toml" | "yaml" | "yml")
+            ) {
+                paths.push(path);
+            }
         }
+        paths.sort();
+
+        for path in paths {
+            self.register(TemplateParser::from_file(path)?);
+        }
+        Ok(self)
+    }
+
+    /// Returns the parsers, in the order they were registered
+    pub fn parsers(&self) -> impl Iterator<Item = &dyn StatementParser> {
+        self.parsers.iter().map(|parser| parser.as_ref())
+    }
+
+    /// Returns the parser registered under a name
+    pub fn get(&self, name: &str) -> Option<&dyn StatementParser> {
+        self.parsers().find(|parser| parser.name() == name)
+    }
+
+    /// Returns the parser that is the most sure it reads the file
+    ///
+    /// Parsers are only asked about the types of files they read. When
+    /// several are as sure, the one registered first is returned. A parser
+    /// failing to identify the file does not read it, which
+    /// [`ParserRegistry::diagnose`] tells.
+    ///
+    /// # Errors
+    /// Returns an error if no parser reads the file
+    pub fn identify(&self, file: &File) -> Result<&dyn StatementParser, Error> {
+        let mut best: Option<(&dyn StatementParser, Confidence)> = None;
+
+        for parser in self.parsers() {
+            if !parser.file_types().contains(&file.file_type) {
+                continue;
+            }
+            let confidence = parser.identify(file).unwrap_or_else(|error| {
+                tracing::info!(
+                    "info.reader.parsers.identify.cannot_identify: {}: {}",
+                    parser.name(),
+                    error
+                );
+                Confidence::NONE
+            });
+            if confidence.is_match() && best.is_none_or(|(_, best)| confidence > best) {
+                best = Some((parser, confidence));
+            }
+        }
+
+        best.map(|(parser, _)| parser).ok_or(Error::parser_mismatch(
+            "error.reader.parsers.get_parser.no_matching_parser_found",
+        ))
+    }
+
+    /// Tells what each parser makes of the file, and why the ones that do not
+    /// read it do not
+    pub fn diagnose(&self, file: &File) -> Vec<ParserDiagnosis> {
+        self.parsers()
+            .map(|parser| {
+                let (confidence, mismatch) = if !parser.file_types().contains(&file.file_type) {
+                    (
+                        Confidence::NONE,
+                        Some(format!("does not read {:?} files", file.file_type)),
+                    )
+                } else {
+                    match parser.identify(file) {
+                        Ok(confidence) if confidence.is_match() => (confidence, None),
+                        Ok(confidence) => (
+                            confidence,
+                            Some(
+                                parser
+                                    .explain_mismatch(file)
+                                    .unwrap_or("does not recognise the file".to_string()),
+                            ),
+                        ),
+                        Err(error) => (
+                            Confidence::NONE,
+                            Some(format!("cannot identify the file: {}", error)),
+                        ),
+                    }
+                };
+                ParserDiagnosis {
+                    parser: parser.name().to_string(),
+                    confidence,
+                    mismatch,
+                }
+            })
+            .collect()
     }
 
-    Err("error.reader.parsers.get_parser.no_matching_parser_found".to_string())
+    /// Returns the parser registered under `name` if given, otherwise the one
+    /// identifying the file
+    ///
+    /// # Errors
+    /// Returns an error if no parser is registered under the name, or if none
+    /// identifies the file
+    pub fn select(&self, file: &File, name: Option<&str>) -> Result<&dyn StatementParser, Error> {
+        match name {
+            Some(name) => self.get(name).ok_or(Error::parser_mismatch(
+                "error.reader.parsers.get_parser.unknown_parser",
+            )),
+            None => self.identify(file),
+        }
+    }
 }
+
+#[cfg(test)]
+mod tests;
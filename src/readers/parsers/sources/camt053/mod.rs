@@ -0,0 +1,361 @@
+//! Parser for ISO 20022 bank-to-customer statements (camt.053)
+//!
+//! Only booked entries are read. When a file holds statements for several
+//! accounts, the first one is used. Datetimes are read in the offset they
+//! are written with.
+use sea_orm::{
+    prelude::{DateTimeUtc, Decimal},
+    sqlx::types::chrono::{DateTime, FixedOffset},
+};
+
+use crate::{
+    error::{Error, Location},
+    models::entities::sea_orm_active_enums::{AccountType, InstitutionName},
+    readers::{
+        parsers::types::{Confidence, SkippedRow, Statement, StatementParser, Transaction},
+        types::{File, FileData, FileType, Node},
+    },
+    utils::{
+        self, account_number,
+        datetime::{DateFormat, IST},
+    },
+};
+
+/// Dates are the leading `YYYY-MM-DD` of an ISO 8601 date or datetime
+const DATE_FORMAT: DateFormat = DateFormat::ISO;
+
+/// camt.053 statements of any institution
+pub struct Camt053 {
+    /// Timezone of the dates and datetimes that carry no offset
+    timezone: FixedOffset,
+}
+
+impl Camt053 {
+    pub fn new(timezone: FixedOffset) -> Self {
+        Camt053 { timezone }
+    }
+}
+
+impl Default for Camt053 {
+    fn default() -> Self {
+        Camt053::new(IST)
+    }
+}
+
+impl StatementParser for Camt053 {
+    fn name(&self) -> &str {
+        "camt053"
+    }
+
+    fn institution(&self) -> Option<InstitutionName> {
+        None
+    }
+
+    fn file_types(&self) -> &[FileType] {
+        &[FileType::Xml]
+    }
+
+    fn version(&self) -> u32 {
+        1
+    }
+
+    fn identify(&self, file: &File) -> Result<Confidence, Error> {
+        Ok(match recognise(file) {
+            Ok(()) => Confidence::FORMAT,
+            Err(_) => Confidence::NONE,
+        })
+    }
+
+    fn explain_mismatch(&self, file: &File) -> Option<String> {
+        recognise(file).err()
+    }
+
+    fn parse(&self, file: &File) -> Result<Statement, Error> {
+        match &file.data {
+            FileData::Tree(data) => parse_camt(data, self.timezone),
+            FileData::Table(_) | FileData::Text(_) | FileData::Layout(_) => Err(
+                Error::parser_mismatch("error.parser.camt053.unsupported_file_data"),
+            ),
+        }
+    }
+}
+
+/// Tells whether the file is a camt.053 document, or why it is not
+fn recognise(file: &File) -> Result<(), String> {
+    let data = match &file.data {
+        FileData::Tree(data) if matches!(file.file_type, FileType::Xml) => data,
+        _ => return Err("the file is not an XML document".to_string()),
+    };
+
+    if data.name != "Document" {
+        return Err(format!(
+            "the root element is `{}`, not `Document`",
+            data.name
+        ));
+    }
+    if let Some(namespace) = data
+        .attribute("xmlns")
+        .filter(|namespace| !namespace.contains("camt.053"))
+    {
+        return Err(format!("the namespace `{}` is not camt.053", namespace));
+    }
+    if data.child("BkToCstmrStmt").is_none() {
+        return Err("the document has no `BkToCstmrStmt` element".to_string());
+    }
+    Ok(())
+}
+
+fn parse_camt(document: &Node, timezone: FixedOffset) -> Result<Statement, Error> {
+    let statement = document
+        .path(&["BkToCstmrStmt", "Stmt"])
+        .ok_or(Error::parse("error.parser.camt053.statement_not_found"))?;
+
+    // The statement is in the timezone its own datetimes give, if any does
+    let timezone = [
+        &["FrToDt", "ToDtTm"][..],
+        &["FrToDt", "FrDtTm"],
+        &["CreDtTm"],
+    ]
+    .iter()
+    .find_map(|path| statement.text_at(path).and_then(parse_offset))
+    .unwrap_or(timezone);
+    let parse_date = |date: &str| parse_date(date, timezone);
+
+    let account_number = statement
+        .text_at(&["Acct", "Id", "IBAN"])
+        .or_else(|| statement.text_at(&["Acct", "Id", "Othr", "Id"]))
+        .map(|number| number.to_string());
+
+    let account_type = match statement.text_at(&["Acct", "Tp", "Cd"]) {
+        Some("CACC") => AccountType::CheckingAccount,
+        Some("SVGS") => AccountType::SavingsAccount,
+        Some("CARD") => AccountType::CreditCard,
+        _ => AccountType::Unknown,
+    };
+
+    let mut opening_balance = None;
+    let mut closing_balance = None;
+    let mut closing_date = None;
+    for balance in statement.children_named("Bal") {
+        let amount = parse_signed_amount(balance)?;
+        match balance.text_at(&["Tp", "CdOrPrtry", "Cd"]) {
+            Some("OPBD") | Some("PRCD") if opening_balance.is_none() => {
+                opening_balance = Some(amount)
+            }
+            Some("CLBD") => {
+                closing_balance = Some(amount);
+                closing_date = parse_date_choice(balance.path(&["Dt"]), timezone);
+            }
+            _ => {}
+        }
+    }
+
+    let period_start = statement
+        .text_at(&["FrToDt", "FrDtTm"])
+        .and_then(parse_date);
+    let period_end = statement
+        .text_at(&["FrToDt", "ToDtTm"])
+        .and_then(parse_date);
+
+    let date = period_end
+        .or(closing_date)
+        .or_else(|| statement.text_at(&["CreDtTm"]).and_then(parse_date))
+        .unwrap_or_else(utils::datetime::get_current_datetime);
+
+    let (transactions, skipped_rows) =
+        parse_trnx(statement, opening_balance, closing_balance, timezone)?;
+
+    Ok(Statement {
+        transactions,
+        institution: None,
+        account_type,
+        masked_account_number: account_number.as_deref().map(account_number::mask),
+        account_number,
+        ifsc: None,
+        branch: None,
+        holder_name: statement
+            .text_at(&["Acct", "Ownr", "Nm"])
+            .map(|name| name.to_string()),
+        currency: statement
+            .text_at(&["Acct", "Ccy"])
+            .map(|code| code.to_string()),
+        date,
+        timezone,
+        period_start,
+        period_end,
+        opening_balance,
+        closing_balance,
+        totals: None,
+        source_hash: None,
+        skipped_rows,
+        warnings: Vec::new(),
+    })
+}
+
+fn parse_trnx(
+    statement: &Node,
+    opening_balance: Option<Decimal>,
+    closing_balance: Option<Decimal>,
+    timezone: FixedOffset,
+) -> Result<(Vec<Transaction>, Vec<SkippedRow>), Error> {
+    let mut records = Vec::new();
+    let mut skipped_rows = Vec::new();
+
+    for (index, entry) in statement.children_named("Ntry").enumerate() {
+        let row = Location::Row(index + 1);
+
+        // Pending and informational entries have not moved any money yet
+        let status = entry
+            .text_at(&["Sts", "Cd"])
+            .or_else(|| entry.text_at(&["Sts"]));
+        if matches!(status, Some("PDNG") | Some("INFO")) {
+            skipped_rows.push(SkippedRow {
+                location: Some(row),
+                content: entry.texts().join(" | "),
+                key: "error.parser.camt053.entry_not_booked",
+                reason: status.map(|status| status.to_string()),
+            });
+            continue;
+        }
+
+        match parse_entry(entry, timezone) {
+            Ok(record) => records.push(record),
+            Err(error) => skipped_rows.push(SkippedRow::from_error(
+                &error.at(row),
+                entry.texts().join(" | "),
+            )),
+        }
+    }
+
+    // Entries carry no running balance, so accumulate one from the
+    // opening balance (or back from the closing balance)
+    let total: Decimal = records.iter().map(|record| record.balance).sum();
+    let mut balance = opening_balance
+        .or(closing_balance.map(|closing| closing - total))
+        .unwrap_or(Decimal::ZERO);
+    for record in records.iter_mut() {
+        balance += record.balance;
+        record.balance = balance;
+    }
+
+    Ok((records, skipped_rows))
+}
+
+/// Reads a booked entry, leaving its signed amount as the balance until the
+/// running balance is worked out
+fn parse_entry(entry: &Node, timezone: FixedOffset) -> Result<Transaction, Error> {
+    let amount = parse_signed_amount(entry)?;
+    let date = parse_date_choice(entry.path(&["BookgDt"]), timezone)
+        .or_else(|| parse_date_choice(entry.path(&["ValDt"]), timezone))
+        .ok_or(Error::parse("error.parser.camt053.booking_date_not_found"))?;
+
+    let details = entry.path(&["NtryDtls", "TxDtls"]);
+    let ref_no = entry
+        .text_at(&["AcctSvcrRef"])
+        .or_else(|| details.and_then(|details| details.text_at(&["Refs", "EndToEndId"])))
+        .filter(|reference| *reference != "NOTPROVIDED")
+        .unwrap_or_default()
+        .to_string();
+
+    Ok(Transaction {
+        date,
+        description: describe(entry, details, amount),
+        ref_no,
+        withdrawal: if amount < Decimal::ZERO {
+            -amount
+        } else {
+            Decimal::ZERO
+        },
+        deposit: if amount > Decimal::ZERO {
+            amount
+        } else {
+            Decimal::ZERO
+        },
+        balance: amount,
+        warnings: Vec::new(),
+    })
+}
+
+/// Builds the description from the counterparty and the remittance information
+fn describe(entry: &Node, details: Option<&Node>, amount: Decimal) -> String {
+    let mut parts: Vec<String> = Vec::new();
+
+    if let Some(details) = details {
+        // Money coming in was sent by the debtor, money going out goes to the creditor
+        let (party, party_account) = if amount >= Decimal::ZERO {
+            ("Dbtr", "DbtrAcct")
+        } else {
+            ("Cdtr", "CdtrAcct")
+        };
+
+        let name = details
+            .text_at(&["RltdPties", party, "Nm"])
+            .or_else(|| details.text_at(&["RltdPties", party, "Pty", "Nm"]));
+        let account = details
+            .text_at(&["RltdPties", party_account, "Id", "IBAN"])
+            .or_else(|| details.text_at(&["RltdPties", party_account, "Id", "Othr", "Id"]));
+
+        match (name, account) {
+            (Some(name), Some(account)) => parts.push(format!("{} ({})", name, account)),
+            (Some(name), None) => parts.push(name.to_string()),
+            (None, Some(account)) => parts.push(account.to_string()),
+            (None, None) => {}
+        }
+
+        if let Some(remittance) = details.path(&["RmtInf"]) {
+            parts.extend(
+                remittance
+                    .children_named("Ustrd")
+                    .map(|line| line.text.trim().to_string())
+                    .filter(|line| !line.is_empty()),
+            );
+        }
+    }
+
+    if let Some(information) = entry.text_at(&["AddtlNtryInf"]) {
+        parts.push(information.to_string());
+    }
+
+    parts.join(" - ")
+}
+
+/// Reads an `Amt` with its `CdtDbtInd`, debits being negative
+fn parse_signed_amount(node: &Node) -> Result<Decimal, Error> {
+    let amount = node
+        .text_at(&["Amt"])
+        .ok_or(Error::parse("error.parser.camt053.amount_not_found"))?;
+    let amount = amount
+        .parse::<Decimal>()
+        .map_err(|_| Error::parse("error.parser.camt053.invalid_amount").with_reason(amount))?;
+
+    match node.text_at(&["CdtDbtInd"]) {
+        Some("DBIT") => Ok(-amount),
+        _ => Ok(amount),
+    }
+}
+
+/// Reads a `DateAndDateTimeChoice`, which holds either a `Dt` or a `DtTm`
+fn parse_date_choice(node: Option<&Node>, timezone: FixedOffset) -> Option<DateTimeUtc> {
+    let node = node?;
+    node.text_at(&["Dt"])
+        .or_else(|| node.text_at(&["DtTm"]))
+        .and_then(|date| parse_date(date, timezone))
+}
+
+/// Reads the date part of an ISO 8601 date or datetime, as the day it is in
+/// its own offset, or in `timezone` without one
+fn parse_date(date: &str, timezone: FixedOffset) -> Option<DateTimeUtc> {
+    let timezone = parse_offset(date).unwrap_or(timezone);
+    utils::datetime::parse_date(date.get(..10)?, &[DATE_FORMAT], timezone).ok()
+}
+
+/// Reads the offset of an ISO 8601 datetime (`2023-01-31T18:00:00+01:00`,
+/// or `Z` for UTC)
+fn parse_offset(date: &str) -> Option<FixedOffset> {
+    DateTime::parse_from_rfc3339(date.trim())
+        .ok()
+        .map(|datetime| *datetime.offset())
+}
+
+#[cfg(test)]
+mod tests;
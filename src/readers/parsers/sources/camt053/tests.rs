@@ -0,0 +1,167 @@
+use crate::utils::datetime::{parse_date, DateFormat, IST};
+use sea_orm::prelude::DateTimeUtc;
+
+/// Start of the day in India Standard Time, where statement dates fall
+pub fn _date(date: &str) -> DateTimeUtc {
+    parse_date(date, &[DateFormat::DD_MM_YY, DateFormat::DD_MM_YYYY], IST).unwrap()
+}
+
+#[cfg(test)]
+mod xml {
+    use crate::{
+        error::Location,
+        models::entities::sea_orm_active_enums::AccountType,
+        readers::{
+            get_statement_from_file_content, get_statement_from_file_content_with_parsers,
+            parsers::{
+                sources::camt053::{tests::_date, Camt053},
+                types::StatementParser,
+                ParserRegistry,
+            },
+            types::{File, FileData, FileType, Node},
+        },
+    };
+    use rust_decimal_macros::dec;
+    use sea_orm::sqlx::types::chrono::{FixedOffset, TimeZone, Utc};
+
+    fn _fixture() -> Vec<u8> {
+        include_bytes!("fixtures/statement.xml").to_vec()
+    }
+
+    #[test]
+    fn test_identify_valid_file() {
+        let file = File {
+            file_type: FileType::Xml,
+            data: FileData::Tree(Node {
+                name: "Document".to_string(),
+                children: vec![Node {
+                    name: "BkToCstmrStmt".to_string(),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }),
+        };
+
+        let parser = Camt053::default();
+        assert!(parser.identify(&file).unwrap().is_match());
+    }
+
+    #[test]
+    fn test_identify_invalid_file_content() {
+        let file = File {
+            file_type: FileType::Xml,
+            data: FileData::Tree(Node {
+                name: "Document".to_string(),
+                children: vec![Node {
+                    name: "CstmrCdtTrfInitn".to_string(),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }),
+        };
+
+        let parser = Camt053::default();
+        assert!(!parser.identify(&file).unwrap().is_match());
+    }
+
+    #[test]
+    fn test_parse_valid_file() {
+        let statement = get_statement_from_file_content(_fixture(), "").unwrap();
+
+        assert_eq!(statement.account_type, AccountType::CheckingAccount);
+        assert_eq!(
+            statement.account_number,
+            Some("DE89370400440532013000".to_string())
+        );
+        assert_eq!(statement.date, _date("31/01/2023"));
+        assert_eq!(statement.opening_balance, Some(dec!(1000.0)));
+        assert_eq!(statement.closing_balance, Some(dec!(1149.5)));
+
+        let transactions = &statement.transactions;
+        assert_eq!(transactions.len(), 2);
+
+        assert_eq!(transactions[0].date, _date("03/01/2023"));
+        assert_eq!(
+            transactions[0].description,
+            "Grocer GmbH (DE02120300000000202051) - Invoice 42"
+        );
+        assert_eq!(transactions[0].ref_no, "BANKREF-0001");
+        assert_eq!(transactions[0].withdrawal, dec!(50.5));
+        assert_eq!(transactions[0].deposit, dec!(0.0));
+        assert_eq!(transactions[0].balance, dec!(949.5));
+
+        assert_eq!(transactions[1].date, _date("15/01/2023"));
+        assert_eq!(
+            transactions[1].description,
+            "Employer AG - SEPA Credit Transfer"
+        );
+        assert_eq!(transactions[1].ref_no, "SALARY-JAN");
+        assert_eq!(transactions[1].withdrawal, dec!(0.0));
+        assert_eq!(transactions[1].deposit, dec!(200.0));
+        assert_eq!(transactions[1].balance, dec!(1149.5));
+
+        // The pending entry is not booked yet, so it is passed over
+        assert_eq!(statement.skipped_rows.len(), 1);
+        let skipped = &statement.skipped_rows[0];
+        assert_eq!(skipped.location, Some(Location::Row(3)));
+        assert_eq!(skipped.key, "error.parser.camt053.entry_not_booked");
+        assert_eq!(skipped.reason.as_deref(), Some("PDNG"));
+        assert_eq!(skipped.content, "75.00 | DBIT | PDNG | 2023-01-31");
+    }
+
+    #[test]
+    fn test_parse_without_statement() {
+        let file = File {
+            file_type: FileType::Xml,
+            data: FileData::Tree(Node {
+                name: "Document".to_string(),
+                children: vec![Node {
+                    name: "BkToCstmrStmt".to_string(),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }),
+        };
+
+        let parser = Camt053::default();
+        assert_eq!(
+            parser.parse(&file).unwrap_err().key(),
+            "error.parser.camt053.statement_not_found"
+        );
+    }
+
+    #[test]
+    fn test_parse_dates_in_their_offset() {
+        let utc = FixedOffset::east_opt(0).unwrap();
+        let parsers = ParserRegistry::with_timezone(utc);
+
+        let (statement, _) =
+            get_statement_from_file_content_with_parsers(_fixture(), "", &parsers, None).unwrap();
+        assert_eq!(statement.timezone, utc);
+        assert_eq!(
+            statement.transactions[0].date,
+            Utc.with_ymd_and_hms(2023, 1, 3, 0, 0, 0).unwrap()
+        );
+
+        let content = String::from_utf8(_fixture())
+            .unwrap()
+            .replace("2023-01-31T23:59:59", "2023-01-31T23:59:59+01:00")
+            .replace("2023-01-15T10:30:00", "2023-01-15T00:30:00+01:00");
+        let (statement, _) =
+            get_statement_from_file_content_with_parsers(content.into_bytes(), "", &parsers, None)
+                .unwrap();
+        let cet = FixedOffset::east_opt(3600).unwrap();
+        assert_eq!(statement.timezone, cet);
+        // Dates without an offset are in the statement's timezone
+        assert_eq!(
+            statement.transactions[0].date,
+            Utc.with_ymd_and_hms(2023, 1, 2, 23, 0, 0).unwrap()
+        );
+        // Half past midnight in Berlin is the 15th there, though still the
+        // 14th in UTC
+        assert_eq!(
+            statement.transactions[1].date,
+            Utc.with_ymd_and_hms(2023, 1, 14, 23, 0, 0).unwrap()
+        );
+    }
+}
@@ -1,266 +1,649 @@
 use regex::Regex;
+use sea_orm::{
+    prelude::{DateTimeUtc, Decimal},
+    sqlx::types::chrono::FixedOffset,
+};
 
 use crate::{
-    models::entities::sea_orm_active_enums::AccountType,
+    error::{Error, Location},
+    models::entities::sea_orm_active_enums::{AccountType, InstitutionName},
     readers::{
-        parsers::types::{Parser, Statement, Transaction},
-        types::{File, FileData, FileType},
+        parsers::types::{
+            derive_balance, Confidence, SkippedRow, Statement, StatementParser, StatementTotals,
+            Transaction,
+        },
+        types::{File, FileData, FileType, Layout},
+    },
+    utils::{
+        self, account_number,
+        datetime::{DateFormat, IST},
     },
-    utils,
 };
 
-pub fn get_parser() -> Parser {
-    fn identify(file: &File) -> Result<bool, String> {
-        let data = &file.data;
+/// Column headings of the transaction table
+const HEADER: [&str; 7] = [
+    "Date",
+    "Narration",
+    "Chq./Ref.No.",
+    "Value Dt",
+    "Withdrawal Amt.",
+    "Deposit Amt.",
+    "Closing Balance",
+];
+
+/// Dates are printed as `dd/mm/yy`, but workbooks may hold them as dates
+const DATE_FORMATS: [DateFormat; 3] = [
+    DateFormat::DD_MM_YY,
+    DateFormat::DD_MM_YYYY,
+    DateFormat::ExcelSerial,
+];
+
+const TIMEZONE: FixedOffset = IST;
+
+/// Transactions read from the table, and the rows of it that were skipped
+type Rows = (Vec<Transaction>, Vec<SkippedRow>);
+
+/// Statements of HDFC Bank savings accounts, as XLS or PDF
+pub struct HdfcInd;
+
+impl StatementParser for HdfcInd {
+    fn name(&self) -> &str {
+        "hdfcind"
+    }
 
-        match data {
-            FileData::Table(data) => {
-                let is_xls = matches!(file.file_type, FileType::Xls);
-                let first_cell = data.first().and_then(|row| row.first());
-                let contains_hdfc = first_cell
-                    .map(|cell| cell.contains("HDFC BANK Ltd."))
-                    .unwrap_or(false);
-
-                if is_xls && contains_hdfc {
-                    return Ok(true);
-                }
-            }
-            FileData::Text(data) => {
-                if matches!(file.file_type, FileType::Pdf)
-                    && data.contains("Statementofaccount")
-                    && data.contains("HDFCBANKLIMITED")
-                {
-                    return Ok(true);
-                }
-            }
-        }
+    fn institution(&self) -> Option<InstitutionName> {
+        Some(InstitutionName::Hdfc)
+    }
+
+    fn file_types(&self) -> &[FileType] {
+        &[
+            FileType::Xls,
+            FileType::Xlsx,
+            FileType::Xlsb,
+            FileType::Ods,
+            FileType::Csv,
+            FileType::Pdf,
+        ]
+    }
+
+    fn version(&self) -> u32 {
+        1
+    }
+
+    fn identify(&self, file: &File) -> Result<Confidence, Error> {
+        Ok(match recognise(file) {
+            Ok(()) => Confidence::CERTAIN,
+            Err(_) => Confidence::NONE,
+        })
+    }
 
-        return Ok(false);
+    fn explain_mismatch(&self, file: &File) -> Option<String> {
+        recognise(file).err()
     }
 
-    fn parse(file: &File) -> Result<Statement, String> {
+    fn parse(&self, file: &File) -> Result<Statement, Error> {
         let data = &file.data;
         match data {
             FileData::Table(data) => parse_xls(data),
-            FileData::Text(data) => parse_pdf(data),
+            FileData::Text(data) => parse_pdf(data, None),
+            FileData::Layout(layout) => parse_pdf(&layout.text, Some(layout)),
+            FileData::Tree(_) => Err(Error::parser_mismatch(
+                "error.parser.hdfcind.unsupported_file_data",
+            )),
         }
     }
-
-    Parser { identify, parse }
 }
 
-fn parse_xls(table: &Vec<Vec<String>>) -> Result<Statement, String> {
-    let mut account_number = String::new();
-    let current_time = utils::datetime::get_current_datetime();
-    let mut statement_date = current_time;
-    let mut account_type = AccountType::Unknown;
-
-    for row in table {
-        for cell in row {
-            if cell.contains("Account No :") {
-                account_number = cell
-                    .split("Account No :")
-                    .nth(1)
-                    .unwrap_or("")
-                    .split_whitespace()
-                    .next()
-                    .unwrap_or("")
-                    .to_string();
-            }
-
-            if cell.contains("Statement From") {
-                let date_str = cell.split("To  :").nth(1).unwrap_or("").trim();
-                statement_date = utils::datetime::date_str_to_datetime(date_str);
+/// Tells whether the file is an HDFC statement, or why it is not
+fn recognise(file: &File) -> Result<(), String> {
+    match &file.data {
+        FileData::Table(data) if file.file_type.is_table() => {
+            let first_cell = data.first().and_then(|row| row.first());
+            if first_cell.is_some_and(|cell| cell.contains("HDFC BANK Ltd.")) {
+                Ok(())
+            } else {
+                Err("the first cell does not contain `HDFC BANK Ltd.`".to_string())
             }
-
-            if cell.contains("Statement of accounts") {
-                account_type = AccountType::SavingsAccount;
+        }
+        FileData::Text(data) | FileData::Layout(Layout { text: data, .. })
+            if matches!(file.file_type, FileType::Pdf) =>
+        {
+            match ["Statementofaccount", "HDFCBANKLIMITED"]
+                .iter()
+                .find(|marker| !data.contains(*marker))
+            {
+                Some(marker) => Err(format!("the text does not contain `{}`", marker)),
+                None => Ok(()),
             }
         }
+        _ => Err("the file is neither a table nor a PDF".to_string()),
+    }
+}
 
-        if !account_number.is_empty() && statement_date != current_time {
-            break;
+fn parse_xls(table: &[Vec<String>]) -> Result<Statement, Error> {
+    let account_type = if table
+        .iter()
+        .flatten()
+        .any(|cell| cell.contains("Statement of accounts"))
+    {
+        AccountType::SavingsAccount
+    } else {
+        AccountType::Unknown
+    };
+
+    // Every cell above the transaction table is read as a line of its own
+    let preamble = table
+        .iter()
+        .take_while(|row| *row != &HEADER)
+        .flatten()
+        .map(|cell| cell.trim())
+        .collect::<Vec<&str>>()
+        .join("\n");
+    let details = parse_details(&preamble)?;
+    let summary = parse_summary_xls(table);
+
+    let (transactions, skipped_rows) = parse_trnx_xls(table, summary.opening_balance)?;
+
+    Ok(details.into_statement(account_type, summary, transactions, skipped_rows))
+}
+
+/// Account and period details printed above the transaction table
+struct Details {
+    account_number: Option<String>,
+    ifsc: Option<String>,
+    branch: Option<String>,
+    holder_name: Option<String>,
+    currency: Option<String>,
+    period_start: Option<DateTimeUtc>,
+    period_end: Option<DateTimeUtc>,
+}
+
+impl Details {
+    fn into_statement(
+        self,
+        account_type: AccountType,
+        summary: Summary,
+        transactions: Vec<Transaction>,
+        skipped_rows: Vec<SkippedRow>,
+    ) -> Statement {
+        Statement {
+            transactions,
+            institution: Some(InstitutionName::Hdfc),
+            account_type,
+            masked_account_number: self.account_number.as_deref().map(account_number::mask),
+            account_number: self.account_number,
+            ifsc: self.ifsc,
+            branch: self.branch,
+            holder_name: self.holder_name,
+            currency: self.currency,
+            date: self
+                .period_end
+                .unwrap_or_else(utils::datetime::get_current_datetime),
+            timezone: TIMEZONE,
+            period_start: self.period_start,
+            period_end: self.period_end,
+            opening_balance: summary.opening_balance,
+            closing_balance: summary.closing_balance,
+            totals: summary.totals,
+            source_hash: None,
+            skipped_rows,
+            warnings: Vec::new(),
         }
     }
+}
 
-    let transactions = parse_trnx_xls(table)?;
+/// Balances and totals of the statement summary below the transaction table
+#[derive(Default)]
+struct Summary {
+    opening_balance: Option<Decimal>,
+    closing_balance: Option<Decimal>,
+    totals: Option<StatementTotals>,
+}
 
-    Ok(Statement {
-        transactions,
-        account_type,
-        date: statement_date,
+/// Reads the account details from the text above the transaction table
+///
+/// Spreadsheets keep the spaces between words while PDFs drop them, so any
+/// whitespace in a label is optional.
+fn parse_details(text: &str) -> Result<Details, Error> {
+    fn capture(text: &str, pattern: &str, key: &'static str) -> Result<Option<String>, Error> {
+        Ok(Regex::new(pattern)
+            .map_err(|_| Error::parse(key))?
+            .captures(text)
+            .and_then(|cap| cap.get(1))
+            .map(|m| {
+                m.as_str()
+                    .split_whitespace()
+                    .collect::<Vec<&str>>()
+                    .join(" ")
+            })
+            .filter(|value| !value.is_empty()))
+    }
+
+    let period_start = capture(
+        text,
+        r"Statement\s*From\s*:\s*(\d{2}/\d{2}/\d{2,4})",
+        "error.parser.hdfcind.regex_creation_failed_8",
+    )?
+    .and_then(|date| parse_date(&date).ok());
+    let period_end = capture(
+        text,
+        r"Statement\s*From\s*:.*?To\s*:\s*(\d{2}/\d{2}/\d{2,4})",
+        "error.parser.hdfcind.regex_creation_failed_9",
+    )?
+    .and_then(|date| parse_date(&date).ok());
+
+    Ok(Details {
+        account_number: capture(
+            text,
+            r"Account\s*No\s*:\s*(\d+)",
+            "error.parser.hdfcind.regex_creation_failed_10",
+        )?,
+        ifsc: capture(
+            text,
+            r"IFSC\s*:?\s*([A-Z]{4}0[A-Z0-9]{6})",
+            "error.parser.hdfcind.regex_creation_failed_11",
+        )?,
+        branch: capture(
+            text,
+            r"(?m)Account\s*Branch\s*:\s*(.*?)\s*(?:Address|$)",
+            "error.parser.hdfcind.regex_creation_failed_12",
+        )?,
+        holder_name: capture(
+            text,
+            r"(?m)^\s*((?:MR|MRS|MS|MISS|M/S|DR)\b.*?)\s*(?:Address|JOINT\s*HOLDERS|$)",
+            "error.parser.hdfcind.regex_creation_failed_13",
+        )?,
+        currency: capture(
+            text,
+            r"Currency\s*:\s*([A-Z]{3})",
+            "error.parser.hdfcind.regex_creation_failed_14",
+        )?,
+        period_start,
+        period_end,
     })
 }
 
-fn parse_trnx_xls(table: &Vec<Vec<String>>) -> Result<Vec<Transaction>, String> {
-    let mut transactions = Vec::new();
+/// Reads the statement summary below the transaction table, where the
+/// amounts are in the row after the headings
+fn parse_summary_xls(table: &[Vec<String>]) -> Summary {
+    let value_below = |heading: &str| {
+        table.windows(2).find_map(|rows| {
+            let column = rows[0]
+                .iter()
+                .position(|cell| cell.trim().starts_with(heading))?;
+            rows[1]
+                .get(column)
+                .map(|cell| cell.trim())
+                .filter(|cell| !cell.is_empty())
+        })
+    };
+    let amount_below = |heading: &str| value_below(heading).and_then(parse_summary_amount);
+
+    Summary {
+        opening_balance: amount_below("Opening Balance"),
+        closing_balance: amount_below("Closing Bal"),
+        totals: amount_below("Debits").zip(amount_below("Credits")).map(
+            |(withdrawals, deposits)| StatementTotals {
+                withdrawals,
+                deposits,
+                withdrawal_count: value_below("Dr Count").and_then(|count| count.parse().ok()),
+                deposit_count: value_below("Cr Count").and_then(|count| count.parse().ok()),
+            },
+        ),
+    }
+}
+
+fn parse_summary_amount(amount: &str) -> Option<Decimal> {
+    amount.trim().replace(',', "").parse::<Decimal>().ok()
+}
+
+fn parse_trnx_xls(table: &[Vec<String>], opening_balance: Option<Decimal>) -> Result<Rows, Error> {
+    let mut transactions: Vec<Transaction> = Vec::new();
+    let mut skipped_rows = Vec::new();
 
     let data_start_index = table
         .iter()
-        .position(|row| {
-            row == &[
-                "Date",
-                "Narration",
-                "Chq./Ref.No.",
-                "Value Dt",
-                "Withdrawal Amt.",
-                "Deposit Amt.",
-                "Closing Balance",
-            ]
-        })
-        .ok_or("error.parser.hdfcind.start_of_data_not_found")?
+        .position(|row| row == &HEADER)
+        .ok_or(Error::parse("error.parser.hdfcind.start_of_data_not_found"))?
         + 2;
 
     let data_end_index = table[data_start_index..]
         .iter()
         .position(|row| row == &["", "", "", "", "", "", ""])
-        .ok_or("error.parser.hdfcind.end_of_data_not_found")?
+        .ok_or(Error::parse("error.parser.hdfcind.end_of_data_not_found"))?
         + data_start_index;
 
-    for row in &table[data_start_index..data_end_index] {
-        let date = utils::datetime::date_str_to_datetime(&row[0]);
-        let description = row[1].trim().to_string();
-        let ref_no = row[2].trim().to_string();
-        let withdrawal = row[4].trim().parse::<f32>().unwrap_or(0.0);
-        let deposit = row[5].trim().parse::<f32>().unwrap_or(0.0);
-        let balance = row[6].trim().parse::<f32>().unwrap_or(0.0);
-
-        transactions.push(Transaction {
-            date,
-            description,
-            ref_no,
-            withdrawal,
-            deposit,
-            balance,
-        });
+    for (index, row) in table[data_start_index..data_end_index].iter().enumerate() {
+        let previous = transactions
+            .last()
+            .map(|transaction| transaction.balance)
+            .or(opening_balance);
+        match parse_row(row, previous) {
+            Ok(transaction) => transactions.push(transaction),
+            Err(error) => skipped_rows.push(SkippedRow::from_error(
+                &error.at(Location::Row(data_start_index + index + 1)),
+                row.join(" | "),
+            )),
+        }
+    }
+
+    Ok((transactions, skipped_rows))
+}
+
+/// Reads a row of the transaction table, whose cells are in the order of
+/// [`HEADER`]
+///
+/// A balance that cannot be read is worked out from the previous one.
+///
+/// CSV rows need not have every cell, so a row cut short is an error.
+fn parse_row(cells: &[String], previous: Option<Decimal>) -> Result<Transaction, Error> {
+    let Some([date, description, ref_no, _, withdrawal, deposit, balance]) =
+        cells.get(..HEADER.len())
+    else {
+        return Err(
+            Error::parse("error.parser.hdfcind.incomplete_row").with_reason(cells.join(" | "))
+        );
+    };
+    let date = parse_date(date)?;
+    let withdrawal = parse_amount(withdrawal)?;
+    let deposit = parse_amount(deposit)?;
+    let mut warnings = Vec::new();
+    let balance = derive_balance(
+        parse_balance(balance),
+        previous,
+        withdrawal,
+        deposit,
+        &mut warnings,
+    )?;
+
+    Ok(Transaction {
+        date,
+        description: description.trim().to_string(),
+        ref_no: ref_no.trim().to_string(),
+        withdrawal,
+        deposit,
+        balance,
+        warnings,
+    })
+}
+
+fn parse_date(date: &str) -> Result<DateTimeUtc, Error> {
+    utils::datetime::parse_date(date, &DATE_FORMATS, TIMEZONE)
+}
+
+/// Reads an amount cell, which is empty when the amount is zero
+fn parse_amount(amount: &str) -> Result<Decimal, Error> {
+    let amount = amount.trim();
+    if amount.is_empty() {
+        return Ok(Decimal::ZERO);
     }
+    amount
+        .replace(',', "")
+        .parse::<Decimal>()
+        .map_err(|_| Error::parse("error.parser.hdfcind.invalid_amount").with_reason(amount))
+}
 
-    Ok(transactions)
+/// Reads a balance cell, which every transaction fills
+fn parse_balance(balance: &str) -> Result<Decimal, Error> {
+    if balance.trim().is_empty() {
+        return Err(Error::parse("error.parser.hdfcind.balance_not_found"));
+    }
+    parse_amount(balance).map_err(|error| {
+        Error::parse("error.parser.hdfcind.invalid_balance")
+            .with_reason(error.reason().unwrap_or_default())
+    })
 }
 
-fn parse_pdf(data: &str) -> Result<Statement, String> {
+fn parse_pdf(data: &str, layout: Option<&Layout>) -> Result<Statement, Error> {
     let account_type = if data.contains("Statementofaccount") {
         AccountType::SavingsAccount
     } else {
         AccountType::Unknown
     };
 
-    let date = {
-        Regex::new(r"StatementFrom:.*?To:(\d{2}/\d{2}/\d{4})")
-            .map_err(|_| "error.parser.hdfcind.regex_creation_failed_6")?
-            .captures(&data)
-            .and_then(|cap| cap.get(1))
-            .map(|m| utils::datetime::date_str_to_datetime(m.as_str()))
-            .unwrap_or_else(|| utils::datetime::get_current_datetime())
+    let details = parse_details(data)?;
+    let summary = parse_summary_pdf(data)?;
+
+    // Read the table by column positions when they are known, since the
+    // extracted text runs neighbouring cells together
+    let opening_balance = summary.opening_balance;
+    let (transactions, skipped_rows) = match layout
+        .map(|layout| parse_trnx_layout(layout, opening_balance))
+        .transpose()?
+        .flatten()
+    {
+        Some(rows) => rows,
+        None => parse_trnx_pdf(data, opening_balance)?,
     };
 
-    let transactions = parse_trnx_pdf(&data)?;
+    Ok(details.into_statement(account_type, summary, transactions, skipped_rows))
+}
 
-    Ok(Statement {
-        transactions,
-        account_type,
-        date,
-    })
+/// Reads the statement summary, whose amounts follow its headings as
+/// `opening, debit count, credit count, debits, credits, closing`
+fn parse_summary_pdf(data: &str) -> Result<Summary, Error> {
+    let summary = Regex::new(
+        r"(?s)STATEMENTSUMMARY.*?ClosingBal\S*\s+([\d,]+\.\d{2})\s+(\d+)\s+(\d+)\s+([\d,]+\.\d{2})\s+([\d,]+\.\d{2})\s+([\d,]+\.\d{2})",
+    )
+    .map_err(|_| Error::parse("error.parser.hdfcind.regex_creation_failed_15"))?;
+
+    Ok(summary
+        .captures(data)
+        .map(|cap| Summary {
+            opening_balance: parse_summary_amount(&cap[1]),
+            closing_balance: parse_summary_amount(&cap[6]),
+            totals: parse_summary_amount(&cap[4])
+                .zip(parse_summary_amount(&cap[5]))
+                .map(|(withdrawals, deposits)| StatementTotals {
+                    withdrawals,
+                    deposits,
+                    withdrawal_count: cap[2].parse().ok(),
+                    deposit_count: cap[3].parse().ok(),
+                }),
+        })
+        .unwrap_or_default())
+}
+
+/// Reads the transaction table from the positioned text of the statement
+///
+/// Returns `None` when no header row of the table could be found.
+fn parse_trnx_layout(
+    layout: &Layout,
+    opening_balance: Option<Decimal>,
+) -> Result<Option<Rows>, Error> {
+    let date = Regex::new(r"^\d{2}/\d{2}/\d{2}$")
+        .map_err(|_| Error::parse("error.parser.hdfcind.regex_creation_failed_7"))?;
+
+    let mut found_header = false;
+    let mut boundaries: Option<Vec<f32>> = None;
+    let mut transactions: Vec<Transaction> = Vec::new();
+    let mut skipped_rows: Vec<SkippedRow> = Vec::new();
+    // Whether the last row starting with a date was skipped, so that the
+    // rows continuing it are not taken for the previous transaction's
+    let mut skipped_last = false;
+
+    for row in layout.rows() {
+        // The header is repeated on every page that continues the table
+        if let Some(header) = row.boundaries(&HEADER) {
+            found_header = true;
+            boundaries = Some(header);
+            continue;
+        }
+
+        let Some(columns) = &boundaries else {
+            continue;
+        };
+        let cells = row.cells(columns);
+
+        if date.is_match(&cells[0]) {
+            let row_number = transactions.len() + skipped_rows.len() + 1;
+            let previous = transactions
+                .last()
+                .map(|transaction| transaction.balance)
+                .or(opening_balance);
+            match parse_row(&cells, previous) {
+                Ok(transaction) => {
+                    transactions.push(transaction);
+                    skipped_last = false;
+                }
+                Err(error) => {
+                    skipped_rows.push(SkippedRow::from_error(
+                        &error.at(Location::Row(row_number)),
+                        cells.join(" | "),
+                    ));
+                    skipped_last = true;
+                }
+            }
+            continue;
+        }
+
+        // A long narration wraps onto rows of its own, which start to the
+        // right of the date column and leave the amounts empty
+        let starts_in_date_column = row.runs.first().is_some_and(|run| run.x < columns[0]);
+        let is_continuation =
+            !starts_in_date_column && cells[3..].iter().all(|cell| cell.is_empty());
+        match (transactions.last_mut(), skipped_rows.last_mut()) {
+            (_, Some(skipped)) if is_continuation && skipped_last => {
+                skipped.content.push_str(" | ");
+                skipped.content.push_str(&cells.join(" | "));
+            }
+            (Some(transaction), _) if is_continuation => {
+                transaction.description.push_str(&cells[1]);
+                transaction.ref_no.push_str(&cells[2]);
+            }
+            // Anything else ends the table on this page
+            _ => boundaries = None,
+        }
+    }
+
+    Ok(Some((transactions, skipped_rows)).filter(|_| found_header))
 }
 
-fn parse_trnx_pdf(source: &str) -> Result<Vec<Transaction>, String> {
+fn parse_trnx_pdf(source: &str, opening_balance: Option<Decimal>) -> Result<Rows, Error> {
     let mut data = source.to_string();
 
     // Remove all the sections not a part of the statement
     data = Regex::new(r"(?ms)PageNo.*?Mumbai400013")
-        .map_err(|_| "error.parser.hdfcind.regex_creation_failed_1")?
+        .map_err(|_| Error::parse("error.parser.hdfcind.regex_creation_failed_1"))?
         .replace_all(&data, "")
         .to_string();
 
     data = Regex::new(r"(?ms)STATEMENTSUMMARY.*")
-        .map_err(|_| "error.parser.hdfcind.regex_creation_failed_2")?
+        .map_err(|_| Error::parse("error.parser.hdfcind.regex_creation_failed_2"))?
         .replace_all(&data, "")
         .to_string();
 
     data = Regex::new(r"(?ms)\nDate.*ClosingBalance ")
-        .map_err(|_| "error.parser.hdfcind.regex_creation_failed_3")?
+        .map_err(|_| Error::parse("error.parser.hdfcind.regex_creation_failed_3"))?
         .replace_all(&data, "")
         .to_string();
 
     // Build statement, one transaction at a time
-    let mut statements = Vec::new();
+    let mut statements: Vec<Transaction> = Vec::new();
+    let mut skipped_rows = Vec::new();
 
     // The statement is split into two lines
     let line1 = Regex::new(r"(?ms)\d{2}/\d{2}/\d{2}.*?\d{2}/\d{2}/\d{2}")
-        .map_err(|_| "error.parser.hdfcind.regex_creation_failed_4")?;
+        .map_err(|_| Error::parse("error.parser.hdfcind.regex_creation_failed_4"))?;
     let line2 = Regex::new(r"(?s).*?(\d{2}/\d{2}/\d{2})")
-        .map_err(|_| "error.parser.hdfcind.regex_creation_failed_5")?;
+        .map_err(|_| Error::parse("error.parser.hdfcind.regex_creation_failed_5"))?;
+    let split = |text: &str| -> Vec<String> {
+        text.trim()
+            .split('\n')
+            .map(|part| part.trim().to_string())
+            .collect()
+    };
 
     while !data.is_empty() {
-        let mut description = String::new();
-        let mut withdrawal = 0.0;
-        let mut deposit = 0.0;
-        let mut balance = 0.0;
+        let row = Location::Row(statements.len() + skipped_rows.len() + 1);
 
         // Capture transaction's 1st line
-        let capture1 = line1
-            .find(&data)
-            .ok_or("error.parser.hdfcind.line1_not_found")?;
-
-        let parts1: Vec<&str> = capture1
-            .as_str()
-            .trim()
-            .split('\n')
-            .map(|part| part.trim())
-            .collect();
-        let date = utils::datetime::date_str_to_datetime(&parts1[0]);
-        let ref_no = parts1[2].to_string();
-        description.push_str(parts1[1]);
-
-        data = (&data[capture1.end()..]).to_string();
+        let Some(capture1) = line1.find(&data) else {
+            let error = Error::parse("error.parser.hdfcind.line1_not_found").at(row);
+            // Without a single transaction, the table itself was not found
+            if statements.is_empty() {
+                return Err(error);
+            }
+            skipped_rows.push(SkippedRow::from_error(&error, split(&data).join(" | ")));
+            break;
+        };
+        let parts1 = split(capture1.as_str());
+        data = data[capture1.end()..].to_string();
 
         // Capture transaction's 2nd line
-        let remaining_data;
-        let parts2: Vec<&str> = if let Some(capture2) = line2.find(&data) {
-            remaining_data = (&data[capture2.end() - 8..]).to_string();
-            capture2.as_str()[..capture2.end() - 8]
-                .trim()
-                .split('\n')
-                .map(|part| part.trim())
-                .collect()
+        let parts2 = if let Some(capture2) = line2.find(&data) {
+            let parts2 = split(&capture2.as_str()[..capture2.end() - 8]);
+            data = data[capture2.end() - 8..].to_string();
+            parts2
         } else {
-            remaining_data = "".to_string();
-            data.trim().split('\n').map(|part| part.trim()).collect()
+            let parts2 = split(&data);
+            data = "".to_string();
+            parts2
         };
 
-        let number_collection = parts2[0].replace(",", "");
-        if let Some(dot_index) = number_collection.chars().position(|c| c == '.') {
-            if dot_index + 2 < number_collection.len() - 1 {
-                deposit = number_collection[..dot_index + 3]
-                    .parse::<f32>()
-                    .unwrap_or(0.0);
-
-                balance = number_collection[dot_index + 1..]
-                    .parse::<f32>()
-                    .unwrap_or(0.0);
-
-                description.push_str(&parts2[1..].join(""));
-            } else {
-                balance = parts2[1].replace(",", "").parse::<f32>().unwrap_or(0.0);
-                withdrawal = number_collection.parse::<f32>().unwrap_or(0.0);
-                description.push_str(&parts2[2..].join(""));
-            }
+        let previous = statements
+            .last()
+            .map(|transaction| transaction.balance)
+            .or(opening_balance);
+        match parse_pdf_row(&parts1, &parts2, previous) {
+            Ok(transaction) => statements.push(transaction),
+            Err(error) => skipped_rows.push(SkippedRow::from_error(
+                &error.at(row),
+                [parts1, parts2].concat().join(" | "),
+            )),
         }
-
-        data = remaining_data;
-
-        // Build the statement
-        statements.push(Transaction {
-            date,
-            description,
-            ref_no,
-            withdrawal,
-            deposit,
-            balance,
-        });
     }
 
-    Ok(statements)
+    Ok((statements, skipped_rows))
+}
+
+/// Reads a transaction from its two lines of text: the date, narration and
+/// reference first, then the amounts run together with the rest of the
+/// narration
+fn parse_pdf_row(
+    parts1: &[String],
+    parts2: &[String],
+    previous: Option<Decimal>,
+) -> Result<Transaction, Error> {
+    // A line cut short by a page break lacks the narration or reference
+    let [date, description, ref_no, ..] = parts1 else {
+        return Err(
+            Error::parse("error.parser.hdfcind.incomplete_line").with_reason(parts1.join(" | "))
+        );
+    };
+    let date = parse_date(date)?;
+    let ref_no = ref_no.to_string();
+    let mut description = description.to_string();
+    let mut withdrawal = Decimal::ZERO;
+    let mut deposit = Decimal::ZERO;
+    let mut warnings = Vec::new();
+
+    let amounts = parts2.first().map(String::as_str).unwrap_or_default();
+    let number_collection = amounts.replace(",", "");
+    let Some(dot_index) = number_collection.chars().position(|c| c == '.') else {
+        return Err(Error::parse("error.parser.hdfcind.amounts_not_found").with_reason(amounts));
+    };
+    let balance = if dot_index + 2 < number_collection.len() - 1 {
+        deposit = parse_amount(&number_collection[..dot_index + 3])?;
+        description.push_str(&parts2[1..].join(""));
+        parse_balance(&number_collection[dot_index + 3..])
+    } else {
+        withdrawal = parse_amount(&number_collection)?;
+        description.push_str(&parts2.get(2..).unwrap_or_default().join(""));
+        parse_balance(parts2.get(1).map(String::as_str).unwrap_or_default())
+    };
+    let balance = derive_balance(balance, previous, withdrawal, deposit, &mut warnings)?;
+
+    // Build the statement
+    Ok(Transaction {
+        date,
+        description,
+        ref_no,
+        withdrawal,
+        deposit,
+        balance,
+        warnings,
+    })
 }
 
 #[cfg(test)]
@@ -1,25 +1,38 @@
+use crate::utils::datetime::{parse_date, DateFormat, IST};
 use sea_orm::{prelude::DateTimeUtc, sqlx::types::chrono::Utc};
 
+/// Start of the day in India Standard Time, where statement dates fall
+pub fn _date(date: &str) -> DateTimeUtc {
+    parse_date(date, &[DateFormat::DD_MM_YY, DateFormat::DD_MM_YYYY], IST).unwrap()
+}
+
 pub fn _today_date_str(date: DateTimeUtc) -> String {
-    return date
-        .date_naive()
+    date.date_naive()
         .and_hms_opt(0, 0, 0)
         .unwrap()
         .and_local_timezone(Utc)
         .unwrap()
-        .to_string();
+        .to_string()
 }
 
 #[cfg(test)]
 mod xls {
     use crate::{
-        models::entities::sea_orm_active_enums::AccountType,
+        error::Location,
+        models::entities::sea_orm_active_enums::{AccountType, InstitutionName},
         readers::{
-            parsers::sources::hdfcind::{get_parser, tests::_today_date_str},
+            get_statement_from_file_content,
+            parsers::{
+                sources::hdfcind::{
+                    tests::{_date, _today_date_str},
+                    HdfcInd,
+                },
+                types::{StatementParser, StatementTotals},
+            },
             types::{File, FileData, FileType},
         },
-        utils,
     };
+    use rust_decimal_macros::dec;
     use sea_orm::sqlx::types::chrono::Utc;
 
     #[test]
@@ -32,8 +45,22 @@ mod xls {
             ]),
         };
 
-        let parser = get_parser();
-        assert!(parser.identify(&file).unwrap());
+        let parser = HdfcInd;
+        assert!(parser.identify(&file).unwrap().is_match());
+    }
+
+    #[test]
+    fn test_identify_valid_xlsx_file() {
+        let file = File {
+            file_type: FileType::Xlsx,
+            data: FileData::Table(vec![
+                vec!["HDFC BANK Ltd.".to_string()],
+                vec!["Some other data".to_string()],
+            ]),
+        };
+
+        let parser = HdfcInd;
+        assert!(parser.identify(&file).unwrap().is_match());
     }
 
     #[test]
@@ -46,8 +73,8 @@ mod xls {
             ]),
         };
 
-        let parser = get_parser();
-        assert!(!parser.identify(&file).unwrap());
+        let parser = HdfcInd;
+        assert!(!parser.identify(&file).unwrap().is_match());
     }
 
     #[test]
@@ -90,33 +117,122 @@ mod xls {
             ]),
         };
 
-        let parser = get_parser();
+        let parser = HdfcInd;
         let statements = parser.parse(&file).unwrap();
 
-        assert_eq!(
-            statements.date,
-            utils::datetime::date_str_to_datetime(&"02/01/2021")
-        );
+        assert_eq!(statements.date, _date("02/01/2021"));
 
         let transactions = &statements.transactions;
         assert_eq!(transactions.len(), 2);
-        assert_eq!(
-            transactions[0].date,
-            utils::datetime::date_str_to_datetime(&"01/01/2021")
-        );
+        assert_eq!(transactions[0].date, _date("01/01/2021"));
         assert_eq!(transactions[0].description, "Description 1");
-        assert_eq!(transactions[0].withdrawal, 100.0);
-        assert_eq!(transactions[0].deposit, 0.0);
-        assert_eq!(transactions[0].balance, 900.0);
+        assert_eq!(transactions[0].withdrawal, dec!(100.0));
+        assert_eq!(transactions[0].deposit, dec!(0.0));
+        assert_eq!(transactions[0].balance, dec!(900.0));
+
+        assert_eq!(transactions[1].date, _date("02/01/2021"));
+        assert_eq!(transactions[1].description, "Description 2");
+        assert_eq!(transactions[1].withdrawal, dec!(0.0));
+        assert_eq!(transactions[1].deposit, dec!(200.0));
+        assert_eq!(transactions[1].balance, dec!(1100.0));
+    }
+
+    #[test]
+    fn test_parse_metadata() {
+        let header = [
+            "Date",
+            "Narration",
+            "Chq./Ref.No.",
+            "Value Dt",
+            "Withdrawal Amt.",
+            "Deposit Amt.",
+            "Closing Balance",
+        ];
+        let file = File {
+            file_type: FileType::Xls,
+            data: FileData::Table(vec![
+                vec![
+                    "HDFC BANK Ltd.".to_string(),
+                    "Page No .: 1".to_string(),
+                    "Statement of accounts".to_string(),
+                ],
+                vec![
+                    "MR  TESTER   TESTER".to_string(),
+                    "Account Branch : MUMBAI - FORT".to_string(),
+                ],
+                vec![
+                    "JOINT HOLDERS :".to_string(),
+                    "OD Limit : 0.00 Currency : INR".to_string(),
+                ],
+                vec![
+                    "".to_string(),
+                    "Account No : 50100123456789   PRIME POTENTIAL".to_string(),
+                ],
+                vec![
+                    "".to_string(),
+                    "RTGS/NEFT IFSC : HDFC0000001   MICR : 400240001".to_string(),
+                ],
+                vec!["Statement From : 01/01/2021 To  : 31/01/2021".to_string()],
+                header.iter().map(|cell| cell.to_string()).collect(),
+                vec!["***".to_string()],
+                vec![
+                    "01/01/21".to_string(),
+                    "Description 1".to_string(),
+                    "".to_string(),
+                    "".to_string(),
+                    "100.0".to_string(),
+                    "".to_string(),
+                    "900.0".to_string(),
+                ],
+                vec!["".to_string(); 7],
+                vec!["STATEMENT SUMMARY  :-".to_string()],
+                vec![
+                    "Opening Balance".to_string(),
+                    "Dr Count".to_string(),
+                    "Cr Count".to_string(),
+                    "Debits".to_string(),
+                    "Credits".to_string(),
+                    "Closing Bal".to_string(),
+                ],
+                vec![
+                    "1,000.00".to_string(),
+                    "1".to_string(),
+                    "0".to_string(),
+                    "100.00".to_string(),
+                    "0.00".to_string(),
+                    "900.00".to_string(),
+                ],
+            ]),
+        };
+
+        let parser = HdfcInd;
+        let statement = parser.parse(&file).unwrap();
 
+        assert_eq!(statement.institution, Some(InstitutionName::Hdfc));
+        assert_eq!(statement.account_number.as_deref(), Some("50100123456789"));
         assert_eq!(
-            transactions[1].date,
-            utils::datetime::date_str_to_datetime(&"02/01/2021")
+            statement.masked_account_number.as_deref(),
+            Some("XXXXXXXXXX6789")
         );
-        assert_eq!(transactions[1].description, "Description 2");
-        assert_eq!(transactions[1].withdrawal, 0.0);
-        assert_eq!(transactions[1].deposit, 200.0);
-        assert_eq!(transactions[1].balance, 1100.0);
+        assert_eq!(statement.ifsc.as_deref(), Some("HDFC0000001"));
+        assert_eq!(statement.branch.as_deref(), Some("MUMBAI - FORT"));
+        assert_eq!(statement.holder_name.as_deref(), Some("MR TESTER TESTER"));
+        assert_eq!(statement.currency.as_deref(), Some("INR"));
+        assert_eq!(statement.period_start, Some(_date("01/01/2021")));
+        assert_eq!(statement.period_end, Some(_date("31/01/2021")));
+        assert_eq!(statement.date, _date("31/01/2021"));
+        assert_eq!(statement.opening_balance, Some(dec!(1000.00)));
+        assert_eq!(statement.closing_balance, Some(dec!(900.00)));
+        assert_eq!(
+            statement.totals,
+            Some(StatementTotals {
+                withdrawals: dec!(100.00),
+                deposits: dec!(0.00),
+                withdrawal_count: Some(1),
+                deposit_count: Some(0),
+            })
+        );
+        assert_eq!(statement.transactions.len(), 1);
     }
 
     #[test]
@@ -147,7 +263,7 @@ mod xls {
             ]),
         };
 
-        let parser = get_parser();
+        let parser = HdfcInd;
         let statements = parser.parse(&file).unwrap();
 
         assert_eq!(
@@ -164,9 +280,9 @@ mod xls {
             data: FileData::Table(vec![vec!["Some other data".to_string()]]),
         };
 
-        let parser = get_parser();
+        let parser = HdfcInd;
         assert_eq!(
-            parser.parse(&file).unwrap_err(),
+            parser.parse(&file).unwrap_err().key(),
             "error.parser.hdfcind.start_of_data_not_found"
         );
     }
@@ -198,30 +314,158 @@ mod xls {
             ]),
         };
 
-        let parser = get_parser();
+        let parser = HdfcInd;
         assert_eq!(
-            parser.parse(&file).unwrap_err(),
+            parser.parse(&file).unwrap_err().key(),
             "error.parser.hdfcind.end_of_data_not_found"
         );
     }
+
+    #[test]
+    fn test_parse_invalid_date() {
+        let file = File {
+            file_type: FileType::Xls,
+            data: FileData::Table(vec![
+                vec![
+                    "Date".to_string(),
+                    "Narration".to_string(),
+                    "Chq./Ref.No.".to_string(),
+                    "Value Dt".to_string(),
+                    "Withdrawal Amt.".to_string(),
+                    "Deposit Amt.".to_string(),
+                    "Closing Balance".to_string(),
+                ],
+                vec!["***".to_string()],
+                vec![
+                    "01/01/21".to_string(),
+                    "Description 1".to_string(),
+                    "".to_string(),
+                    "".to_string(),
+                    "100.0".to_string(),
+                    "0.0".to_string(),
+                    "900.0".to_string(),
+                ],
+                vec![
+                    "31/02/21".to_string(),
+                    "Description 2".to_string(),
+                    "".to_string(),
+                    "".to_string(),
+                    "0.0".to_string(),
+                    "200.0".to_string(),
+                    "1100.0".to_string(),
+                ],
+                vec!["".to_string(); 7],
+            ]),
+        };
+
+        // The rest of the table is still read
+        let parser = HdfcInd;
+        let statement = parser.parse(&file).unwrap();
+        assert_eq!(statement.transactions.len(), 1);
+
+        let skipped = &statement.skipped_rows[0];
+        assert_eq!(skipped.key, "error.utils.datetime.parse_date");
+        assert_eq!(skipped.location, Some(Location::Row(4)));
+        assert_eq!(
+            skipped.content,
+            "31/02/21 | Description 2 |  |  | 0.0 | 200.0 | 1100.0"
+        );
+    }
+    #[test]
+    fn test_parse_short_csv_row() {
+        let content = [
+            "HDFC BANK Ltd.",
+            "Date,Narration,Chq./Ref.No.,Value Dt,Withdrawal Amt.,Deposit Amt.,Closing Balance",
+            "********,********,********,********,********,********,********",
+            "01/01/21,Description 1,,01/01/21,100.0,,900.0",
+            "02/01/21,Description 2",
+            "03/01/21,Description 3,,03/01/21,,200.0,1100.0",
+            ",,,,,,",
+        ]
+        .join("\n");
+
+        // The row without amounts is left out instead of failing the statement
+        let statement = get_statement_from_file_content(content.into_bytes(), "").unwrap();
+        assert_eq!(statement.transactions.len(), 2);
+        assert_eq!(statement.transactions[1].balance, dec!(1100.0));
+
+        let skipped = &statement.skipped_rows[0];
+        assert_eq!(skipped.key, "error.parser.hdfcind.incomplete_row");
+        assert_eq!(skipped.location, Some(Location::Row(5)));
+        assert_eq!(skipped.content, "02/01/21 | Description 2");
+    }
 }
 
 #[cfg(test)]
 mod pdf {
     use crate::{
-        models::entities::sea_orm_active_enums::AccountType,
+        error::Location,
+        models::entities::sea_orm_active_enums::{AccountType, InstitutionName},
         readers::{
-            parsers::sources::hdfcind::{get_parser, tests::_today_date_str},
-            types::{File, FileData, FileType},
+            parsers::{
+                sources::hdfcind::{
+                    tests::{_date, _today_date_str},
+                    HdfcInd,
+                },
+                types::{StatementParser, StatementTotals},
+            },
+            types::{File, FileData, FileType, Layout, TextRun},
         },
-        utils,
     };
+    use rust_decimal_macros::dec;
     use sea_orm::sqlx::types::chrono::Utc;
 
     fn _common_pdf_data() -> FileData {
         FileData::Text("\nDate \nNarration \nChq./Ref.No. \nValueDt \nWithdrawalAmt. \nDepositAmt. \nClosingBalance \n01/01/23 \nUPI-TESTUSER-TEST@BANK \n0000000000000001 \n01/01/23 \n1,000.00 \n10,000.00 \nTEST-TRANSACTION-1 \n02/01/23 \nNEFT-TESTBANK-TESTUSER \n0000000000000002 \n02/01/23 \n500.00 \n9,500.00 \n03/01/23 \nPOS-TESTSHOP-TESTCITY \n0000000000000003 \n03/01/23 \n200.009,700.00 \nTEST-TRANSACTION-3\n\nTestMore\nPageNo.:1Statementofaccount \nMR.Tester TesterAddress JOINTHOLDERS: Holder1 Nomination:Nomination1 StatementFrom:01/04/1900To:31/03/1910 \nAccountBranch:Branch Address ODLimit:10Currency:INR Email:email@example.com CustID:12345 AccountNo:123456789 A/COpenDate:11/01/1900 AccountStatus:Regular RTGS/NEFTIFSC :HDFC0000001MICR:1000000 BranchCode:000ProductCode:100 HDFCBANKLIMITED *Closingbalanceincludesfundsearmarkedforholdandunclearedfunds Contentsofthisstatementwillbeconsideredcorrectifnoerrorisreportedwithin30daysofreceiptofstatement.TheaddressonthisstatementisthatonrecordwiththeBankasatthedayofrequesting thisstatement. StateaccountbranchGSTN:12345 HDFCBankGSTINnumberdetailsareavailableathttps://www.hdfcbank.com/personal/making-payments/online-tax-payment/goods-and-service-tax. RegisteredOfficeAddress:HDFCBankHouse,SenapatiBapatMarg,LowerParel,Mumbai400013".to_string())
     }
 
+    fn _run(x: f32, y: f32, text: &str) -> TextRun {
+        TextRun {
+            page: 1,
+            x,
+            y,
+            width: text.len() as f32 * 5.0,
+            font_size: 10.0,
+            text: text.to_string(),
+        }
+    }
+
+    fn _layout_pdf_data() -> FileData {
+        FileData::Layout(Layout {
+            text: "Statementofaccount StatementFrom:01/04/1900To:31/03/1910 HDFCBANKLIMITED"
+                .to_string(),
+            runs: vec![
+                _run(30.0, 700.0, "Date"),
+                _run(80.0, 700.0, "Narration"),
+                _run(250.0, 700.0, "Chq./Ref.No."),
+                _run(330.0, 700.0, "Value"),
+                _run(360.0, 700.0, "Dt"),
+                _run(400.0, 700.0, "Withdrawal"),
+                _run(455.0, 700.0, "Amt."),
+                _run(480.0, 700.0, "Deposit"),
+                _run(520.0, 700.0, "Amt."),
+                _run(550.0, 700.0, "Closing"),
+                _run(590.0, 700.0, "Balance"),
+                _run(30.0, 685.0, "01/01/23"),
+                _run(80.0, 685.0, "UPI-TESTUSER-TEST@BANK"),
+                _run(250.0, 685.0, "0000000000000001"),
+                _run(330.0, 685.0, "01/01/23"),
+                _run(415.0, 685.0, "1,000.00"),
+                _run(560.0, 685.0, "10,000.00"),
+                _run(80.0, 675.0, "TEST-TRANSACTION-1"),
+                _run(30.0, 665.0, "02/01/23"),
+                _run(80.0, 665.0, "NEFT-TESTBANK-TESTUSER"),
+                _run(250.0, 665.0, "0000000000000002"),
+                _run(330.0, 665.0, "02/01/23"),
+                _run(510.0, 665.0, "500.00"),
+                _run(565.0, 665.0, "10,500.00"),
+                _run(30.0, 640.0, "STATEMENT SUMMARY"),
+                _run(30.0, 630.0, "05/01/23"),
+                _run(420.0, 630.0, "5.00"),
+            ],
+        })
+    }
+
     #[test]
     fn test_identify_valid_file() {
         let file = File {
@@ -229,8 +473,19 @@ mod pdf {
             data: _common_pdf_data(),
         };
 
-        let parser = get_parser();
-        assert!(parser.identify(&file).unwrap());
+        let parser = HdfcInd;
+        assert!(parser.identify(&file).unwrap().is_match());
+    }
+
+    #[test]
+    fn test_identify_valid_layout() {
+        let file = File {
+            file_type: FileType::Pdf,
+            data: _layout_pdf_data(),
+        };
+
+        let parser = HdfcInd;
+        assert!(parser.identify(&file).unwrap().is_match());
     }
 
     #[test]
@@ -240,8 +495,8 @@ mod pdf {
             data: FileData::Text("Some other bank".to_string()),
         };
 
-        let parser = get_parser();
-        assert!(!parser.identify(&file).unwrap());
+        let parser = HdfcInd;
+        assert!(!parser.identify(&file).unwrap().is_match());
     }
 
     #[test]
@@ -251,52 +506,100 @@ mod pdf {
             data: _common_pdf_data(),
         };
 
-        let parser = get_parser();
+        let parser = HdfcInd;
         let statements = parser.parse(&file).unwrap();
 
         assert_eq!(statements.account_type, AccountType::SavingsAccount);
-        assert_eq!(statements.date.to_string(), "1910-03-31 00:00:00 UTC");
+        assert_eq!(statements.date.to_string(), "1910-03-30 18:30:00 UTC");
 
         assert_eq!(statements.transactions.len(), 3);
 
         let transaction = &statements.transactions[0];
-        assert_eq!(
-            transaction.date,
-            utils::datetime::date_str_to_datetime(&"01/01/23")
-        );
+        assert_eq!(transaction.date, _date("01/01/23"));
         assert_eq!(
             transaction.description,
             "UPI-TESTUSER-TEST@BANKTEST-TRANSACTION-1"
         );
         assert_eq!(transaction.ref_no, "0000000000000001");
-        assert_eq!(transaction.withdrawal, 1000.0);
-        assert_eq!(transaction.deposit, 0.0);
-        assert_eq!(transaction.balance, 10000.0);
+        assert_eq!(transaction.withdrawal, dec!(1000.0));
+        assert_eq!(transaction.deposit, dec!(0.0));
+        assert_eq!(transaction.balance, dec!(10000.0));
 
         let transaction = &statements.transactions[1];
-        assert_eq!(
-            transaction.date,
-            utils::datetime::date_str_to_datetime(&"02/01/23")
-        );
+        assert_eq!(transaction.date, _date("02/01/23"));
         assert_eq!(transaction.description, "NEFT-TESTBANK-TESTUSER");
         assert_eq!(transaction.ref_no, "0000000000000002");
-        assert_eq!(transaction.withdrawal, 500.0);
-        assert_eq!(transaction.deposit, 0.0);
-        assert_eq!(transaction.balance, 9500.0);
+        assert_eq!(transaction.withdrawal, dec!(500.0));
+        assert_eq!(transaction.deposit, dec!(0.0));
+        assert_eq!(transaction.balance, dec!(9500.0));
 
         let transaction = &statements.transactions[2];
-        assert_eq!(
-            transaction.date,
-            utils::datetime::date_str_to_datetime(&"03/01/23")
-        );
+        assert_eq!(transaction.date, _date("03/01/23"));
         assert_eq!(
             transaction.description,
             "POS-TESTSHOP-TESTCITYTEST-TRANSACTION-3TestMore"
         );
         assert_eq!(transaction.ref_no, "0000000000000003");
-        assert_eq!(transaction.withdrawal, 0.0);
-        assert_eq!(transaction.deposit, 200.0);
-        assert_eq!(transaction.balance, 9700.0);
+        assert_eq!(transaction.withdrawal, dec!(0.0));
+        assert_eq!(transaction.deposit, dec!(200.0));
+        assert_eq!(transaction.balance, dec!(9700.0));
+    }
+
+    #[test]
+    fn test_parse_metadata() {
+        let file = File {
+            file_type: FileType::Pdf,
+            data: _common_pdf_data(),
+        };
+
+        let parser = HdfcInd;
+        let statement = parser.parse(&file).unwrap();
+
+        assert_eq!(statement.institution, Some(InstitutionName::Hdfc));
+        assert_eq!(statement.account_number.as_deref(), Some("123456789"));
+        assert_eq!(
+            statement.masked_account_number.as_deref(),
+            Some("XXXXX6789")
+        );
+        assert_eq!(statement.ifsc.as_deref(), Some("HDFC0000001"));
+        assert_eq!(statement.branch.as_deref(), Some("Branch"));
+        assert_eq!(statement.holder_name.as_deref(), Some("MR.Tester Tester"));
+        assert_eq!(statement.currency.as_deref(), Some("INR"));
+        assert_eq!(statement.period_start, Some(_date("01/04/1900")));
+        assert_eq!(statement.period_end, Some(_date("31/03/1910")));
+        assert_eq!(statement.opening_balance, None);
+        assert_eq!(statement.closing_balance, None);
+    }
+
+    #[test]
+    fn test_parse_summary() {
+        let text = match _common_pdf_data() {
+            FileData::Text(text) => text,
+            _ => unreachable!(),
+        };
+        let file = File {
+            file_type: FileType::Pdf,
+            data: FileData::Text(text.replace(
+                "\n\nTestMore",
+                "\n\nTestMore\nSTATEMENTSUMMARY:- \nOpeningBalance DrCount CrCount Debits Credits ClosingBal \n11,000.00 1 1 1,000.00 200.00 10,200.00 \n",
+            )),
+        };
+
+        let parser = HdfcInd;
+        let statement = parser.parse(&file).unwrap();
+
+        assert_eq!(statement.opening_balance, Some(dec!(11000.00)));
+        assert_eq!(statement.closing_balance, Some(dec!(10200.00)));
+        assert_eq!(
+            statement.totals,
+            Some(StatementTotals {
+                withdrawals: dec!(1000.00),
+                deposits: dec!(200.00),
+                withdrawal_count: Some(1),
+                deposit_count: Some(1),
+            })
+        );
+        assert_eq!(statement.transactions.len(), 3);
     }
 
     #[test]
@@ -306,7 +609,7 @@ mod pdf {
                 data: FileData::Text("\nDate \nNarration \nChq./Ref.No. \nValueDt \nWithdrawalAmt. \nDepositAmt. \nClosingBalance \n01/01/23 \nUPI-TESTUSER-TEST@BANK \n0000000000000001 \n01/01/23 \n1,000.00 \n10,000.00 \nTEST-TRANSACTION-1 \nPageNo.:1 \nMR.Tester TesterAddress JOINTHOLDERS: Holder1 Nomination:Nomination1 StatementFrom:01/04/1900To:A1/03/1910 \nAccountBranch:Branch Address ODLimit:10Currency:INR Email:email@example.com CustID:12345 A/COpenDate:11/01/1900 AccountStatus:Regular RTGS/NEFTIFSC :HDFC0000001MICR:1000000 BranchCode:000ProductCode:100 HDFCBANKLIMITED *Closingbalanceincludesfundsearmarkedforholdandunclearedfunds Contentsofthisstatementwillbeconsideredcorrectifnoerrorisreportedwithin30daysofreceiptofstatement.TheaddressonthisstatementisthatonrecordwiththeBankasatthedayofrequesting thisstatement. StateaccountbranchGSTN:12345 HDFCBankGSTINnumberdetailsareavailableathttps://www.hdfcbank.com/personal/making-payments/online-tax-payment/goods-and-service-tax. RegisteredOfficeAddress:HDFCBankHouse,SenapatiBapatMarg,LowerParel,Mumbai400013".to_string()),
             };
 
-        let parser = get_parser();
+        let parser = HdfcInd;
         let statements = parser.parse(&file).unwrap();
 
         assert_eq!(statements.account_type, AccountType::Unknown);
@@ -323,10 +626,106 @@ mod pdf {
                 data: FileData::Text("\n1,000.00 \n10,000.00 \nTEST-TRANSACTION-1 \n \nNEFT-TESTBANK-TESTUSER \n0000000000000002 \n021/23 \n500.00 \n9,500.00 \n03/0123 \nPOS-TESTSHOP-TESTCITY \n0000000000000003 \n001/23 \n200.009,700.00 \nTEST-TRANSACTION-3\n\nTestMore\nPageNo.:1Statementofaccount \nMR.Tester TesterAddress JOINTHOLDERS: Holder1 Nomination:Nomination1 StatementFrom:01/04/1900To:3/1910 \nAccountBranch:Branch Address ODLimit:10Currency:INR Email:email@example.com CustID:12345 AccountNo:123456789 A/COpenDate:11/01/1900 AccountStatus:Regular RTGS/NEFTIFSC :HDFC0000001MICR:1000000 BranchCode:000ProductCode:100 HDFCBANKLIMITED *Closingbalanceincludesfundsearmarkedforholdandunclearedfunds Contentsofthisstatementwillbeconsideredcorrectifnoerrorisreportedwithin30daysofreceiptofstatement.TheaddressonthisstatementisthatonrecordwiththeBankasatthedayofrequesting thisstatement. StateaccountbranchGSTN:12345 HDFCBankGSTINnumberdetailsareavailableathttps://www.hdfcbank.com/personal/making-payments/online-tax-payment/goods-and-service-tax. RegisteredOfficeAddress:HDFCBankHouse,SenapatiBapatMarg,LowerParel,Mumbai400013".to_string()),
             };
 
-        let parser = get_parser();
+        let parser = HdfcInd;
         assert_eq!(
-            parser.parse(&file).unwrap_err(),
+            parser.parse(&file).unwrap_err().key(),
             "error.parser.hdfcind.line1_not_found"
         );
     }
+
+    #[test]
+    fn test_parse_deposit_with_paise() {
+        let text = match _common_pdf_data() {
+            FileData::Text(text) => text,
+            _ => unreachable!(),
+        };
+        let file = File {
+            file_type: FileType::Pdf,
+            data: FileData::Text(text.replace("200.009,700.00", "200.509,700.50")),
+        };
+
+        let parser = HdfcInd;
+        let statement = parser.parse(&file).unwrap();
+
+        let transaction = &statement.transactions[2];
+        assert_eq!(transaction.deposit, dec!(200.50));
+        assert_eq!(transaction.balance, dec!(9700.50));
+        assert!(transaction.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_parse_skips_short_lines() {
+        let text = match _common_pdf_data() {
+            FileData::Text(text) => text,
+            _ => unreachable!(),
+        };
+        let file = File {
+            file_type: FileType::Pdf,
+            data: FileData::Text(text.replace(
+                "02/01/23 \nNEFT-TESTBANK-TESTUSER \n0000000000000002 \n02/01/23",
+                "02/01/23 \n02/01/23",
+            )),
+        };
+
+        let parser = HdfcInd;
+        let statement = parser.parse(&file).unwrap();
+
+        assert_eq!(statement.transactions.len(), 2);
+        let skipped = &statement.skipped_rows[0];
+        assert_eq!(skipped.key, "error.parser.hdfcind.incomplete_line");
+        assert_eq!(skipped.location, Some(Location::Row(2)));
+        assert_eq!(skipped.reason.as_deref(), Some("02/01/23 | 02/01/23"));
+    }
+
+    #[test]
+    fn test_parse_layout() {
+        let file = File {
+            file_type: FileType::Pdf,
+            data: _layout_pdf_data(),
+        };
+
+        let parser = HdfcInd;
+        let statement = parser.parse(&file).unwrap();
+
+        assert_eq!(statement.account_type, AccountType::SavingsAccount);
+        assert_eq!(statement.date.to_string(), "1910-03-30 18:30:00 UTC");
+        assert_eq!(statement.transactions.len(), 2);
+
+        let transaction = &statement.transactions[0];
+        assert_eq!(transaction.date, _date("01/01/23"));
+        assert_eq!(
+            transaction.description,
+            "UPI-TESTUSER-TEST@BANKTEST-TRANSACTION-1"
+        );
+        assert_eq!(transaction.ref_no, "0000000000000001");
+        assert_eq!(transaction.withdrawal, dec!(1000.0));
+        assert_eq!(transaction.deposit, dec!(0.0));
+        assert_eq!(transaction.balance, dec!(10000.0));
+
+        let transaction = &statement.transactions[1];
+        assert_eq!(transaction.description, "NEFT-TESTBANK-TESTUSER");
+        assert_eq!(transaction.withdrawal, dec!(0.0));
+        assert_eq!(transaction.deposit, dec!(500.0));
+        assert_eq!(transaction.balance, dec!(10500.0));
+    }
+
+    #[test]
+    fn test_parse_layout_without_positions() {
+        let text = match _common_pdf_data() {
+            FileData::Text(text) => text,
+            _ => unreachable!(),
+        };
+        let file = File {
+            file_type: FileType::Pdf,
+            data: FileData::Layout(Layout {
+                text,
+                runs: Vec::new(),
+            }),
+        };
+
+        let parser = HdfcInd;
+        let statement = parser.parse(&file).unwrap();
+
+        assert_eq!(statement.transactions.len(), 3);
+    }
 }
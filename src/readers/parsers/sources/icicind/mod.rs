@@ -1,20 +1,609 @@
-use crate::readers::{
-    parsers::types::{Parser, Statement},
-    types::{File, FileType},
+use regex::Regex;
+use sea_orm::{
+    prelude::{DateTimeUtc, Decimal},
+    sqlx::types::chrono::FixedOffset,
 };
 
-pub fn get_parser() -> Parser {
-    fn identify(file: &File) -> Result<bool, String> {
-        if !matches!(file.file_type, FileType::Xls) {
-            return Ok(false);
+use crate::{
+    error::{Error, Location},
+    models::entities::sea_orm_active_enums::{AccountType, InstitutionName},
+    readers::{
+        parsers::types::{
+            derive_balance, Confidence, SkippedRow, Statement, StatementParser, Transaction,
+        },
+        types::{File, FileData, FileType, Layout, Row},
+    },
+    utils::{
+        self, account_number,
+        datetime::{DateFormat, IST},
+    },
+};
+
+/// Column headings of the transaction table, in the order ICICI prints them.
+const HEADER: [&str; 8] = [
+    "S No.",
+    "Value Date",
+    "Transaction Date",
+    "Cheque Number",
+    "Transaction Remarks",
+    "Withdrawal Amount (INR )",
+    "Deposit Amount (INR )",
+    "Balance (INR )",
+];
+
+/// Dates are printed as `dd/mm/yyyy`, but workbooks may hold them as dates
+const DATE_FORMATS: [DateFormat; 3] = [
+    DateFormat::DD_MM_YYYY,
+    DateFormat::DD_MMM_YYYY,
+    DateFormat::ExcelSerial,
+];
+
+const TIMEZONE: FixedOffset = IST;
+
+/// The transactions read from a table, and the rows that could not be read
+type Rows = (Vec<Transaction>, Vec<SkippedRow>);
+
+/// Statements of ICICI Bank savings accounts, as XLS or PDF
+pub struct IciciInd;
+
+impl StatementParser for IciciInd {
+    fn name(&self) -> &str {
+        "icicind"
+    }
+
+    fn institution(&self) -> Option<InstitutionName> {
+        Some(InstitutionName::Icici)
+    }
+
+    fn file_types(&self) -> &[FileType] {
+        &[
+            FileType::Xls,
+            FileType::Xlsx,
+            FileType::Xlsb,
+            FileType::Ods,
+            FileType::Csv,
+            FileType::Pdf,
+        ]
+    }
+
+    fn version(&self) -> u32 {
+        1
+    }
+
+    fn identify(&self, file: &File) -> Result<Confidence, Error> {
+        Ok(match recognise(file) {
+            Ok(()) => Confidence::CERTAIN,
+            Err(_) => Confidence::NONE,
+        })
+    }
+
+    fn explain_mismatch(&self, file: &File) -> Option<String> {
+        recognise(file).err()
+    }
+
+    fn parse(&self, file: &File) -> Result<Statement, Error> {
+        let data = &file.data;
+        match data {
+            FileData::Table(data) => parse_xls(data),
+            FileData::Text(data) => parse_pdf(data, None),
+            FileData::Layout(layout) => parse_pdf(&layout.text, Some(layout)),
+            FileData::Tree(_) => Err(Error::parser_mismatch(
+                "error.parser.icicind.unsupported_file_data",
+            )),
+        }
+    }
+}
+
+/// Tells whether the file is an ICICI statement, or why it is not
+fn recognise(file: &File) -> Result<(), String> {
+    match &file.data {
+        FileData::Table(data) if file.file_type.is_table() => match find_header(data) {
+            Some(_) => Ok(()),
+            None => Err("no row holds the headings of the transaction table".to_string()),
+        },
+        FileData::Text(data) | FileData::Layout(Layout { text: data, .. })
+            if matches!(file.file_type, FileType::Pdf) =>
+        {
+            let squashed = squash(data);
+            if !squashed.contains("ICICIBank") {
+                Err("the text does not contain `ICICI Bank`".to_string())
+            } else if !squashed.contains(&squash(&HEADER.concat())) {
+                Err("the text does not contain the headings of the transaction table".to_string())
+            } else {
+                Ok(())
+            }
+        }
+        _ => Err("the file is neither a table nor a PDF".to_string()),
+    }
+}
+
+/// Removes all whitespace, since PDF text extraction does not keep it reliably
+fn squash(text: &str) -> String {
+    text.chars().filter(|c| !c.is_whitespace()).collect()
+}
+
+/// Finds the header row of the transaction table and the column it starts at
+fn find_header(table: &[Vec<String>]) -> Option<(usize, usize)> {
+    table.iter().enumerate().find_map(|(row_index, row)| {
+        row.windows(HEADER.len())
+            .position(|cells| cells.iter().map(|cell| cell.trim()).eq(HEADER))
+            .map(|column_index| (row_index, column_index))
+    })
+}
+
+fn parse_date(date: &str) -> Result<DateTimeUtc, Error> {
+    utils::datetime::parse_date(date, &DATE_FORMATS, TIMEZONE)
+}
+
+/// Reads an amount cell, which is empty when the amount is zero
+fn parse_amount(amount: &str) -> Result<Decimal, Error> {
+    let amount = amount.trim();
+    if amount.is_empty() {
+        return Ok(Decimal::ZERO);
+    }
+    amount
+        .replace(',', "")
+        .parse::<Decimal>()
+        .map_err(|_| Error::parse("error.parser.icicind.invalid_amount").with_reason(amount))
+}
+
+/// Reads the cells of a transaction, working out a balance that cannot be
+/// read from the previous one
+fn parse_transaction(cells: [&str; 6], previous: Option<Decimal>) -> Result<Transaction, Error> {
+    let [date, description, ref_no, withdrawal, deposit, balance] = cells;
+    let date = parse_date(date)?;
+    let withdrawal = parse_amount(withdrawal)?;
+    let deposit = parse_amount(deposit)?;
+    let mut warnings = Vec::new();
+    let balance = match balance.trim() {
+        "" => Err(Error::parse("error.parser.icicind.balance_not_found")),
+        balance => parse_amount(balance)
+            .map_err(|_| Error::parse("error.parser.icicind.invalid_balance").with_reason(balance)),
+    };
+    let balance = derive_balance(balance, previous, withdrawal, deposit, &mut warnings)?;
+
+    Ok(Transaction {
+        date,
+        description: description.trim().to_string(),
+        ref_no: parse_ref_no(ref_no),
+        withdrawal,
+        deposit,
+        balance,
+        warnings,
+    })
+}
+
+fn parse_ref_no(ref_no: &str) -> String {
+    match ref_no.trim() {
+        "-" => String::new(),
+        ref_no => ref_no.to_string(),
+    }
+}
+
+/// Account and period details printed above the transaction table
+struct Details {
+    account_type: AccountType,
+    account_number: Option<String>,
+    ifsc: Option<String>,
+    branch: Option<String>,
+    holder_name: Option<String>,
+    period_start: Option<DateTimeUtc>,
+    period_end: Option<DateTimeUtc>,
+    opening_balance: Option<Decimal>,
+    closing_balance: Option<Decimal>,
+}
+
+impl Details {
+    fn into_statement(self, (transactions, skipped_rows): Rows) -> Statement {
+        // Some statements print the account number masked
+        let (account_number, masked_account_number) = match self.account_number {
+            Some(number) if account_number::is_masked(&number) => (None, Some(number)),
+            number => (number.clone(), number.as_deref().map(account_number::mask)),
+        };
+
+        Statement {
+            transactions,
+            institution: Some(InstitutionName::Icici),
+            account_type: self.account_type,
+            account_number,
+            masked_account_number,
+            ifsc: self.ifsc,
+            branch: self.branch,
+            holder_name: self.holder_name,
+            currency: None,
+            date: self
+                .period_end
+                .unwrap_or_else(utils::datetime::get_current_datetime),
+            timezone: TIMEZONE,
+            period_start: self.period_start,
+            period_end: self.period_end,
+            opening_balance: self.opening_balance,
+            closing_balance: self.closing_balance,
+            totals: None,
+            source_hash: None,
+            skipped_rows,
+            warnings: Vec::new(),
+        }
+    }
+}
+
+/// Reads the account details from the text above the transaction table
+///
+/// Several details can share a line, so a value ends where the next label
+/// starts. Spreadsheets keep the spaces between words while PDFs drop them,
+/// so any whitespace in a label is optional.
+fn parse_details(text: &str) -> Result<Details, Error> {
+    let capture = |pattern: &str, key: &'static str| -> Result<Option<String>, Error> {
+        Ok(Regex::new(pattern)
+            .map_err(|_| Error::parse(key))?
+            .captures(text)
+            .and_then(|cap| cap.get(1))
+            .map(|m| {
+                m.as_str()
+                    .split_whitespace()
+                    .collect::<Vec<&str>>()
+                    .join(" ")
+            })
+            .filter(|value| !value.is_empty()))
+    };
+    let balance = |pattern: &str, key: &'static str| -> Result<Option<Decimal>, Error> {
+        Ok(capture(pattern, key)?.and_then(|balance| parse_amount(&balance).ok()))
+    };
+
+    let account_type = capture(
+        r"(?i)account\s*type\s*:?\s*(savings|current)",
+        "error.parser.icicind.regex_creation_failed_1",
+    )?
+    .map(|account_type| match account_type.to_lowercase().as_str() {
+        "savings" => AccountType::SavingsAccount,
+        _ => AccountType::CheckingAccount,
+    })
+    .unwrap_or(AccountType::Unknown);
+
+    let period = Regex::new(r"(?i)from\s*:?\s*(\d{2}/\d{2}/\d{4})\s*to\s*:?\s*(\d{2}/\d{2}/\d{4})")
+        .map_err(|_| Error::parse("error.parser.icicind.regex_creation_failed_2"))?
+        .captures(text);
+    let period_start = period.as_ref().and_then(|cap| parse_date(&cap[1]).ok());
+    let period_end = period.as_ref().and_then(|cap| parse_date(&cap[2]).ok());
+
+    Ok(Details {
+        account_type,
+        period_start,
+        period_end,
+        account_number: capture(
+            r"(?i)Account\s*(?:Number|No\.?)\s*:?\s*([\dXx*]{6,})",
+            "error.parser.icicind.regex_creation_failed_9",
+        )?,
+        ifsc: capture(
+            r"IFSC?\s*(?:Code)?\s*:?\s*([A-Z]{4}0[A-Z0-9]{6})",
+            "error.parser.icicind.regex_creation_failed_10",
+        )?,
+        branch: capture(
+            r"(?im)Branch(?:\s*Name)?\s*:\s*(.*?)\s*(?:IFS|Account|Customer|Transaction|$)",
+            "error.parser.icicind.regex_creation_failed_11",
+        )?,
+        holder_name: capture(
+            r"(?im)(?:Account|Customer)\s*Name\s*:\s*(.*?)\s*(?:Branch|IFS|Account|Customer|Transaction|$)",
+            "error.parser.icicind.regex_creation_failed_12",
+        )?,
+        opening_balance: balance(
+            r"(?i)Opening\s*Balance\s*(?:\(\s*INR\s*\))?\s*:?\s*(-?[\d,]+\.\d{2})",
+            "error.parser.icicind.regex_creation_failed_13",
+        )?,
+        closing_balance: balance(
+            r"(?i)Closing\s*Balance\s*(?:\(\s*INR\s*\))?\s*:?\s*(-?[\d,]+\.\d{2})",
+            "error.parser.icicind.regex_creation_failed_14",
+        )?,
+    })
+}
+
+fn parse_xls(table: &[Vec<String>]) -> Result<Statement, Error> {
+    let header =
+        find_header(table).ok_or(Error::parse("error.parser.icicind.start_of_data_not_found"))?;
+
+    let preamble = table[..header.0]
+        .iter()
+        .map(|row| row.join(" "))
+        .collect::<Vec<String>>()
+        .join("\n");
+    let details = parse_details(&preamble)?;
+    let rows = parse_trnx_xls(table, header, details.opening_balance);
+
+    Ok(details.into_statement(rows))
+}
+
+/// Reads the transaction rows below the header row, given as its index and
+/// the column the table starts at
+fn parse_trnx_xls(
+    table: &[Vec<String>],
+    (header_index, column): (usize, usize),
+    opening_balance: Option<Decimal>,
+) -> Rows {
+    let mut transactions: Vec<Transaction> = Vec::new();
+    let mut skipped_rows = Vec::new();
+
+    // The table ends at the first row without a serial number, which is
+    // either a blank row or the "Legends" section below the transactions
+    for (index, row) in table.iter().enumerate().skip(header_index + 1) {
+        let cells = match row.get(column..column + HEADER.len()) {
+            Some(cells) => cells,
+            None => break,
+        };
+
+        if cells[0].trim().parse::<f64>().is_err() {
+            break;
         }
 
-        return Ok(false);
+        let previous = transactions
+            .last()
+            .map(|transaction| transaction.balance)
+            .or(opening_balance);
+        match parse_transaction(
+            [
+                &cells[2], &cells[4], &cells[3], &cells[5], &cells[6], &cells[7],
+            ],
+            previous,
+        ) {
+            Ok(transaction) => transactions.push(transaction),
+            Err(error) => skipped_rows.push(SkippedRow::from_error(
+                &error.at(Location::Row(index + 1)),
+                cells.join(" | "),
+            )),
+        }
     }
 
-    fn parse(_: &File) -> Result<Statement, String> {
-        todo!()
+    (transactions, skipped_rows)
+}
+
+fn parse_pdf(data: &str, layout: Option<&Layout>) -> Result<Statement, Error> {
+    let details = parse_details(data)?;
+
+    // Read the table by column positions when they are known, since the
+    // extracted text loses which cell a wrapped remark belongs to
+    let opening_balance = details.opening_balance;
+    let rows = match layout
+        .map(|layout| parse_trnx_layout(layout, opening_balance))
+        .transpose()?
+        .flatten()
+    {
+        Some(rows) => rows,
+        None => parse_trnx_pdf(data, opening_balance)?,
+    };
+
+    Ok(details.into_statement(rows))
+}
+
+/// A transaction row of the table, with the remarks wrapped around it
+struct Record<'a> {
+    row: &'a Row,
+    cells: Vec<String>,
+    remarks: Vec<(f32, String)>,
+}
+
+/// Reads the transaction table from the positioned text of the statement
+///
+/// Returns `None` when no header row of the table could be found.
+fn parse_trnx_layout(
+    layout: &Layout,
+    opening_balance: Option<Decimal>,
+) -> Result<Option<Rows>, Error> {
+    let serial = Regex::new(r"^\d+$")
+        .map_err(|_| Error::parse("error.parser.icicind.regex_creation_failed_7"))?;
+    let page_number = Regex::new(r"(?i)^Page\s*\d+\s*of\s*\d+$")
+        .map_err(|_| Error::parse("error.parser.icicind.regex_creation_failed_8"))?;
+
+    let rows = layout.rows();
+    let mut found_header = false;
+    let mut boundaries: Option<Vec<f32>> = None;
+    let mut records: Vec<Record> = Vec::new();
+    let mut wrapped: Vec<(&Row, String)> = Vec::new();
+
+    for row in &rows {
+        // The header is repeated on every page that continues the table
+        if let Some(header) = row.boundaries(&HEADER) {
+            found_header = true;
+            boundaries = Some(header);
+            continue;
+        }
+
+        let Some(columns) = &boundaries else {
+            continue;
+        };
+        let cells = row.cells(columns);
+
+        if page_number.is_match(cells.join(" ").trim()) {
+            continue;
+        }
+
+        if serial.is_match(&cells[0]) {
+            let remarks = vec![(row.y, cells[4].clone())];
+            records.push(Record {
+                row,
+                cells,
+                remarks,
+            });
+        } else if cells
+            .iter()
+            .enumerate()
+            .all(|(column, cell)| column == 4 || cell.is_empty())
+        {
+            wrapped.push((row, cells[4].clone()));
+        } else {
+            // Anything else, such as the legends, ends the table on this page
+            boundaries = None;
+        }
+    }
+
+    // Long remarks wrap above and below the rest of their row, so each
+    // wrapped line belongs to the nearest row of its page
+    for (row, remark) in wrapped {
+        let nearest = records
+            .iter_mut()
+            .filter(|record| record.row.page == row.page)
+            .min_by(|a, b| (a.row.y - row.y).abs().total_cmp(&(b.row.y - row.y).abs()));
+        if let Some(record) = nearest {
+            record.remarks.push((row.y, remark));
+        }
     }
 
-    Parser { identify, parse }
+    let mut transactions: Vec<Transaction> = Vec::new();
+    let mut skipped_rows = Vec::new();
+    for (index, mut record) in records.into_iter().enumerate() {
+        record.remarks.sort_by(|a, b| b.0.total_cmp(&a.0));
+        let description = record
+            .remarks
+            .iter()
+            .map(|(_, remark)| remark.as_str())
+            .filter(|remark| !remark.is_empty())
+            .collect::<Vec<&str>>()
+            .join(" ");
+
+        let cells = &record.cells;
+        let previous = transactions
+            .last()
+            .map(|transaction| transaction.balance)
+            .or(opening_balance);
+        match parse_transaction(
+            [
+                &cells[2],
+                &description,
+                &cells[3],
+                &cells[5],
+                &cells[6],
+                &cells[7],
+            ],
+            previous,
+        ) {
+            Ok(transaction) => transactions.push(transaction),
+            Err(error) => skipped_rows.push(SkippedRow::from_error(
+                &error.at(Location::Row(index + 1)),
+                cells.join(" | "),
+            )),
+        }
+    }
+
+    Ok(Some((transactions, skipped_rows)).filter(|_| found_header))
+}
+
+fn parse_trnx_pdf(source: &str, opening_balance: Option<Decimal>) -> Result<Rows, Error> {
+    let serial = Regex::new(r"^\d+$")
+        .map_err(|_| Error::parse("error.parser.icicind.regex_creation_failed_3"))?;
+    let date = Regex::new(r"^\d{2}/\d{2}/\d{4}$")
+        .map_err(|_| Error::parse("error.parser.icicind.regex_creation_failed_4"))?;
+    let amount = Regex::new(r"^-?[\d,]+\.\d{2}$")
+        .map_err(|_| Error::parse("error.parser.icicind.regex_creation_failed_5"))?;
+    let page_number = Regex::new(r"(?i)^Page\s*\d+\s*of\s*\d+$")
+        .map_err(|_| Error::parse("error.parser.icicind.regex_creation_failed_6"))?;
+
+    let header: Vec<String> = HEADER.iter().map(|heading| squash(heading)).collect();
+
+    // Every cell is extracted on its own line. Drop the page furniture so
+    // that only the cells of the transaction table remain.
+    let lines: Vec<&str> = source
+        .split('\n')
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .collect();
+
+    let start = lines
+        .windows(header.len())
+        .position(|cells| {
+            cells
+                .iter()
+                .map(|cell| squash(cell))
+                .eq(header.iter().cloned())
+        })
+        .ok_or(Error::parse("error.parser.icicind.start_of_data_not_found"))?
+        + header.len();
+
+    let mut tokens: Vec<&str> = Vec::new();
+    let mut index = start;
+    while index < lines.len() {
+        let line = lines[index];
+        if squash(line).starts_with("Legends") {
+            break;
+        }
+
+        if lines[index..].get(..header.len()).is_some_and(|cells| {
+            cells
+                .iter()
+                .map(|cell| squash(cell))
+                .eq(header.iter().cloned())
+        }) {
+            index += header.len();
+            continue;
+        }
+
+        if !page_number.is_match(line) {
+            tokens.push(line);
+        }
+        index += 1;
+    }
+
+    // A transaction starts with its serial number, value date and transaction date
+    let is_record_start = |index: usize| {
+        index + 2 < tokens.len()
+            && serial.is_match(tokens[index])
+            && date.is_match(tokens[index + 1])
+            && date.is_match(tokens[index + 2])
+    };
+
+    let starts: Vec<usize> = (0..tokens.len()).filter(|&i| is_record_start(i)).collect();
+    if starts.first() != Some(&0) && !tokens.is_empty() {
+        return Err(Error::parse("error.parser.icicind.record_not_found"));
+    }
+
+    let mut transactions: Vec<Transaction> = Vec::new();
+    let mut skipped_rows = Vec::new();
+    for (position, &record_start) in starts.iter().enumerate() {
+        let record_end = starts.get(position + 1).copied().unwrap_or(tokens.len());
+        let record = &tokens[record_start..record_end];
+        let previous = transactions
+            .last()
+            .map(|transaction| transaction.balance)
+            .or(opening_balance);
+
+        let transaction = if record.len() < 7 {
+            Err(Error::parse("error.parser.icicind.incomplete_record"))
+        } else {
+            // Remarks can wrap over several lines, on either side of the amounts
+            let details = &record[4..];
+            details
+                .windows(3)
+                .position(|cells| cells.iter().all(|cell| amount.is_match(cell)))
+                .ok_or(Error::parse("error.parser.icicind.amounts_not_found"))
+                .and_then(|amounts_index| {
+                    let description = details[..amounts_index]
+                        .iter()
+                        .chain(&details[amounts_index + 3..])
+                        .copied()
+                        .collect::<Vec<&str>>()
+                        .join(" ");
+                    parse_transaction(
+                        [
+                            record[2],
+                            &description,
+                            record[3],
+                            details[amounts_index],
+                            details[amounts_index + 1],
+                            details[amounts_index + 2],
+                        ],
+                        previous,
+                    )
+                })
+        };
+
+        match transaction {
+            Ok(transaction) => transactions.push(transaction),
+            Err(error) => skipped_rows.push(SkippedRow::from_error(
+                &error.at(Location::Row(position + 1)),
+                record.join(" | "),
+            )),
+        }
+    }
+
+    Ok((transactions, skipped_rows))
 }
+
+#[cfg(test)]
+mod tests;
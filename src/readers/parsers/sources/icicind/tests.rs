@@ -1,2 +1,466 @@
+use crate::utils::datetime::{parse_date, DateFormat, IST};
+use sea_orm::{prelude::DateTimeUtc, sqlx::types::chrono::Utc};
+
+/// Start of the day in India Standard Time, where statement dates fall
+pub fn _date(date: &str) -> DateTimeUtc {
+    parse_date(date, &[DateFormat::DD_MM_YY, DateFormat::DD_MM_YYYY], IST).unwrap()
+}
+
+pub fn _today_date_str(date: DateTimeUtc) -> String {
+    date.date_naive()
+        .and_hms_opt(0, 0, 0)
+        .unwrap()
+        .and_local_timezone(Utc)
+        .unwrap()
+        .to_string()
+}
+
 #[cfg(test)]
-mod tests {}
+mod xls {
+    use crate::{
+        models::entities::sea_orm_active_enums::AccountType,
+        readers::{
+            parsers::{
+                sources::icicind::{
+                    tests::{_date, _today_date_str},
+                    IciciInd,
+                },
+                types::StatementParser,
+            },
+            types::{File, FileData, FileType},
+        },
+    };
+    use rust_decimal_macros::dec;
+    use sea_orm::sqlx::types::chrono::Utc;
+
+    fn _header_row() -> Vec<String> {
+        vec![
+            "".to_string(),
+            "S No.".to_string(),
+            "Value Date".to_string(),
+            "Transaction Date".to_string(),
+            "Cheque Number".to_string(),
+            "Transaction Remarks".to_string(),
+            "Withdrawal Amount (INR )".to_string(),
+            "Deposit Amount (INR )".to_string(),
+            "Balance (INR )".to_string(),
+        ]
+    }
+
+    #[test]
+    fn test_identify_valid_file() {
+        let file = File {
+            file_type: FileType::Xls,
+            data: FileData::Table(vec![
+                vec!["".to_string(), "DETAILED STATEMENT".to_string()],
+                _header_row(),
+            ]),
+        };
+
+        let parser = IciciInd;
+        assert!(parser.identify(&file).unwrap().is_match());
+    }
+
+    #[test]
+    fn test_identify_invalid_file_content() {
+        let file = File {
+            file_type: FileType::Xls,
+            data: FileData::Table(vec![
+                vec!["HDFC BANK Ltd.".to_string()],
+                vec!["Some other data".to_string()],
+            ]),
+        };
+
+        let parser = IciciInd;
+        assert!(!parser.identify(&file).unwrap().is_match());
+    }
+
+    #[test]
+    fn test_parse_valid_file() {
+        let file = File {
+            file_type: FileType::Xls,
+            data: FileData::Table(vec![
+                vec!["".to_string(), "DETAILED STATEMENT".to_string()],
+                vec![
+                    "".to_string(),
+                    "Account Type : Savings".to_string(),
+                    "Account Number : 000401234567".to_string(),
+                ],
+                vec![
+                    "".to_string(),
+                    "Account Name : MR TEST USER".to_string(),
+                    "Branch : TEST BRANCH, MUMBAI".to_string(),
+                    "IFSC Code : ICIC0000004".to_string(),
+                ],
+                vec![
+                    "".to_string(),
+                    "Opening Balance : 1,000.00".to_string(),
+                    "Closing Balance : 2,100.50".to_string(),
+                ],
+                vec![
+                    "".to_string(),
+                    "Transaction Period : From 01/01/2021 To 31/01/2021".to_string(),
+                ],
+                _header_row(),
+                vec![
+                    "".to_string(),
+                    "1".to_string(),
+                    "01/01/2021".to_string(),
+                    "01/01/2021".to_string(),
+                    "-".to_string(),
+                    "UPI/123456789012/Payment".to_string(),
+                    "100".to_string(),
+                    "0".to_string(),
+                    "900".to_string(),
+                ],
+                vec![
+                    "".to_string(),
+                    "2".to_string(),
+                    "03/01/2021".to_string(),
+                    "02/01/2021".to_string(),
+                    "000123".to_string(),
+                    "NEFT-SALARY".to_string(),
+                    "0".to_string(),
+                    "1,200.50".to_string(),
+                    "2,100.50".to_string(),
+                ],
+                vec!["".to_string(); 9],
+                vec![
+                    "".to_string(),
+                    "Legends Used in Account Statement".to_string(),
+                ],
+            ]),
+        };
+
+        let parser = IciciInd;
+        let statement = parser.parse(&file).unwrap();
+
+        assert_eq!(statement.account_type, AccountType::SavingsAccount);
+        assert_eq!(statement.date, _date("31/01/2021"));
+        assert_eq!(statement.account_number.as_deref(), Some("000401234567"));
+        assert_eq!(
+            statement.masked_account_number.as_deref(),
+            Some("XXXXXXXX4567")
+        );
+        assert_eq!(statement.holder_name.as_deref(), Some("MR TEST USER"));
+        assert_eq!(statement.branch.as_deref(), Some("TEST BRANCH, MUMBAI"));
+        assert_eq!(statement.ifsc.as_deref(), Some("ICIC0000004"));
+        assert_eq!(statement.opening_balance, Some(dec!(1000.00)));
+        assert_eq!(statement.closing_balance, Some(dec!(2100.50)));
+        assert_eq!(statement.period_start, Some(_date("01/01/2021")));
+        assert_eq!(statement.period_end, Some(_date("31/01/2021")));
+
+        let transactions = &statement.transactions;
+        assert_eq!(transactions.len(), 2);
+        assert_eq!(transactions[0].date, _date("01/01/2021"));
+        assert_eq!(transactions[0].description, "UPI/123456789012/Payment");
+        assert_eq!(transactions[0].ref_no, "");
+        assert_eq!(transactions[0].withdrawal, dec!(100.0));
+        assert_eq!(transactions[0].deposit, dec!(0.0));
+        assert_eq!(transactions[0].balance, dec!(900.0));
+
+        assert_eq!(transactions[1].date, _date("02/01/2021"));
+        assert_eq!(transactions[1].description, "NEFT-SALARY");
+        assert_eq!(transactions[1].ref_no, "000123");
+        assert_eq!(transactions[1].withdrawal, dec!(0.0));
+        assert_eq!(transactions[1].deposit, dec!(1200.5));
+        assert_eq!(transactions[1].balance, dec!(2100.5));
+    }
+
+    #[test]
+    fn test_parse_current_account() {
+        let file = File {
+            file_type: FileType::Xls,
+            data: FileData::Table(vec![
+                vec!["Account Type : Current".to_string()],
+                _header_row(),
+            ]),
+        };
+
+        let parser = IciciInd;
+        let statement = parser.parse(&file).unwrap();
+
+        assert_eq!(statement.account_type, AccountType::CheckingAccount);
+        assert!(statement.transactions.is_empty());
+    }
+
+    #[test]
+    fn test_parse_without_required_data() {
+        let file = File {
+            file_type: FileType::Xls,
+            data: FileData::Table(vec![
+                _header_row(),
+                vec![
+                    "".to_string(),
+                    "1".to_string(),
+                    "01/01/2021".to_string(),
+                    "01/01/2021".to_string(),
+                    "-".to_string(),
+                    "Description 1".to_string(),
+                    "100".to_string(),
+                    "0".to_string(),
+                    "900".to_string(),
+                ],
+            ]),
+        };
+
+        let parser = IciciInd;
+        let statement = parser.parse(&file).unwrap();
+
+        assert_eq!(statement.transactions.len(), 1);
+        assert_eq!(statement.account_type, AccountType::Unknown);
+        assert_eq!(_today_date_str(statement.date), _today_date_str(Utc::now()));
+    }
+
+    #[test]
+    fn test_parse_missing_data_start() {
+        let file = File {
+            file_type: FileType::Xls,
+            data: FileData::Table(vec![vec!["Some other data".to_string()]]),
+        };
+
+        let parser = IciciInd;
+        assert_eq!(
+            parser.parse(&file).unwrap_err().key(),
+            "error.parser.icicind.start_of_data_not_found"
+        );
+    }
+}
+
+#[cfg(test)]
+mod pdf {
+    use crate::{
+        error::Location,
+        models::entities::sea_orm_active_enums::AccountType,
+        readers::{
+            parsers::{
+                sources::icicind::{tests::_date, IciciInd},
+                types::StatementParser,
+            },
+            types::{File, FileData, FileType, Layout, TextRun},
+        },
+    };
+    use rust_decimal_macros::dec;
+
+    fn _run(page: u32, x: f32, y: f32, text: &str) -> TextRun {
+        TextRun {
+            page,
+            x,
+            y,
+            width: text.len() as f32 * 5.0,
+            font_size: 10.0,
+            text: text.to_string(),
+        }
+    }
+
+    fn _header(page: u32, y: f32) -> Vec<TextRun> {
+        vec![
+            _run(page, 20.0, y, "S No."),
+            _run(page, 60.0, y, "Value Date"),
+            _run(page, 120.0, y, "Transaction Date"),
+            _run(page, 210.0, y, "Cheque Number"),
+            _run(page, 290.0, y, "Transaction Remarks"),
+            _run(page, 400.0, y + 2.0, "Withdrawal Amount"),
+            _run(page, 400.0, y, "(INR )"),
+            _run(page, 500.0, y + 2.0, "Deposit Amount"),
+            _run(page, 500.0, y, "(INR )"),
+            _run(page, 580.0, y, "Balance (INR )"),
+        ]
+    }
+
+    fn _layout_pdf_data() -> FileData {
+        let mut runs = _header(1, 700.0);
+        runs.extend([
+            // The remarks wrap around the middle of their row
+            _run(1, 290.0, 688.0, "UPI/300112345678/"),
+            _run(1, 20.0, 680.0, "1"),
+            _run(1, 60.0, 680.0, "01/01/2023"),
+            _run(1, 120.0, 680.0, "01/01/2023"),
+            _run(1, 210.0, 680.0, "-"),
+            _run(1, 290.0, 680.0, "TESTUSER@BANK/"),
+            _run(1, 400.0, 680.0, "1,000.00"),
+            _run(1, 500.0, 680.0, "0.00"),
+            _run(1, 580.0, 680.0, "10,000.00"),
+            _run(1, 290.0, 672.0, "PAYMENT"),
+            _run(1, 20.0, 650.0, "2"),
+            _run(1, 60.0, 650.0, "02/01/2023"),
+            _run(1, 120.0, 650.0, "02/01/2023"),
+            _run(1, 210.0, 650.0, "000045"),
+            _run(1, 290.0, 650.0, "CLG/TESTUSER"),
+            _run(1, 400.0, 650.0, "0.00"),
+            _run(1, 500.0, 650.0, "500.00"),
+            _run(1, 580.0, 650.0, "10,500.00"),
+            _run(1, 280.0, 100.0, "Page 1 of 2"),
+        ]);
+        runs.extend(_header(2, 700.0));
+        runs.extend([
+            _run(2, 20.0, 680.0, "3"),
+            _run(2, 60.0, 680.0, "04/01/2023"),
+            _run(2, 120.0, 680.0, "03/01/2023"),
+            _run(2, 210.0, 680.0, "-"),
+            _run(2, 290.0, 680.0, "NEFT-TESTBANK"),
+            _run(2, 400.0, 680.0, "ten"),
+            _run(2, 500.0, 680.0, "0.00"),
+            _run(2, 580.0, 680.0, "10,300.00"),
+            _run(2, 20.0, 640.0, "Legends Used in Account Statement"),
+            _run(2, 290.0, 620.0, "ICICI Bank Limited"),
+        ]);
+
+        FileData::Layout(Layout {
+            text: "DETAILED STATEMENT \nAccountType:Savings \nTransactionPeriod:From01/01/2023To31/01/2023 \nICICI Bank Limited".to_string(),
+            runs,
+        })
+    }
+
+    fn _common_pdf_data() -> FileData {
+        FileData::Text("DETAILED STATEMENT \nAccountType:Savings \nAccountNumber:XXXXXXXX4567 \nAccountName:MR TEST USER \nIFSCCode:ICIC0000004 \nOpeningBalance:11,000.00 \nClosingBalance:10,300.00 \nTransactionPeriod:From01/01/2023To31/01/2023 \nS No. \nValue Date \nTransaction Date \nCheque Number \nTransaction Remarks \nWithdrawal Amount (INR ) \nDeposit Amount (INR ) \nBalance (INR ) \n1 \n01/01/2023 \n01/01/2023 \n- \nUPI/300112345678/ \nTESTUSER@BANK \n1,000.00 \n0.00 \n10,000.00 \n2 \n02/01/2023 \n02/01/2023 \n000045 \nCLG/TESTUSER \n0.00 \n500.00 \n10,500.00 \nPage 1 of 2 \nS No. \nValue Date \nTransaction Date \nCheque Number \nTransaction Remarks \nWithdrawal Amount (INR ) \nDeposit Amount (INR ) \nBalance (INR ) \n3 \n04/01/2023 \n03/01/2023 \n- \nNEFT-TESTBANK \n200.00 \n0.00 \n10,300.00 \nTEST-TRANSACTION-3 \nPage 2 of 2 \nLegends Used in Account Statement \nICICI Bank Limited".to_string())
+    }
+
+    #[test]
+    fn test_identify_valid_file() {
+        let file = File {
+            file_type: FileType::Pdf,
+            data: _common_pdf_data(),
+        };
+
+        let parser = IciciInd;
+        assert!(parser.identify(&file).unwrap().is_match());
+    }
+
+    #[test]
+    fn test_identify_invalid_file_content() {
+        let file = File {
+            file_type: FileType::Pdf,
+            data: FileData::Text("Statementofaccount HDFCBANKLIMITED".to_string()),
+        };
+
+        let parser = IciciInd;
+        assert!(!parser.identify(&file).unwrap().is_match());
+    }
+
+    #[test]
+    fn test_parse_valid_file() {
+        let file = File {
+            file_type: FileType::Pdf,
+            data: _common_pdf_data(),
+        };
+
+        let parser = IciciInd;
+        let statement = parser.parse(&file).unwrap();
+
+        assert_eq!(statement.account_type, AccountType::SavingsAccount);
+        assert_eq!(statement.date.to_string(), "2023-01-30 18:30:00 UTC");
+        // The statement prints the account number masked
+        assert_eq!(statement.account_number, None);
+        assert_eq!(
+            statement.masked_account_number.as_deref(),
+            Some("XXXXXXXX4567")
+        );
+        assert_eq!(statement.holder_name.as_deref(), Some("MR TEST USER"));
+        assert_eq!(statement.ifsc.as_deref(), Some("ICIC0000004"));
+        assert_eq!(statement.opening_balance, Some(dec!(11000.00)));
+        assert_eq!(statement.closing_balance, Some(dec!(10300.00)));
+
+        assert_eq!(statement.transactions.len(), 3);
+
+        let transaction = &statement.transactions[0];
+        assert_eq!(transaction.date, _date("01/01/2023"));
+        assert_eq!(transaction.description, "UPI/300112345678/ TESTUSER@BANK");
+        assert_eq!(transaction.ref_no, "");
+        assert_eq!(transaction.withdrawal, dec!(1000.0));
+        assert_eq!(transaction.deposit, dec!(0.0));
+        assert_eq!(transaction.balance, dec!(10000.0));
+
+        let transaction = &statement.transactions[1];
+        assert_eq!(transaction.date, _date("02/01/2023"));
+        assert_eq!(transaction.description, "CLG/TESTUSER");
+        assert_eq!(transaction.ref_no, "000045");
+        assert_eq!(transaction.withdrawal, dec!(0.0));
+        assert_eq!(transaction.deposit, dec!(500.0));
+        assert_eq!(transaction.balance, dec!(10500.0));
+
+        let transaction = &statement.transactions[2];
+        assert_eq!(transaction.date, _date("03/01/2023"));
+        assert_eq!(transaction.description, "NEFT-TESTBANK TEST-TRANSACTION-3");
+        assert_eq!(transaction.ref_no, "");
+        assert_eq!(transaction.withdrawal, dec!(200.0));
+        assert_eq!(transaction.deposit, dec!(0.0));
+        assert_eq!(transaction.balance, dec!(10300.0));
+    }
+
+    #[test]
+    fn test_parse_layout_file() {
+        let file = File {
+            file_type: FileType::Pdf,
+            data: _layout_pdf_data(),
+        };
+
+        let parser = IciciInd;
+        let statement = parser.parse(&file).unwrap();
+
+        assert_eq!(statement.account_type, AccountType::SavingsAccount);
+        assert_eq!(statement.transactions.len(), 2);
+
+        let transaction = &statement.transactions[0];
+        assert_eq!(transaction.date, _date("01/01/2023"));
+        assert_eq!(
+            transaction.description,
+            "UPI/300112345678/ TESTUSER@BANK/ PAYMENT"
+        );
+        assert_eq!(transaction.ref_no, "");
+        assert_eq!(transaction.withdrawal, dec!(1000.0));
+        assert_eq!(transaction.balance, dec!(10000.0));
+
+        let transaction = &statement.transactions[1];
+        assert_eq!(transaction.description, "CLG/TESTUSER");
+        assert_eq!(transaction.ref_no, "000045");
+        assert_eq!(transaction.deposit, dec!(500.0));
+        assert_eq!(transaction.balance, dec!(10500.0));
+
+        // The legends below the table are not taken for a remark
+        assert_eq!(statement.skipped_rows.len(), 1);
+        let skipped = &statement.skipped_rows[0];
+        assert_eq!(skipped.key, "error.parser.icicind.invalid_amount");
+        assert_eq!(skipped.location, Some(Location::Row(3)));
+        assert_eq!(
+            skipped.content,
+            "3 | 04/01/2023 | 03/01/2023 | - | NEFT-TESTBANK | ten | 0.00 | 10,300.00"
+        );
+    }
+
+    #[test]
+    fn test_parse_missing_data_start() {
+        let file = File {
+            file_type: FileType::Pdf,
+            data: FileData::Text("ICICI Bank Limited \n1 \n01/01/2023 \n01/01/2023".to_string()),
+        };
+
+        let parser = IciciInd;
+        assert_eq!(
+            parser.parse(&file).unwrap_err().key(),
+            "error.parser.icicind.start_of_data_not_found"
+        );
+    }
+
+    #[test]
+    fn test_parse_missing_amounts() {
+        let file = File {
+            file_type: FileType::Pdf,
+            data: FileData::Text("S No. \nValue Date \nTransaction Date \nCheque Number \nTransaction Remarks \nWithdrawal Amount (INR ) \nDeposit Amount (INR ) \nBalance (INR ) \n1 \n01/01/2023 \n01/01/2023 \n- \nUPI/300112345678/ \nTESTUSER@BANK \n1,000.00 \n10,000.00".to_string()),
+        };
+
+        // The row is left out of the statement instead of failing it
+        let parser = IciciInd;
+        let statement = parser.parse(&file).unwrap();
+        assert!(statement.transactions.is_empty());
+        assert_eq!(statement.skipped_rows.len(), 1);
+        assert_eq!(
+            statement.skipped_rows[0].key,
+            "error.parser.icicind.amounts_not_found"
+        );
+        assert_eq!(statement.skipped_rows[0].location, Some(Location::Row(1)));
+        assert!(statement.skipped_rows[0]
+            .content
+            .starts_with("1 | 01/01/2023"));
+    }
+}
@@ -1,14 +1,24 @@
-use super::types::Parser;
+use sea_orm::sqlx::types::chrono::FixedOffset;
 
+use super::types::StatementParser;
+
+mod camt053;
 mod hdfcind;
 mod icicind;
+mod mt940;
+mod ofx;
+mod sbiind;
 
-pub fn get_all_parsers() -> Vec<Parser> {
-    let mut parsers = Vec::new();
-
-    // Add New Parsers Here
-    parsers.push(hdfcind::get_parser());
-    parsers.push(icicind::get_parser());
-
-    return parsers;
+/// Returns the parsers of this crate, those of standard formats reading the
+/// dates that carry no timezone in `timezone`
+pub fn get_all_parsers(timezone: FixedOffset) -> Vec<Box<dyn StatementParser>> {
+    vec![
+        // Add New Parsers Here
+        Box::new(hdfcind::HdfcInd),
+        Box::new(icicind::IciciInd),
+        Box::new(sbiind::SbiInd),
+        Box::new(ofx::Ofx::new(timezone)),
+        Box::new(camt053::Camt053::new(timezone)),
+        Box::new(mt940::Mt940::new(timezone)),
+    ]
 }
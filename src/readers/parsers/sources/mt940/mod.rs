@@ -0,0 +1,401 @@
+//! Parser for SWIFT MT940 customer statements
+//!
+//! Files with several statement messages (for example one per page, chained
+//! with intermediate `:62M:` balances) are read as a single statement.
+use std::borrow::Cow;
+
+use regex::Regex;
+use sea_orm::{
+    prelude::{DateTimeUtc, Decimal},
+    sqlx::types::chrono::FixedOffset,
+};
+
+use crate::{
+    error::{Error, Location},
+    models::entities::sea_orm_active_enums::{AccountType, InstitutionName},
+    readers::{
+        parsers::types::{Confidence, SkippedRow, Statement, StatementParser, Transaction},
+        types::{File, FileData, FileType},
+    },
+    utils::{
+        self, account_number,
+        datetime::{DateFormat, IST},
+    },
+};
+
+/// Dates are written as `YYMMDD`
+const DATE_FORMAT: DateFormat = DateFormat::Pattern(Cow::Borrowed("%y%m%d"));
+
+/// MT940 statements of any institution
+///
+/// MT940 dates carry no timezone, so they are read in the one the parser is
+/// made with.
+pub struct Mt940 {
+    timezone: FixedOffset,
+}
+
+impl Mt940 {
+    pub fn new(timezone: FixedOffset) -> Self {
+        Mt940 { timezone }
+    }
+}
+
+impl Default for Mt940 {
+    fn default() -> Self {
+        Mt940::new(IST)
+    }
+}
+
+impl StatementParser for Mt940 {
+    fn name(&self) -> &str {
+        "mt940"
+    }
+
+    fn institution(&self) -> Option<InstitutionName> {
+        None
+    }
+
+    fn file_types(&self) -> &[FileType] {
+        &[FileType::Mt940]
+    }
+
+    fn version(&self) -> u32 {
+        1
+    }
+
+    fn identify(&self, file: &File) -> Result<Confidence, Error> {
+        Ok(match recognise(file) {
+            Ok(()) => Confidence::FORMAT,
+            Err(_) => Confidence::NONE,
+        })
+    }
+
+    fn explain_mismatch(&self, file: &File) -> Option<String> {
+        recognise(file).err()
+    }
+
+    fn parse(&self, file: &File) -> Result<Statement, Error> {
+        match &file.data {
+            FileData::Text(data) => parse_mt940(data, self.timezone),
+            FileData::Table(_) | FileData::Tree(_) | FileData::Layout(_) => Err(
+                Error::parser_mismatch("error.parser.mt940.unsupported_file_data"),
+            ),
+        }
+    }
+}
+
+/// Tells whether the file is an MT940 message, or why it is not
+fn recognise(file: &File) -> Result<(), String> {
+    let data = match &file.data {
+        FileData::Text(data) if matches!(file.file_type, FileType::Mt940) => data,
+        _ => return Err("the file is not an MT940 message".to_string()),
+    };
+
+    match [":20:", ":25:"].iter().find(|tag| !data.contains(*tag)) {
+        Some(tag) => Err(format!("the message has no `{}` field", tag)),
+        None => Ok(()),
+    }
+}
+
+/// Splits the message into `(tag, value)` fields, dropping the SWIFT block wrappers
+fn split_fields(data: &str) -> Result<Vec<(String, String)>, Error> {
+    let field = Regex::new(r"^:(\d{2}[A-Z]?):(.*)$")
+        .map_err(|_| Error::parse("error.parser.mt940.regex_creation_failed_1"))?;
+
+    let mut fields: Vec<(String, String)> = Vec::new();
+    for line in data.lines() {
+        let line = match line.find("{4:") {
+            Some(index) => &line[index + 3..],
+            None => line,
+        };
+        let line = line.trim_end();
+
+        if line.is_empty() || line.starts_with('{') || line.starts_with('-') {
+            continue;
+        }
+
+        if let Some(captures) = field.captures(line) {
+            fields.push((captures[1].to_string(), captures[2].to_string()));
+        } else if let Some((_, value)) = fields.last_mut() {
+            value.push('\n');
+            value.push_str(line);
+        }
+    }
+
+    Ok(fields)
+}
+
+fn parse_mt940(data: &str, timezone: FixedOffset) -> Result<Statement, Error> {
+    let balance = Regex::new(r"^([CD])(\d{6})([A-Z]{3})([\d,]+)$")
+        .map_err(|_| Error::parse("error.parser.mt940.regex_creation_failed_2"))?;
+
+    let fields = split_fields(data)?;
+
+    let mut account_number = None;
+    let mut opening_balance = None;
+    let mut closing_balance = None;
+    let mut currency = None;
+    let mut date = None;
+    for (tag, value) in &fields {
+        match tag.as_str() {
+            "25" if account_number.is_none() => account_number = Some(value.trim().to_string()),
+            "60F" | "60M" if opening_balance.is_none() => {
+                let (amount, _, balance_currency) = parse_balance(&balance, value, timezone)?;
+                opening_balance = Some(amount);
+                currency = Some(balance_currency);
+            }
+            "62F" | "62M" => {
+                let (amount, balance_date, _) = parse_balance(&balance, value, timezone)?;
+                closing_balance = Some(amount);
+                date = Some(balance_date);
+            }
+            _ => {}
+        }
+    }
+
+    let (transactions, skipped_rows) =
+        parse_trnx(&fields, opening_balance.unwrap_or(Decimal::ZERO), timezone)?;
+
+    Ok(Statement {
+        transactions,
+        institution: None,
+        account_type: AccountType::Unknown,
+        masked_account_number: account_number.as_deref().map(account_number::mask),
+        account_number,
+        ifsc: None,
+        branch: None,
+        holder_name: None,
+        currency,
+        date: date.unwrap_or_else(utils::datetime::get_current_datetime),
+        timezone,
+        period_start: None,
+        period_end: date,
+        opening_balance,
+        closing_balance,
+        totals: None,
+        source_hash: None,
+        skipped_rows,
+        warnings: Vec::new(),
+    })
+}
+
+fn parse_trnx(
+    fields: &[(String, String)],
+    opening_balance: Decimal,
+    timezone: FixedOffset,
+) -> Result<(Vec<Transaction>, Vec<SkippedRow>), Error> {
+    let statement_line = Regex::new(
+        r"(?s)^(\d{6})(\d{4})?(RC|RD|C|D)([A-Z])?([\d,]+)([NFS][A-Z0-9]{3})([^\n]*?)(?://([^\n]*))?(?:\n(.*))?$",
+    )
+    .map_err(|_| Error::parse("error.parser.mt940.regex_creation_failed_3"))?;
+    let subfield = Regex::new(r"\?(\d{2})")
+        .map_err(|_| Error::parse("error.parser.mt940.regex_creation_failed_4"))?;
+
+    let mut transactions: Vec<Transaction> = Vec::new();
+    let mut skipped_rows = Vec::new();
+    let mut balance = opening_balance;
+
+    for (index, (tag, value)) in fields.iter().enumerate() {
+        if tag != "61" {
+            continue;
+        }
+        let row = Location::Row(transactions.len() + skipped_rows.len() + 1);
+
+        // The information to the account owner follows its statement line
+        let information = match fields.get(index + 1) {
+            Some((tag, information)) if tag == "86" => Some(information.as_str()),
+            _ => None,
+        };
+
+        match parse_statement_line(&statement_line, &subfield, value, information, timezone) {
+            Ok(mut transaction) => {
+                // The signed amount is kept as the balance until it is added up
+                balance += transaction.balance;
+                transaction.balance = balance;
+                transactions.push(transaction);
+            }
+            Err(error) => skipped_rows.push(SkippedRow::from_error(
+                &error.at(row),
+                [Some(value.as_str()), information]
+                    .into_iter()
+                    .flatten()
+                    .collect::<Vec<&str>>()
+                    .join(" | "),
+            )),
+        }
+    }
+
+    Ok((transactions, skipped_rows))
+}
+
+/// Reads a `:61:` statement line and the `:86:` information following it,
+/// with the signed amount as the balance
+fn parse_statement_line(
+    statement_line: &Regex,
+    subfield: &Regex,
+    value: &str,
+    information: Option<&str>,
+    timezone: FixedOffset,
+) -> Result<Transaction, Error> {
+    let captures = statement_line.captures(value).ok_or_else(|| {
+        Error::parse("error.parser.mt940.invalid_statement_line").with_reason(value)
+    })?;
+
+    let date = match captures.get(2) {
+        Some(entry_date) => booking_date(&captures[1], entry_date.as_str(), timezone),
+        None => parse_date(&captures[1], timezone),
+    }?;
+
+    let amount = parse_amount(&captures[5])?;
+    // RC and RD reverse a previous debit or credit
+    let amount = match &captures[3] {
+        "C" | "RD" => amount,
+        _ => -amount,
+    };
+
+    let owner_reference = captures[7].trim();
+    let bank_reference = captures.get(8).map(|m| m.as_str().trim()).unwrap_or("");
+    let ref_no = if owner_reference.is_empty() || owner_reference == "NONREF" {
+        bank_reference.to_string()
+    } else {
+        owner_reference.to_string()
+    };
+
+    let description = match information {
+        Some(information) => describe(subfield, information),
+        None => captures
+            .get(9)
+            .map(|m| m.as_str().trim().to_string())
+            .unwrap_or_default(),
+    };
+
+    Ok(Transaction {
+        date,
+        description,
+        ref_no,
+        withdrawal: if amount < Decimal::ZERO {
+            -amount
+        } else {
+            Decimal::ZERO
+        },
+        deposit: if amount > Decimal::ZERO {
+            amount
+        } else {
+            Decimal::ZERO
+        },
+        balance: amount,
+        warnings: Vec::new(),
+    })
+}
+
+/// Builds the description from a `:86:` field
+///
+/// Structured fields (`?20` purpose, `?31` account, `?32`/`?33` name, ...)
+/// are reduced to the counterparty and the purpose; anything else is
+/// joined into one line.
+fn describe(subfield: &Regex, information: &str) -> String {
+    if !subfield.is_match(information) {
+        return information
+            .lines()
+            .map(|line| line.trim())
+            .collect::<Vec<&str>>()
+            .join(" ");
+    }
+
+    let information = information.replace('\n', "");
+    let mut codes: Vec<(String, String)> = Vec::new();
+    let mut positions = subfield.captures_iter(&information).peekable();
+    while let Some(captures) = positions.next() {
+        let start = captures.get(0).map(|m| m.end()).unwrap_or_default();
+        let end = positions
+            .peek()
+            .and_then(|next| next.get(0))
+            .map(|m| m.start())
+            .unwrap_or(information.len());
+        codes.push((captures[1].to_string(), information[start..end].to_string()));
+    }
+
+    let collect = |range: &[&str]| {
+        codes
+            .iter()
+            .filter(|(code, _)| range.contains(&code.as_str()))
+            .map(|(_, text)| text.as_str())
+            .collect::<String>()
+            .trim()
+            .to_string()
+    };
+
+    let name = collect(&["32", "33"]);
+    let account = collect(&["31"]);
+    let purpose = collect(&[
+        "20", "21", "22", "23", "24", "25", "26", "27", "28", "29", "60", "61", "62", "63",
+    ]);
+
+    let counterparty = match (name.is_empty(), account.is_empty()) {
+        (false, false) => format!("{} ({})", name, account),
+        (false, true) => name,
+        (true, false) => account,
+        (true, true) => collect(&["00"]),
+    };
+
+    [counterparty, purpose]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<String>>()
+        .join(" - ")
+}
+
+/// Reads a balance field into its signed amount, date and currency
+fn parse_balance(
+    balance: &Regex,
+    value: &str,
+    timezone: FixedOffset,
+) -> Result<(Decimal, DateTimeUtc, String), Error> {
+    let captures = balance
+        .captures(value.trim())
+        .ok_or_else(|| Error::parse("error.parser.mt940.invalid_balance").with_reason(value))?;
+
+    let amount = parse_amount(&captures[4])?;
+    let amount = if &captures[1] == "D" { -amount } else { amount };
+
+    Ok((
+        amount,
+        parse_date(&captures[2], timezone)?,
+        captures[3].to_string(),
+    ))
+}
+
+/// Amounts use a comma as the decimal separator, e.g. `1234,56` or `1234,`
+fn parse_amount(amount: &str) -> Result<Decimal, Error> {
+    amount
+        .replace(',', ".")
+        .trim_end_matches('.')
+        .parse::<Decimal>()
+        .map_err(|_| Error::parse("error.parser.mt940.invalid_amount").with_reason(amount))
+}
+
+fn parse_date(date: &str, timezone: FixedOffset) -> Result<DateTimeUtc, Error> {
+    utils::datetime::parse_date(date, &[DATE_FORMAT], timezone)
+        .map_err(|_| Error::parse("error.parser.mt940.invalid_date").with_reason(date))
+}
+
+/// The booking date (`MMDD`) takes its year from the value date (`YYMMDD`),
+/// which can fall in the neighbouring year
+fn booking_date(
+    value_date: &str,
+    entry_date: &str,
+    timezone: FixedOffset,
+) -> Result<DateTimeUtc, Error> {
+    let invalid_date = || Error::parse("error.parser.mt940.invalid_date").with_reason(entry_date);
+    let year: u32 = value_date[..2].parse().map_err(|_| invalid_date())?;
+    let year = match (&value_date[2..4], &entry_date[..2]) {
+        ("01", "12") => (year + 99) % 100,
+        ("12", "01") => (year + 1) % 100,
+        _ => year,
+    };
+
+    parse_date(&format!("{:02}{}", year, entry_date), timezone)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,120 @@
+use crate::utils::datetime::{parse_date, DateFormat, IST};
+use sea_orm::prelude::DateTimeUtc;
+
+/// Start of the day in India Standard Time, where statement dates fall
+pub fn _date(date: &str) -> DateTimeUtc {
+    parse_date(date, &[DateFormat::DD_MM_YY, DateFormat::DD_MM_YYYY], IST).unwrap()
+}
+
+#[cfg(test)]
+mod text {
+    use crate::{
+        error::Location,
+        models::entities::sea_orm_active_enums::AccountType,
+        readers::{
+            get_statement_from_file_content,
+            parsers::{
+                sources::mt940::{tests::_date, Mt940},
+                types::StatementParser,
+            },
+            types::{File, FileData, FileType},
+        },
+    };
+    use rust_decimal_macros::dec;
+
+    fn _fixture() -> Vec<u8> {
+        include_bytes!("fixtures/statement.sta").to_vec()
+    }
+
+    #[test]
+    fn test_identify_valid_file() {
+        let file = File {
+            file_type: FileType::Mt940,
+            data: FileData::Text(":20:STMT\n:25:123\n:60F:C230101EUR0,\n".to_string()),
+        };
+
+        let parser = Mt940::default();
+        assert!(parser.identify(&file).unwrap().is_match());
+    }
+
+    #[test]
+    fn test_identify_invalid_file_content() {
+        let file = File {
+            file_type: FileType::Pdf,
+            data: FileData::Text(":20:STMT\n:25:123\n".to_string()),
+        };
+
+        let parser = Mt940::default();
+        assert!(!parser.identify(&file).unwrap().is_match());
+    }
+
+    #[test]
+    fn test_parse_valid_file() {
+        let statement = get_statement_from_file_content(_fixture(), "").unwrap();
+
+        assert_eq!(statement.account_type, AccountType::Unknown);
+        assert_eq!(
+            statement.account_number,
+            Some("10020030/1234567890".to_string())
+        );
+        assert_eq!(statement.date, _date("31/01/2023"));
+        assert_eq!(statement.opening_balance, Some(dec!(1000.0)));
+        assert_eq!(statement.closing_balance, Some(dec!(1139.5)));
+        assert_eq!(statement.currency.as_deref(), Some("EUR"));
+        assert_eq!(
+            statement.masked_account_number.as_deref(),
+            Some("XXXXXXXXXXXXXXX7890")
+        );
+        assert_eq!(
+            statement.source_hash.as_deref(),
+            Some("8ce1609d9dab087de8fcdabc92cc9ca93af0f59c4886c5384c1efed3209a8241")
+        );
+
+        let transactions = &statement.transactions;
+        assert_eq!(transactions.len(), 3);
+
+        assert_eq!(transactions[0].date, _date("03/01/2023"));
+        assert_eq!(
+            transactions[0].description,
+            "Grocer GmbH (DE02120300000000202051) - SVWZ+Invoice 42 for January groceries"
+        );
+        assert_eq!(transactions[0].ref_no, "INV-42");
+        assert_eq!(transactions[0].withdrawal, dec!(50.5));
+        assert_eq!(transactions[0].deposit, dec!(0.0));
+        assert_eq!(transactions[0].balance, dec!(949.5));
+
+        assert_eq!(transactions[1].date, _date("16/01/2023"));
+        assert_eq!(transactions[1].description, "Salary January Employer AG");
+        assert_eq!(transactions[1].ref_no, "SALARY-JAN");
+        assert_eq!(transactions[1].withdrawal, dec!(0.0));
+        assert_eq!(transactions[1].deposit, dec!(200.0));
+        assert_eq!(transactions[1].balance, dec!(1149.5));
+
+        // Booked in January for a value date in the previous December
+        assert_eq!(transactions[2].date, _date("02/01/2024"));
+        assert_eq!(transactions[2].ref_no, "");
+        assert_eq!(transactions[2].withdrawal, dec!(10.0));
+        assert_eq!(transactions[2].balance, dec!(1139.5));
+    }
+
+    #[test]
+    fn test_parse_invalid_statement_line() {
+        let file = File {
+            file_type: FileType::Mt940,
+            data: FileData::Text(
+                ":20:STMT\n:25:123\n:60F:C230101EUR0,\n:61:garbage\n:61:2301020102D10,00NTRFNONREF\n"
+                    .to_string(),
+            ),
+        };
+
+        let parser = Mt940::default();
+        let statement = parser.parse(&file).unwrap();
+        assert_eq!(statement.transactions.len(), 1);
+        assert_eq!(statement.transactions[0].balance, dec!(-10.0));
+
+        let skipped = &statement.skipped_rows[0];
+        assert_eq!(skipped.key, "error.parser.mt940.invalid_statement_line");
+        assert_eq!(skipped.location, Some(Location::Row(1)));
+        assert_eq!(skipped.content, "garbage");
+    }
+}
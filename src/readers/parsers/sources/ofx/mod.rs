@@ -0,0 +1,293 @@
+//! Institution independent parser for OFX/QFX files
+//!
+//! Both bank (`STMTRS`) and credit card (`CCSTMTRS`) statements are read.
+//! When a file holds statements for several accounts, the first one is used.
+//! Dates are read in the timezone their `[offset:TZ]` suffix gives.
+use std::borrow::Cow;
+
+use sea_orm::{
+    prelude::{DateTimeUtc, Decimal},
+    sqlx::types::chrono::FixedOffset,
+};
+
+use crate::{
+    error::{Error, Location},
+    models::entities::sea_orm_active_enums::{AccountType, InstitutionName},
+    readers::{
+        parsers::types::{Confidence, SkippedRow, Statement, StatementParser, Transaction},
+        types::{File, FileData, FileType, Node},
+    },
+    utils::{
+        self, account_number,
+        datetime::{DateFormat, IST},
+    },
+};
+
+/// Dates are the leading `YYYYMMDD` of an OFX datetime
+const DATE_FORMAT: DateFormat = DateFormat::Pattern(Cow::Borrowed("%Y%m%d"));
+
+/// OFX/QFX statements of any institution
+pub struct Ofx {
+    /// Timezone of the dates that carry no offset
+    timezone: FixedOffset,
+}
+
+impl Ofx {
+    pub fn new(timezone: FixedOffset) -> Self {
+        Ofx { timezone }
+    }
+}
+
+impl Default for Ofx {
+    fn default() -> Self {
+        Ofx::new(IST)
+    }
+}
+
+impl StatementParser for Ofx {
+    fn name(&self) -> &str {
+        "ofx"
+    }
+
+    fn institution(&self) -> Option<InstitutionName> {
+        None
+    }
+
+    fn file_types(&self) -> &[FileType] {
+        &[FileType::Ofx]
+    }
+
+    fn version(&self) -> u32 {
+        1
+    }
+
+    fn identify(&self, file: &File) -> Result<Confidence, Error> {
+        Ok(match recognise(file) {
+            Ok(()) => Confidence::FORMAT,
+            Err(_) => Confidence::NONE,
+        })
+    }
+
+    fn explain_mismatch(&self, file: &File) -> Option<String> {
+        recognise(file).err()
+    }
+
+    fn parse(&self, file: &File) -> Result<Statement, Error> {
+        match &file.data {
+            FileData::Tree(data) => parse_ofx(data, self.timezone),
+            FileData::Table(_) | FileData::Text(_) | FileData::Layout(_) => Err(
+                Error::parser_mismatch("error.parser.ofx.unsupported_file_data"),
+            ),
+        }
+    }
+}
+
+/// Tells whether the file is an OFX document, or why it is not
+fn recognise(file: &File) -> Result<(), String> {
+    match &file.data {
+        FileData::Tree(data) if matches!(file.file_type, FileType::Ofx) => {
+            if data.name == "OFX" {
+                Ok(())
+            } else {
+                Err(format!("the root element is `{}`, not `OFX`", data.name))
+            }
+        }
+        _ => Err("the file is not an OFX document".to_string()),
+    }
+}
+
+fn parse_ofx(ofx: &Node, timezone: FixedOffset) -> Result<Statement, Error> {
+    let (statement, account_type, account_number) = if let Some(statement) =
+        ofx.path(&["BANKMSGSRSV1", "STMTTRNRS", "STMTRS"])
+    {
+        let account_type = match statement.text_at(&["BANKACCTFROM", "ACCTTYPE"]) {
+            Some("SAVINGS") => AccountType::SavingsAccount,
+            Some("CHECKING") => AccountType::CheckingAccount,
+            _ => AccountType::Unknown,
+        };
+        let account_number = statement.text_at(&["BANKACCTFROM", "ACCTID"]);
+        (statement, account_type, account_number)
+    } else if let Some(statement) = ofx.path(&["CREDITCARDMSGSRSV1", "CCSTMTTRNRS", "CCSTMTRS"]) {
+        let account_number = statement.text_at(&["CCACCTFROM", "ACCTID"]);
+        (statement, AccountType::CreditCard, account_number)
+    } else {
+        return Err(Error::parse("error.parser.ofx.statement_not_found"));
+    };
+
+    // The statement is in the timezone its own dates give, if any does
+    let timezone = [
+        &["BANKTRANLIST", "DTEND"][..],
+        &["BANKTRANLIST", "DTSTART"],
+        &["LEDGERBAL", "DTASOF"],
+    ]
+    .iter()
+    .find_map(|path| statement.text_at(path).and_then(parse_offset))
+    .unwrap_or(timezone);
+    let parse_date = |date: &str| parse_date(date, timezone);
+
+    let closing_balance = statement
+        .text_at(&["LEDGERBAL", "BALAMT"])
+        .map(parse_amount)
+        .transpose()?;
+
+    let period_start = statement
+        .text_at(&["BANKTRANLIST", "DTSTART"])
+        .and_then(parse_date);
+    let period_end = statement
+        .text_at(&["BANKTRANLIST", "DTEND"])
+        .and_then(parse_date);
+
+    let date = statement
+        .text_at(&["BANKTRANLIST", "DTEND"])
+        .or_else(|| statement.text_at(&["LEDGERBAL", "DTASOF"]))
+        .and_then(parse_date)
+        .unwrap_or_else(utils::datetime::get_current_datetime);
+
+    let (transactions, skipped_rows) = parse_trnx(statement, closing_balance, timezone)?;
+
+    Ok(Statement {
+        transactions,
+        institution: None,
+        account_type,
+        account_number: account_number.map(|number| number.to_string()),
+        masked_account_number: account_number.map(account_number::mask),
+        ifsc: None,
+        branch: None,
+        holder_name: None,
+        currency: statement.text_at(&["CURDEF"]).map(|code| code.to_string()),
+        date,
+        timezone,
+        period_start,
+        period_end,
+        opening_balance: None,
+        closing_balance,
+        totals: None,
+        source_hash: None,
+        skipped_rows,
+        warnings: Vec::new(),
+    })
+}
+
+fn parse_trnx(
+    statement: &Node,
+    closing_balance: Option<Decimal>,
+    timezone: FixedOffset,
+) -> Result<(Vec<Transaction>, Vec<SkippedRow>), Error> {
+    let mut records = Vec::new();
+    let mut skipped_rows = Vec::new();
+
+    let transaction_list = match statement.child("BANKTRANLIST") {
+        Some(transaction_list) => transaction_list,
+        None => return Ok((Vec::new(), skipped_rows)),
+    };
+
+    for (index, record) in transaction_list.children_named("STMTTRN").enumerate() {
+        match parse_record(record, timezone) {
+            Ok(record) => records.push(record),
+            Err(error) => skipped_rows.push(SkippedRow::from_error(
+                &error.at(Location::Row(index + 1)),
+                record.texts().join(" | "),
+            )),
+        }
+    }
+
+    // OFX does not carry running balances and does not promise any order,
+    // so walk back from the ledger balance over the date sorted records.
+    records.sort_by_key(|(date, _, _, _)| *date);
+    let total: Decimal = records.iter().map(|(_, amount, _, _)| amount).sum();
+    let mut balance = closing_balance.unwrap_or(total) - total;
+
+    let transactions = records
+        .into_iter()
+        .map(|(date, amount, ref_no, description)| {
+            balance += amount;
+            Transaction {
+                date,
+                description,
+                ref_no,
+                withdrawal: if amount < Decimal::ZERO {
+                    -amount
+                } else {
+                    Decimal::ZERO
+                },
+                deposit: if amount > Decimal::ZERO {
+                    amount
+                } else {
+                    Decimal::ZERO
+                },
+                balance,
+                warnings: Vec::new(),
+            }
+        })
+        .collect();
+
+    Ok((transactions, skipped_rows))
+}
+
+/// Reads the date, amount, reference and description of a `STMTTRN`
+fn parse_record(
+    record: &Node,
+    timezone: FixedOffset,
+) -> Result<(DateTimeUtc, Decimal, String, String), Error> {
+    let date = record
+        .text_at(&["DTPOSTED"])
+        .and_then(|date| parse_date(date, timezone))
+        .ok_or(Error::parse("error.parser.ofx.invalid_date"))?;
+    let amount = parse_amount(
+        record
+            .text_at(&["TRNAMT"])
+            .ok_or(Error::parse("error.parser.ofx.amount_not_found"))?,
+    )?;
+
+    // Cheque and reference numbers are what the bank prints on paper,
+    // the FITID is only guaranteed to be unique
+    let ref_no = record
+        .text_at(&["CHECKNUM"])
+        .or_else(|| record.text_at(&["REFNUM"]))
+        .or_else(|| record.text_at(&["FITID"]))
+        .unwrap_or_default()
+        .to_string();
+
+    let description = [record.text_at(&["NAME"]), record.text_at(&["MEMO"])]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<&str>>()
+        .join(" - ");
+
+    Ok((date, amount, ref_no, description))
+}
+
+fn parse_amount(amount: &str) -> Result<Decimal, Error> {
+    amount
+        .trim()
+        .replace(',', ".")
+        .parse::<Decimal>()
+        .map_err(|_| Error::parse("error.parser.ofx.invalid_amount").with_reason(amount))
+}
+
+/// Reads the date part of an OFX datetime (`YYYYMMDD[HHMMSS[.XXX]][[offset:TZ]]`),
+/// as the day it is in its own offset, or in `timezone` without one
+fn parse_date(date: &str, timezone: FixedOffset) -> Option<DateTimeUtc> {
+    let timezone = parse_offset(date).unwrap_or(timezone);
+    utils::datetime::parse_date(date.get(..8)?, &[DATE_FORMAT], timezone).ok()
+}
+
+/// Reads the `[offset:TZ]` of an OFX datetime, whose offset is in hours
+/// from UTC and may have a fraction (`[-5:EST]`, `[+5.5:IST]`, `[0]`)
+///
+/// Some institutions write the minutes after the hours (`[+5:30:IST]`).
+fn parse_offset(date: &str) -> Option<FixedOffset> {
+    let zone = date.split_once('[')?.1.strip_suffix(']')?;
+    let parts: Vec<&str> = zone.split(':').map(str::trim).collect();
+    let hours = parts[0].parse::<f64>().ok()?;
+    let minutes = match parts.get(1..) {
+        Some([minutes, _, ..]) if minutes.chars().all(|c| c.is_ascii_digit()) => {
+            minutes.parse::<f64>().ok()?.copysign(hours)
+        }
+        _ => 0.0,
+    };
+    FixedOffset::east_opt((hours * 3600.0 + minutes * 60.0).round() as i32)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,308 @@
+use crate::utils::datetime::{parse_date, DateFormat, IST};
+use sea_orm::prelude::DateTimeUtc;
+
+/// Start of the day in India Standard Time, where statement dates fall
+pub fn _date(date: &str) -> DateTimeUtc {
+    parse_date(date, &[DateFormat::DD_MM_YY, DateFormat::DD_MM_YYYY], IST).unwrap()
+}
+
+#[cfg(test)]
+mod ofx {
+    use crate::{
+        error::Location,
+        models::entities::sea_orm_active_enums::AccountType,
+        readers::{
+            parsers::{
+                sources::ofx::{tests::_date, Ofx},
+                types::StatementParser,
+            },
+            types::{File, FileData, FileType, Node},
+        },
+    };
+    use rust_decimal_macros::dec;
+    use sea_orm::sqlx::types::chrono::{FixedOffset, TimeZone, Utc};
+
+    fn _leaf(name: &str, text: &str) -> Node {
+        Node {
+            name: name.to_string(),
+            text: text.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn _node(name: &str, children: Vec<Node>) -> Node {
+        Node {
+            name: name.to_string(),
+            children,
+            ..Default::default()
+        }
+    }
+
+    fn _statement_transaction(date: &str, amount: &str, fitid: &str, name: &str) -> Node {
+        _node(
+            "STMTTRN",
+            vec![
+                _leaf("TRNTYPE", "OTHER"),
+                _leaf("DTPOSTED", date),
+                _leaf("TRNAMT", amount),
+                _leaf("FITID", fitid),
+                _leaf("NAME", name),
+            ],
+        )
+    }
+
+    fn _common_ofx_data() -> FileData {
+        FileData::Tree(_node(
+            "OFX",
+            vec![_node(
+                "BANKMSGSRSV1",
+                vec![_node(
+                    "STMTTRNRS",
+                    vec![_node(
+                        "STMTRS",
+                        vec![
+                            _leaf("CURDEF", "INR"),
+                            _node(
+                                "BANKACCTFROM",
+                                vec![
+                                    _leaf("BANKID", "HDFC0000001"),
+                                    _leaf("ACCTID", "123456789"),
+                                    _leaf("ACCTTYPE", "SAVINGS"),
+                                ],
+                            ),
+                            _node(
+                                "BANKTRANLIST",
+                                vec![
+                                    _leaf("DTSTART", "20230101"),
+                                    _leaf("DTEND", "20230131235959.000[+5:30:IST]"),
+                                    _statement_transaction(
+                                        "20230105120000",
+                                        "250.00",
+                                        "1002",
+                                        "Salary",
+                                    ),
+                                    _node(
+                                        "STMTTRN",
+                                        vec![
+                                            _leaf("DTPOSTED", "20230102"),
+                                            _leaf("TRNAMT", "-50.00"),
+                                            _leaf("FITID", "1001"),
+                                            _leaf("CHECKNUM", "000123"),
+                                            _leaf("NAME", "Grocer"),
+                                            _leaf("MEMO", "Weekly shopping"),
+                                        ],
+                                    ),
+                                ],
+                            ),
+                            _node(
+                                "LEDGERBAL",
+                                vec![_leaf("BALAMT", "1200.00"), _leaf("DTASOF", "20230131")],
+                            ),
+                        ],
+                    )],
+                )],
+            )],
+        ))
+    }
+
+    #[test]
+    fn test_identify_valid_file() {
+        let file = File {
+            file_type: FileType::Ofx,
+            data: _common_ofx_data(),
+        };
+
+        let parser = Ofx::default();
+        assert!(parser.identify(&file).unwrap().is_match());
+    }
+
+    #[test]
+    fn test_identify_invalid_file_content() {
+        let file = File {
+            file_type: FileType::Csv,
+            data: FileData::Table(vec![vec!["OFX".to_string()]]),
+        };
+
+        let parser = Ofx::default();
+        assert!(!parser.identify(&file).unwrap().is_match());
+    }
+
+    #[test]
+    fn test_parse_valid_file() {
+        let file = File {
+            file_type: FileType::Ofx,
+            data: _common_ofx_data(),
+        };
+
+        let parser = Ofx::default();
+        let statement = parser.parse(&file).unwrap();
+
+        assert_eq!(statement.account_type, AccountType::SavingsAccount);
+        assert_eq!(statement.account_number, Some("123456789".to_string()));
+        assert_eq!(statement.closing_balance, Some(dec!(1200.0)));
+        assert_eq!(statement.date, _date("31/01/2023"));
+        assert_eq!(statement.currency.as_deref(), Some("INR"));
+        assert_eq!(statement.period_start, Some(_date("01/01/2023")));
+
+        let transactions = &statement.transactions;
+        assert_eq!(transactions.len(), 2);
+
+        assert_eq!(transactions[0].date, _date("02/01/2023"));
+        assert_eq!(transactions[0].description, "Grocer - Weekly shopping");
+        assert_eq!(transactions[0].ref_no, "000123");
+        assert_eq!(transactions[0].withdrawal, dec!(50.0));
+        assert_eq!(transactions[0].deposit, dec!(0.0));
+        assert_eq!(transactions[0].balance, dec!(950.0));
+
+        assert_eq!(transactions[1].date, _date("05/01/2023"));
+        assert_eq!(transactions[1].description, "Salary");
+        assert_eq!(transactions[1].ref_no, "1002");
+        assert_eq!(transactions[1].withdrawal, dec!(0.0));
+        assert_eq!(transactions[1].deposit, dec!(250.0));
+        assert_eq!(transactions[1].balance, dec!(1200.0));
+    }
+
+    #[test]
+    fn test_parse_credit_card_statement() {
+        let file = File {
+            file_type: FileType::Ofx,
+            data: FileData::Tree(_node(
+                "OFX",
+                vec![_node(
+                    "CREDITCARDMSGSRSV1",
+                    vec![_node(
+                        "CCSTMTTRNRS",
+                        vec![_node(
+                            "CCSTMTRS",
+                            vec![
+                                _node("CCACCTFROM", vec![_leaf("ACCTID", "4111111111111111")]),
+                                _node(
+                                    "BANKTRANLIST",
+                                    vec![_statement_transaction(
+                                        "20230110", "-10.50", "A1", "Coffee",
+                                    )],
+                                ),
+                            ],
+                        )],
+                    )],
+                )],
+            )),
+        };
+
+        let parser = Ofx::default();
+        let statement = parser.parse(&file).unwrap();
+
+        assert_eq!(statement.account_type, AccountType::CreditCard);
+        assert_eq!(
+            statement.account_number,
+            Some("4111111111111111".to_string())
+        );
+        assert_eq!(statement.closing_balance, None);
+        assert_eq!(statement.transactions.len(), 1);
+        assert_eq!(statement.transactions[0].withdrawal, dec!(10.5));
+        assert_eq!(statement.transactions[0].balance, dec!(-10.5));
+    }
+
+    #[test]
+    fn test_parse_without_statement() {
+        let file = File {
+            file_type: FileType::Ofx,
+            data: FileData::Tree(_node("OFX", vec![_node("SIGNONMSGSRSV1", vec![])])),
+        };
+
+        let parser = Ofx::default();
+        assert_eq!(
+            parser.parse(&file).unwrap_err().key(),
+            "error.parser.ofx.statement_not_found"
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_amount() {
+        let file = File {
+            file_type: FileType::Ofx,
+            data: FileData::Tree(_node(
+                "OFX",
+                vec![_node(
+                    "BANKMSGSRSV1",
+                    vec![_node(
+                        "STMTTRNRS",
+                        vec![_node(
+                            "STMTRS",
+                            vec![_node(
+                                "BANKTRANLIST",
+                                vec![_statement_transaction("20230110", "ten", "A1", "Coffee")],
+                            )],
+                        )],
+                    )],
+                )],
+            )),
+        };
+
+        let parser = Ofx::default();
+        let statement = parser.parse(&file).unwrap();
+        assert!(statement.transactions.is_empty());
+
+        let skipped = &statement.skipped_rows[0];
+        assert_eq!(skipped.key, "error.parser.ofx.invalid_amount");
+        assert_eq!(skipped.reason.as_deref(), Some("ten"));
+        assert_eq!(skipped.location, Some(Location::Row(1)));
+        assert!(skipped.content.contains("Coffee"));
+    }
+
+    #[test]
+    fn test_parse_dates_in_their_offset() {
+        let statement = |dtend: &str, dtposted: &[&str]| File {
+            file_type: FileType::Ofx,
+            data: FileData::Tree(_node(
+                "OFX",
+                vec![_node(
+                    "BANKMSGSRSV1",
+                    vec![_node(
+                        "STMTTRNRS",
+                        vec![_node(
+                            "STMTRS",
+                            vec![_node(
+                                "BANKTRANLIST",
+                                [_leaf("DTEND", dtend)]
+                                    .into_iter()
+                                    .chain(dtposted.iter().map(|date| {
+                                        _statement_transaction(date, "1.00", "A1", "Coffee")
+                                    }))
+                                    .collect(),
+                            )],
+                        )],
+                    )],
+                )],
+            )),
+        };
+        let est = FixedOffset::west_opt(5 * 3600).unwrap();
+
+        // Days start at midnight in New York, not in India
+        let file = statement(
+            "20230131[-5:EST]",
+            &["20230105230000.000[-5:EST]", "20230106"],
+        );
+        let statement_in_est = Ofx::default().parse(&file).unwrap();
+        assert_eq!(statement_in_est.timezone, est);
+        assert_eq!(
+            statement_in_est.transactions[0].date,
+            Utc.with_ymd_and_hms(2023, 1, 5, 5, 0, 0).unwrap()
+        );
+        // Dates without an offset are in the statement's timezone
+        assert_eq!(
+            statement_in_est.transactions[1].date,
+            Utc.with_ymd_and_hms(2023, 1, 6, 5, 0, 0).unwrap()
+        );
+
+        // Without any offset, the parser's timezone is used
+        let utc = FixedOffset::east_opt(0).unwrap();
+        let file = statement("20230131", &["20230105230000"]);
+        let statement_in_utc = Ofx::new(utc).parse(&file).unwrap();
+        assert_eq!(statement_in_utc.timezone, utc);
+        assert_eq!(
+            statement_in_utc.transactions[0].date,
+            Utc.with_ymd_and_hms(2023, 1, 5, 0, 0, 0).unwrap()
+        );
+    }
+}
@@ -0,0 +1,486 @@
+use regex::Regex;
+use sea_orm::{
+    prelude::{DateTimeUtc, Decimal},
+    sqlx::types::chrono::FixedOffset,
+};
+
+use crate::{
+    error::{Error, Location},
+    models::entities::sea_orm_active_enums::{AccountType, InstitutionName},
+    readers::{
+        parsers::types::{
+            derive_balance, Confidence, SkippedRow, Statement, StatementParser, Transaction,
+        },
+        types::{File, FileData, FileType, Layout, Row},
+    },
+    utils::{
+        self, account_number,
+        datetime::{DateFormat, IST},
+    },
+};
+
+/// Column headings of the transaction table, in the order SBI prints them
+const HEADER: [&str; 7] = [
+    "Txn Date",
+    "Value Date",
+    "Description",
+    "Ref No./Cheque No.",
+    "Debit",
+    "Credit",
+    "Balance",
+];
+
+/// Dates are printed as `1 Jan 2023`, but older exports use `01/01/2023`
+/// and workbooks may hold them as dates
+const DATE_FORMATS: [DateFormat; 4] = [
+    DateFormat::D_MMM_YYYY,
+    DateFormat::DD_MMM_YYYY,
+    DateFormat::DD_MM_YYYY,
+    DateFormat::ExcelSerial,
+];
+
+/// Prefix of the IFSC of every SBI branch, printed above the table
+const IFSC_PREFIX: &str = "SBIN";
+
+const TIMEZONE: FixedOffset = IST;
+
+/// Labels of the account description that name a savings account scheme
+const SAVINGS_LABELS: [&str; 3] = ["SB", "SBCHQ", "SAVINGS"];
+
+/// Labels of the account description that name a current account scheme
+const CURRENT_LABELS: [&str; 2] = ["CA", "CURRENT"];
+
+/// Transactions read from the table, and the rows of it that were skipped
+type Rows = (Vec<Transaction>, Vec<SkippedRow>);
+
+/// Statements of State Bank of India savings and current accounts, as XLS
+/// or PDF
+pub struct SbiInd;
+
+impl StatementParser for SbiInd {
+    fn name(&self) -> &str {
+        "sbiind"
+    }
+
+    fn institution(&self) -> Option<InstitutionName> {
+        Some(InstitutionName::StateBankOfIndia)
+    }
+
+    fn file_types(&self) -> &[FileType] {
+        &[
+            FileType::Xls,
+            FileType::Xlsx,
+            FileType::Xlsb,
+            FileType::Ods,
+            FileType::Csv,
+            FileType::Pdf,
+        ]
+    }
+
+    fn version(&self) -> u32 {
+        1
+    }
+
+    fn identify(&self, file: &File) -> Result<Confidence, Error> {
+        Ok(recognise(file).unwrap_or(Confidence::NONE))
+    }
+
+    fn explain_mismatch(&self, file: &File) -> Option<String> {
+        recognise(file).err()
+    }
+
+    fn parse(&self, file: &File) -> Result<Statement, Error> {
+        match &file.data {
+            FileData::Table(data) => parse_xls(data),
+            FileData::Layout(layout) => parse_pdf(layout),
+            FileData::Text(_) => Err(Error::parse("error.parser.sbiind.positions_not_found")),
+            FileData::Tree(_) => Err(Error::parser_mismatch(
+                "error.parser.sbiind.unsupported_file_data",
+            )),
+        }
+    }
+}
+
+/// Tells how sure the parser is that the file is an SBI statement, or why
+/// it is not one
+///
+/// Other banks print the same headings, so only a statement naming an SBI
+/// branch is certain.
+fn recognise(file: &File) -> Result<Confidence, String> {
+    let preamble = match &file.data {
+        FileData::Table(data) if file.file_type.is_table() => match find_header(data) {
+            Some((header_index, _)) => preamble_xls(&data[..header_index]),
+            None => return Err("no row holds the headings of the transaction table".to_string()),
+        },
+        FileData::Layout(layout) if matches!(file.file_type, FileType::Pdf) => {
+            let rows = layout.rows();
+            match rows
+                .iter()
+                .position(|row| row.boundaries(&HEADER).is_some())
+            {
+                Some(header_index) => preamble_pdf(&rows[..header_index]),
+                None => {
+                    return Err("no line holds the headings of the transaction table".to_string())
+                }
+            }
+        }
+        FileData::Text(_) if matches!(file.file_type, FileType::Pdf) => {
+            return Err("the PDF was read without the positions of its text".to_string())
+        }
+        _ => return Err("the file is neither a table nor a PDF".to_string()),
+    };
+
+    if preamble.contains(IFSC_PREFIX) || preamble.contains("State Bank of India") {
+        Ok(Confidence::CERTAIN)
+    } else {
+        Ok(Confidence::FORMAT)
+    }
+}
+
+/// Finds the header row of the transaction table and the column it starts at
+fn find_header(table: &[Vec<String>]) -> Option<(usize, usize)> {
+    table.iter().enumerate().find_map(|(row_index, row)| {
+        row.windows(HEADER.len())
+            .position(|cells| cells.iter().map(|cell| cell.trim()).eq(HEADER))
+            .map(|column_index| (row_index, column_index))
+    })
+}
+
+/// Text above the table, one line per row with its cells separated by spaces
+fn preamble_xls(rows: &[Vec<String>]) -> String {
+    rows.iter()
+        .map(|row| {
+            row.iter()
+                .map(|cell| cell.trim())
+                .filter(|cell| !cell.is_empty())
+                .collect::<Vec<&str>>()
+                .join(" ")
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Text above the table, one line per row of the page
+fn preamble_pdf(rows: &[Row]) -> String {
+    rows.iter()
+        .map(|row| row.cells(&[]).concat())
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn parse_date(date: &str) -> Result<DateTimeUtc, Error> {
+    utils::datetime::parse_date(date, &DATE_FORMATS, TIMEZONE)
+}
+
+/// Reads an amount cell, which is empty when the amount is zero
+fn parse_amount(amount: &str) -> Result<Decimal, Error> {
+    let amount = amount.trim();
+    if amount.is_empty() {
+        return Ok(Decimal::ZERO);
+    }
+    amount
+        .replace(',', "")
+        .parse::<Decimal>()
+        .map_err(|_| Error::parse("error.parser.sbiind.invalid_amount").with_reason(amount))
+}
+
+/// Reads a balance cell, which every transaction fills
+fn parse_balance(balance: &str) -> Result<Decimal, Error> {
+    match balance.trim() {
+        "" => Err(Error::parse("error.parser.sbiind.balance_not_found")),
+        balance => parse_amount(balance)
+            .map_err(|_| Error::parse("error.parser.sbiind.invalid_balance").with_reason(balance)),
+    }
+}
+
+/// Reads a row of the transaction table, whose cells are in the order of
+/// [`HEADER`]
+///
+/// A balance that cannot be read is worked out from the previous one.
+fn parse_row(cells: &[String], previous: Option<Decimal>) -> Result<Transaction, Error> {
+    let date = parse_date(&cells[0])?;
+    let withdrawal = parse_amount(&cells[4])?;
+    let deposit = parse_amount(&cells[5])?;
+    let mut warnings = Vec::new();
+    let balance = derive_balance(
+        parse_balance(&cells[6]),
+        previous,
+        withdrawal,
+        deposit,
+        &mut warnings,
+    )?;
+
+    Ok(Transaction {
+        date,
+        description: squeeze(&cells[2]),
+        ref_no: match squeeze(&cells[3]).as_str() {
+            "-" => String::new(),
+            ref_no => ref_no.to_string(),
+        },
+        withdrawal,
+        deposit,
+        balance,
+        warnings,
+    })
+}
+
+/// Collapses the line breaks and runs of spaces SBI pads its cells with
+fn squeeze(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+/// Account and period details printed above the transaction table, as
+/// `Label : value` lines
+struct Details {
+    account_type: AccountType,
+    account_number: Option<String>,
+    ifsc: Option<String>,
+    branch: Option<String>,
+    holder_name: Option<String>,
+    date: Option<DateTimeUtc>,
+    period_start: Option<DateTimeUtc>,
+    period_end: Option<DateTimeUtc>,
+    opening_balance: Option<Decimal>,
+}
+
+impl Details {
+    fn into_statement(
+        self,
+        transactions: Vec<Transaction>,
+        skipped_rows: Vec<SkippedRow>,
+    ) -> Statement {
+        Statement {
+            institution: Some(InstitutionName::StateBankOfIndia),
+            account_type: self.account_type,
+            masked_account_number: self.account_number.as_deref().map(account_number::mask),
+            account_number: self.account_number,
+            ifsc: self.ifsc,
+            branch: self.branch,
+            holder_name: self.holder_name,
+            currency: None,
+            date: self
+                .period_end
+                .or(self.date)
+                .unwrap_or_else(utils::datetime::get_current_datetime),
+            timezone: TIMEZONE,
+            period_start: self.period_start,
+            period_end: self.period_end,
+            opening_balance: self.opening_balance,
+            closing_balance: None,
+            totals: None,
+            source_hash: None,
+            transactions,
+            skipped_rows,
+            warnings: Vec::new(),
+        }
+    }
+}
+
+fn parse_details(text: &str) -> Result<Details, Error> {
+    let capture = |pattern: &str, key: &'static str| -> Result<Option<String>, Error> {
+        Ok(Regex::new(pattern)
+            .map_err(|_| Error::parse(key))?
+            .captures(text)
+            .and_then(|cap| cap.get(1))
+            .map(|m| squeeze(m.as_str()))
+            .filter(|value| !value.is_empty()))
+    };
+    let date = |pattern: &str, key: &'static str| -> Result<Option<DateTimeUtc>, Error> {
+        Ok(capture(pattern, key)?.and_then(|date| parse_date(&date).ok()))
+    };
+
+    // Descriptions such as `REGULAR SB CHQ-INDIVIDUALS` or `CA-GEN-PUB-OTH`
+    // name the scheme by a label of their own
+    let account_type = capture(
+        r"(?im)^\s*Account\s*Description\s*:[^\S\n]*(.*)$",
+        "error.parser.sbiind.regex_creation_failed_1",
+    )?
+    .map(|description| {
+        let labels: Vec<String> = description
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .map(|label| label.to_uppercase())
+            .collect();
+        let has_label = |names: &[&str]| labels.iter().any(|label| names.contains(&label.as_str()));
+        if has_label(&SAVINGS_LABELS) {
+            AccountType::SavingsAccount
+        } else if has_label(&CURRENT_LABELS) {
+            AccountType::CheckingAccount
+        } else {
+            AccountType::Unknown
+        }
+    })
+    .unwrap_or(AccountType::Unknown);
+
+    // Account numbers are padded to 17 digits with zeros, kept as printed,
+    // and sometimes prefixed with `_` to keep spreadsheets from reading them
+    // as numbers
+    let account_number = capture(
+        r"(?im)^\s*Account\s*Number\s*:\s*_?(\d+)",
+        "error.parser.sbiind.regex_creation_failed_2",
+    )?;
+
+    // Statements print the period either as its own lines, or as a sentence
+    let period_start = date(
+        r"(?im)(?:^\s*Start\s*Date\s*:|Statement\s*from)\s*(\d{1,2}[\s/-]\w{2,3}[\s/-]\d{4})",
+        "error.parser.sbiind.regex_creation_failed_3",
+    )?;
+    let period_end = date(
+        r"(?im)(?:^\s*End\s*Date\s*:|Statement\s*from.*?\bto)\s*(\d{1,2}[\s/-]\w{2,3}[\s/-]\d{4})",
+        "error.parser.sbiind.regex_creation_failed_4",
+    )?;
+
+    Ok(Details {
+        account_type,
+        account_number,
+        ifsc: capture(
+            r"(?i)IFS\s*C(?:ode)?\s*:?\s*([A-Z]{4}0[A-Z0-9]{6})",
+            "error.parser.sbiind.regex_creation_failed_5",
+        )?,
+        branch: capture(
+            r"(?im)^\s*Branch\s*:[^\S\n]*(.*)$",
+            "error.parser.sbiind.regex_creation_failed_6",
+        )?,
+        holder_name: capture(
+            r"(?im)^\s*Account\s*Name\s*:[^\S\n]*(.*)$",
+            "error.parser.sbiind.regex_creation_failed_7",
+        )?,
+        date: date(
+            r"(?im)^\s*Date\s*:\s*(\d{1,2}[\s/-]\w{2,3}[\s/-]\d{4})",
+            "error.parser.sbiind.regex_creation_failed_8",
+        )?,
+        period_start,
+        period_end,
+        opening_balance: capture(
+            r"(?im)^\s*Balance\s*as\s*on.*?:\s*(-?[\d,]+\.\d{2})",
+            "error.parser.sbiind.regex_creation_failed_9",
+        )?
+        .and_then(|balance| parse_amount(&balance).ok()),
+    })
+}
+
+fn parse_xls(table: &[Vec<String>]) -> Result<Statement, Error> {
+    let (header_index, column) =
+        find_header(table).ok_or(Error::parse("error.parser.sbiind.start_of_data_not_found"))?;
+    let details = parse_details(&preamble_xls(&table[..header_index]))?;
+
+    let mut transactions: Vec<Transaction> = Vec::new();
+    let mut skipped_rows = Vec::new();
+
+    // The table ends at the first blank row, or at the note below it that
+    // only fills the first cell
+    for (index, row) in table.iter().enumerate().skip(header_index + 1) {
+        let Some(cells) = row.get(column..column + HEADER.len()) else {
+            break;
+        };
+        if cells[1..].iter().all(|cell| cell.trim().is_empty()) {
+            break;
+        }
+
+        let previous = transactions
+            .last()
+            .map(|transaction| transaction.balance)
+            .or(details.opening_balance);
+        match parse_row(cells, previous) {
+            Ok(transaction) => transactions.push(transaction),
+            Err(error) => skipped_rows.push(SkippedRow::from_error(
+                &error.at(Location::Row(index + 1)),
+                cells.join(" | "),
+            )),
+        }
+    }
+
+    Ok(details.into_statement(transactions, skipped_rows))
+}
+
+fn parse_pdf(layout: &Layout) -> Result<Statement, Error> {
+    let rows = layout.rows();
+    let header_index = rows
+        .iter()
+        .position(|row| row.boundaries(&HEADER).is_some())
+        .ok_or(Error::parse("error.parser.sbiind.start_of_data_not_found"))?;
+    let details = parse_details(&preamble_pdf(&rows[..header_index]))?;
+
+    let (transactions, skipped_rows) = parse_trnx_pdf(&rows, details.opening_balance)?;
+
+    Ok(details.into_statement(transactions, skipped_rows))
+}
+
+/// Reads the transaction table from the rows of the statement, by the
+/// column positions of its header
+fn parse_trnx_pdf(rows: &[Row], opening_balance: Option<Decimal>) -> Result<Rows, Error> {
+    let date = Regex::new(r"^\d{1,2}[\s/-]\w{2,3}[\s/-]\d{4}$")
+        .map_err(|_| Error::parse("error.parser.sbiind.regex_creation_failed_10"))?;
+
+    let mut boundaries: Option<Vec<f32>> = None;
+    let mut transactions: Vec<Transaction> = Vec::new();
+    let mut skipped_rows: Vec<SkippedRow> = Vec::new();
+    // Whether the last row starting with a date was skipped, so that the
+    // rows continuing it are not taken for the previous transaction's
+    let mut skipped_last = false;
+
+    for row in rows {
+        // The header is repeated on every page that continues the table
+        if let Some(header) = row.boundaries(&HEADER) {
+            boundaries = Some(header);
+            continue;
+        }
+
+        let Some(columns) = &boundaries else {
+            continue;
+        };
+        let cells = row.cells(columns);
+
+        if date.is_match(&cells[0]) {
+            let row_number = transactions.len() + skipped_rows.len() + 1;
+            let previous = transactions
+                .last()
+                .map(|transaction| transaction.balance)
+                .or(opening_balance);
+            match parse_row(&cells, previous) {
+                Ok(transaction) => {
+                    transactions.push(transaction);
+                    skipped_last = false;
+                }
+                Err(error) => {
+                    skipped_rows.push(SkippedRow::from_error(
+                        &error.at(Location::Row(row_number)),
+                        cells.join(" | "),
+                    ));
+                    skipped_last = true;
+                }
+            }
+            continue;
+        }
+
+        // Long descriptions and references wrap onto rows of their own,
+        // which start right of the dates and leave the amounts empty
+        let is_continuation = row.runs.first().is_some_and(|run| run.x >= columns[1])
+            && cells[4..].iter().all(|cell| cell.is_empty());
+        match (transactions.last_mut(), skipped_rows.last_mut()) {
+            (_, Some(skipped)) if is_continuation && skipped_last => {
+                skipped.content.push_str(" | ");
+                skipped.content.push_str(&cells.join(" | "));
+            }
+            (Some(transaction), _) if is_continuation => {
+                for (field, cell) in [
+                    (&mut transaction.description, &cells[2]),
+                    (&mut transaction.ref_no, &cells[3]),
+                ] {
+                    if !cell.is_empty() {
+                        if !field.is_empty() {
+                            field.push(' ');
+                        }
+                        field.push_str(&squeeze(cell));
+                    }
+                }
+            }
+            // Anything else ends the table on this page
+            _ => boundaries = None,
+        }
+    }
+
+    Ok((transactions, skipped_rows))
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,366 @@
+use crate::utils::datetime::{parse_date, DateFormat, IST};
+use sea_orm::prelude::DateTimeUtc;
+
+/// Start of the day in India Standard Time, where statement dates fall
+pub fn _date(date: &str) -> DateTimeUtc {
+    parse_date(date, &[DateFormat::D_MMM_YYYY], IST).unwrap()
+}
+
+#[cfg(test)]
+mod xls {
+    use crate::{
+        error::Location,
+        models::entities::sea_orm_active_enums::{AccountType, InstitutionName},
+        readers::{
+            parsers::{
+                sources::sbiind::{tests::_date, SbiInd},
+                types::{Confidence, StatementParser},
+                ParserRegistry,
+            },
+            types::{File, FileData, FileType},
+        },
+    };
+    use rust_decimal_macros::dec;
+
+    fn _table(rows: &[&[&str]]) -> File {
+        File {
+            file_type: FileType::Xls,
+            data: FileData::Table(
+                rows.iter()
+                    .map(|row| row.iter().map(|cell| cell.to_string()).collect())
+                    .collect(),
+            ),
+        }
+    }
+
+    const HEADER: &[&str] = &[
+        "Txn Date",
+        "Value Date",
+        "Description",
+        "Ref No./Cheque No.",
+        "Debit",
+        "Credit",
+        "Balance",
+    ];
+
+    fn _statement(rows: &[&[&str]]) -> File {
+        let mut table: Vec<&[&str]> = vec![
+            &["Account Name", ":", "Mr. TEST USER"],
+            &["Address", ":", "1 TEST STREET"],
+            &["Date", ":", "1 Feb 2023"],
+            &["Account Number", ":", "_00000012345678901"],
+            &["Account Description", ":", "REGULAR SB CHQ-INDIVIDUALS"],
+            &["Branch", ":", "TEST BRANCH"],
+            &["IFS Code", ":", "SBIN0001234"],
+            &["Balance as on 1 Jan 2023", ":", "10,000.00"],
+            &[],
+            &["Start Date", ":", "1 Jan 2023"],
+            &["End Date", ":", "31 Jan 2023"],
+            HEADER,
+        ];
+        table.extend(rows);
+        _table(&table)
+    }
+
+    #[test]
+    fn test_identify_valid_file() {
+        let parser = SbiInd;
+        assert_eq!(
+            parser.identify(&_statement(&[])).unwrap(),
+            Confidence::CERTAIN
+        );
+
+        // The layout is SBI's, but nothing above it names the bank
+        let file = _table(&[&["Account Name", ":", "Mr. TEST USER"], HEADER]);
+        assert_eq!(parser.identify(&file).unwrap(), Confidence::FORMAT);
+
+        let parsers = ParserRegistry::default();
+        assert_eq!(parsers.identify(&_statement(&[])).unwrap().name(), "sbiind");
+    }
+
+    #[test]
+    fn test_identify_invalid_file_content() {
+        let parser = SbiInd;
+        let file = _table(&[
+            &["State Bank of India"],
+            &["Date", "Description", "Debit", "Credit", "Balance"],
+        ]);
+        assert_eq!(parser.identify(&file).unwrap(), Confidence::NONE);
+        assert_eq!(
+            parser.explain_mismatch(&file).as_deref(),
+            Some("no row holds the headings of the transaction table")
+        );
+    }
+
+    #[test]
+    fn test_parse_metadata() {
+        let statement = SbiInd.parse(&_statement(&[])).unwrap();
+
+        assert_eq!(
+            statement.institution,
+            Some(InstitutionName::StateBankOfIndia)
+        );
+        assert_eq!(statement.account_type, AccountType::SavingsAccount);
+        assert_eq!(
+            statement.account_number.as_deref(),
+            Some("00000012345678901")
+        );
+        assert!(statement.masked_account_number.is_some());
+        assert_eq!(statement.ifsc.as_deref(), Some("SBIN0001234"));
+        assert_eq!(statement.branch.as_deref(), Some("TEST BRANCH"));
+        assert_eq!(statement.holder_name.as_deref(), Some("Mr. TEST USER"));
+        assert_eq!(statement.period_start, Some(_date("1 Jan 2023")));
+        assert_eq!(statement.period_end, Some(_date("31 Jan 2023")));
+        assert_eq!(statement.date, _date("31 Jan 2023"));
+        assert_eq!(statement.opening_balance, Some(dec!(10000.00)));
+        assert!(statement.transactions.is_empty());
+    }
+
+    #[test]
+    fn test_parse_account_type() {
+        for (description, account_type) in [
+            ("REGULAR SB CHQ-INDIVIDUALS", AccountType::SavingsAccount),
+            (
+                "SBCHQ-GEN-PUB-IND-NONRURAL-INR",
+                AccountType::SavingsAccount,
+            ),
+            ("CA-GEN-PUB-OTH", AccountType::CheckingAccount),
+            ("CURRENT ACCOUNT", AccountType::CheckingAccount),
+            // Labels are matched whole, not as parts of other words
+            ("CASH CREDIT-SBI-MSME", AccountType::Unknown),
+        ] {
+            let file = _table(&[&["Account Description", ":", description], HEADER]);
+            let statement = SbiInd.parse(&file).unwrap();
+            assert_eq!(statement.account_type, account_type, "{}", description);
+        }
+    }
+
+    #[test]
+    fn test_parse_valid_file() {
+        let file = _statement(&[
+            &[
+                "2 Jan 2023",
+                "2 Jan 2023",
+                "BY TRANSFER-\nNEFT*TESTBANK*SALARY",
+                "TRANSFER FROM 1234",
+                "",
+                "50,000.00",
+                "60,000.00",
+            ],
+            &[
+                "5 Jan 2023",
+                "5 Jan 2023",
+                "TO TRANSFER-UPI/DR/301234/SHOP",
+                "-",
+                "1,250.50",
+                " ",
+                "58,749.50",
+            ],
+            &[],
+            &["**This is a computer generated statement"],
+        ]);
+
+        let statement = SbiInd.parse(&file).unwrap();
+        assert_eq!(statement.transactions.len(), 2);
+        assert!(statement.skipped_rows.is_empty());
+
+        let transaction = &statement.transactions[0];
+        assert_eq!(transaction.date, _date("2 Jan 2023"));
+        assert_eq!(transaction.description, "BY TRANSFER- NEFT*TESTBANK*SALARY");
+        assert_eq!(transaction.ref_no, "TRANSFER FROM 1234");
+        assert_eq!(transaction.withdrawal, dec!(0));
+        assert_eq!(transaction.deposit, dec!(50000.00));
+        assert_eq!(transaction.balance, dec!(60000.00));
+
+        let transaction = &statement.transactions[1];
+        assert_eq!(transaction.ref_no, "");
+        assert_eq!(transaction.withdrawal, dec!(1250.50));
+        assert_eq!(transaction.balance, dec!(58749.50));
+    }
+
+    #[test]
+    fn test_parse_skips_unreadable_rows() {
+        let file = _statement(&[
+            &[
+                "2 Jan 2023",
+                "2 Jan 2023",
+                "ATM WDL",
+                "-",
+                "1O0.00",
+                "",
+                "9,900.00",
+            ],
+            &[
+                "3 Jan 2023",
+                "3 Jan 2023",
+                "ATM WDL",
+                "-",
+                "100.00",
+                "",
+                "9,9OO.00",
+            ],
+        ]);
+
+        let statement = SbiInd.parse(&file).unwrap();
+        let skipped = &statement.skipped_rows[0];
+        assert_eq!(skipped.key, "error.parser.sbiind.invalid_amount");
+        assert_eq!(skipped.location, Some(Location::Row(13)));
+        assert_eq!(skipped.reason.as_deref(), Some("1O0.00"));
+
+        // The balance is worked out from the opening balance
+        let transaction = &statement.transactions[0];
+        assert_eq!(transaction.balance, dec!(9900.00));
+        assert_eq!(
+            transaction.warnings[0].key,
+            "error.parser.sbiind.invalid_balance"
+        );
+    }
+}
+
+#[cfg(test)]
+mod pdf {
+    use crate::{
+        models::entities::sea_orm_active_enums::AccountType,
+        readers::{
+            parsers::{
+                sources::sbiind::{tests::_date, SbiInd},
+                types::{Confidence, StatementParser},
+            },
+            types::{File, FileData, FileType, Layout, TextRun},
+        },
+    };
+    use rust_decimal_macros::dec;
+
+    fn _run(x: f32, y: f32, text: &str) -> TextRun {
+        TextRun {
+            page: 1,
+            x,
+            y,
+            width: text.len() as f32 * 5.0,
+            font_size: 10.0,
+            text: text.to_string(),
+        }
+    }
+
+    fn _header(y: f32) -> Vec<TextRun> {
+        vec![
+            _run(30.0, y, "Txn Date"),
+            _run(90.0, y, "Value Date"),
+            _run(150.0, y, "Description"),
+            _run(300.0, y, "Ref No./Cheque"),
+            _run(300.0 + 14.0 * 5.0 + 2.0, y, "No."),
+            _run(420.0, y, "Debit"),
+            _run(480.0, y, "Credit"),
+            _run(540.0, y, "Balance"),
+        ]
+    }
+
+    fn _layout_pdf_data() -> FileData {
+        let mut runs = vec![
+            _run(30.0, 800.0, "Account Name : Mr. TEST USER"),
+            _run(30.0, 785.0, "Account Number : 00000012345678901"),
+            _run(30.0, 770.0, "Account Description : CA-GEN-PUB-OTH"),
+            _run(30.0, 755.0, "IFS Code : SBIN0001234"),
+            _run(30.0, 740.0, "Balance as on 1 Jan 2023 : 1,000.00"),
+            _run(
+                30.0,
+                725.0,
+                "Account Statement from 1 Jan 2023 to 31 Jan 2023",
+            ),
+        ];
+        runs.extend(_header(700.0));
+        runs.extend([
+            _run(30.0, 680.0, "3 Jan 2023"),
+            _run(90.0, 680.0, "3 Jan 2023"),
+            _run(150.0, 680.0, "BY TRANSFER-"),
+            _run(300.0, 680.0, "TRANSFER FROM"),
+            _run(480.0, 680.0, "500.00"),
+            _run(540.0, 680.0, "1,500.00"),
+            _run(150.0, 668.0, "NEFT*REFUND"),
+            _run(300.0, 668.0, "4897"),
+            _run(30.0, 650.0, "4 Jan 2023"),
+            _run(90.0, 650.0, "4 Jan 2023"),
+            _run(150.0, 650.0, "ATM WDL"),
+            _run(300.0, 650.0, "-"),
+            _run(420.0, 650.0, "200.00"),
+            _run(540.0, 650.0, "1,300.00"),
+            _run(
+                30.0,
+                600.0,
+                "Please do not share your ATM or OTP with anyone",
+            ),
+        ]);
+        FileData::Layout(Layout {
+            text: String::new(),
+            runs,
+        })
+    }
+
+    #[test]
+    fn test_identify_valid_file() {
+        let file = File {
+            file_type: FileType::Pdf,
+            data: _layout_pdf_data(),
+        };
+        assert_eq!(SbiInd.identify(&file).unwrap(), Confidence::CERTAIN);
+    }
+
+    #[test]
+    fn test_identify_invalid_file_content() {
+        let file = File {
+            file_type: FileType::Pdf,
+            data: FileData::Layout(Layout {
+                text: String::new(),
+                runs: vec![_run(30.0, 800.0, "State Bank of India")],
+            }),
+        };
+        assert_eq!(SbiInd.identify(&file).unwrap(), Confidence::NONE);
+    }
+
+    #[test]
+    fn test_parse_valid_file() {
+        let file = File {
+            file_type: FileType::Pdf,
+            data: _layout_pdf_data(),
+        };
+
+        let statement = SbiInd.parse(&file).unwrap();
+        assert_eq!(statement.account_type, AccountType::CheckingAccount);
+        assert_eq!(
+            statement.account_number.as_deref(),
+            Some("00000012345678901")
+        );
+        assert_eq!(statement.holder_name.as_deref(), Some("Mr. TEST USER"));
+        assert_eq!(statement.ifsc.as_deref(), Some("SBIN0001234"));
+        assert_eq!(statement.opening_balance, Some(dec!(1000.00)));
+        assert_eq!(statement.period_start, Some(_date("1 Jan 2023")));
+        assert_eq!(statement.period_end, Some(_date("31 Jan 2023")));
+
+        // The wrapped description and reference belong to the first row,
+        // and the note below the table ends it
+        assert_eq!(statement.transactions.len(), 2);
+        let transaction = &statement.transactions[0];
+        assert_eq!(transaction.date, _date("3 Jan 2023"));
+        assert_eq!(transaction.description, "BY TRANSFER- NEFT*REFUND");
+        assert_eq!(transaction.ref_no, "TRANSFER FROM 4897");
+        assert_eq!(transaction.deposit, dec!(500.00));
+        assert_eq!(transaction.balance, dec!(1500.00));
+
+        let transaction = &statement.transactions[1];
+        assert_eq!(transaction.ref_no, "");
+        assert_eq!(transaction.withdrawal, dec!(200.00));
+        assert_eq!(transaction.balance, dec!(1300.00));
+    }
+
+    #[test]
+    fn test_parse_text_only_file() {
+        let file = File {
+            file_type: FileType::Pdf,
+            data: FileData::Text("Txn Date Value Date Description".to_string()),
+        };
+        assert_eq!(
+            SbiInd.parse(&file).unwrap_err().key(),
+            "error.parser.sbiind.positions_not_found"
+        );
+    }
+}
@@ -0,0 +1,544 @@
+//! Parser for table based exports described by a template
+//!
+//! A template is a TOML or YAML document telling how to recognise an export
+//! and where its transactions are, so that a simple institution can be read
+//! without writing a parser for it:
+//!
+//! ```toml
+//! name = "axisind"
+//! institution = "Axis"
+//! account_type = "SavingsAccount"
+//! file_types = ["xls", "xlsx", "csv"]
+//! markers = ["AXIS BANK"]
+//! header = ["Tran Date", "CHQNO", "PARTICULARS", "DR", "CR", "BAL"]
+//! end_marker = "TRANSACTION TOTAL"
+//! date_formats = ["%d-%m-%Y"]
+//!
+//! [columns]
+//! date = "Tran Date"
+//! description = "PARTICULARS"
+//! ref_no = "CHQNO"
+//! balance = "BAL"
+//!
+//! [amounts]
+//! style = "debit_credit"
+//! debit = "DR"
+//! credit = "CR"
+//!
+//! [details]
+//! account_number = 'Account No\s*:\s*(\d+)'
+//! ```
+//!
+//! Columns are named by their headings in `header`, the row the transaction
+//! table starts after. Its rows are read until a row with a cell holding the
+//! `end_marker`, or when there is none, until the first empty row.
+use std::{borrow::Cow, fs, path::Path};
+
+use regex::Regex;
+use sea_orm::{
+    prelude::{DateTimeUtc, Decimal},
+    sqlx::types::chrono::FixedOffset,
+};
+use serde::Deserialize;
+
+use crate::{
+    error::{Error, Location},
+    models::entities::sea_orm_active_enums::{AccountType, InstitutionName},
+    readers::{
+        parsers::types::{
+            derive_balance, Confidence, ParseWarning, SkippedRow, Statement, StatementParser,
+            Transaction,
+        },
+        types::{File, FileData, FileType},
+    },
+    utils::{
+        self, account_number,
+        datetime::{DateFormat, IST},
+    },
+};
+
+/// Description of a table based export, as written in a template file
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Template {
+    /// Name the parser is registered under
+    pub name: String,
+    pub institution: Option<InstitutionName>,
+    /// Type of the accounts the export is for, `Unknown` if not given
+    pub account_type: Option<AccountType>,
+    #[serde(default = "default_version")]
+    pub version: u32,
+    /// Kinds of table files the export comes as, such as `xls` or `csv`
+    pub file_types: Vec<FileType>,
+    /// UTC offset the dates are written in, such as `+05:30`; IST if not given
+    pub timezone: Option<String>,
+    /// ISO 4217 code of the currency the amounts are in
+    pub currency: Option<String>,
+    /// Texts that all have to be found in the cells above the header
+    #[serde(default)]
+    pub markers: Vec<String>,
+    /// Headings of the transaction table, each matching a cell of its row
+    pub header: Vec<String>,
+    /// Rows between the header and the first transaction
+    #[serde(default)]
+    pub skip_rows: usize,
+    /// Text of a cell of the row that ends the transaction table
+    pub end_marker: Option<String>,
+    /// `chrono` formats the dates are written in, or `excel_serial` for the
+    /// dates workbooks store as numbers
+    pub date_formats: Vec<String>,
+    pub columns: Columns,
+    pub amounts: Amounts,
+    #[serde(default)]
+    pub details: Details,
+}
+
+fn default_version() -> u32 {
+    1
+}
+
+/// Headings of the columns a transaction is read from
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Columns {
+    pub date: String,
+    pub description: String,
+    pub ref_no: Option<String>,
+    pub balance: String,
+}
+
+/// How the amount of a transaction is written
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "style", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Amounts {
+    /// Withdrawals and deposits in columns of their own
+    DebitCredit { debit: String, credit: String },
+    /// A single column, negative for withdrawals
+    Signed { amount: String },
+    /// A single column, and a column telling withdrawals from deposits
+    Indicator {
+        amount: String,
+        indicator: String,
+        /// Values of the indicator marking withdrawals, such as `Dr`, matched
+        /// regardless of case
+        debit: Vec<String>,
+    },
+}
+
+/// Regular expressions reading the statement details from the cells above the
+/// header, each capturing the value in its first group
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Details {
+    pub account_number: Option<String>,
+    pub ifsc: Option<String>,
+    pub branch: Option<String>,
+    pub holder_name: Option<String>,
+    pub period_start: Option<String>,
+    pub period_end: Option<String>,
+}
+
+/// Compiled [`Details`]
+#[derive(Debug)]
+struct Patterns {
+    account_number: Option<Regex>,
+    ifsc: Option<Regex>,
+    branch: Option<Regex>,
+    holder_name: Option<Regex>,
+    period_start: Option<Regex>,
+    period_end: Option<Regex>,
+}
+
+/// Reads the exports a [`Template`] describes
+#[derive(Debug)]
+pub struct TemplateParser {
+    template: Template,
+    timezone: FixedOffset,
+    date_formats: Vec<DateFormat>,
+    patterns: Patterns,
+}
+
+impl TemplateParser {
+    /// Makes a parser out of a template, checking it can be used
+    ///
+    /// # Errors
+    /// Returns an error if the template names a file type that is not a
+    /// table, a column missing from its header, no date format, or an
+    /// invalid timezone or detail pattern
+    pub fn new(template: Template) -> Result<Self, Error> {
+        let invalid = |key| Error::parse(key).with_reason(&template.name);
+
+        if template.file_types.is_empty()
+            || !template
+                .file_types
+                .iter()
+                .all(|file_type| file_type.is_table())
+        {
+            return Err(invalid("error.parser.template.unsupported_file_type"));
+        }
+        if template.header.is_empty() {
+            return Err(invalid("error.parser.template.header_missing"));
+        }
+        let columns = &template.columns;
+        let mut names = vec![&columns.date, &columns.description, &columns.balance];
+        names.extend(&columns.ref_no);
+        match &template.amounts {
+            Amounts::DebitCredit { debit, credit } => names.extend([debit, credit]),
+            Amounts::Signed { amount } => names.push(amount),
+            Amounts::Indicator {
+                amount, indicator, ..
+            } => names.extend([amount, indicator]),
+        }
+        if let Some(name) = names.iter().find(|name| !template.header.contains(name)) {
+            return Err(Error::parse("error.parser.template.column_not_in_header")
+                .with_reason(format!("{}: {}", template.name, name)));
+        }
+        if template.date_formats.is_empty() {
+            return Err(invalid("error.parser.template.date_formats_missing"));
+        }
+
+        let timezone = match &template.timezone {
+            Some(timezone) => timezone.parse::<FixedOffset>().map_err(|_| {
+                Error::parse("error.parser.template.invalid_timezone").with_reason(timezone)
+            })?,
+            None => IST,
+        };
+        let date_formats = template
+            .date_formats
+            .iter()
+            .map(|format| match format.as_str() {
+                "excel_serial" => DateFormat::ExcelSerial,
+                pattern => DateFormat::Pattern(Cow::Owned(pattern.to_string())),
+            })
+            .collect();
+
+        let compile = |pattern: &Option<String>| {
+            pattern
+                .as_deref()
+                .map(|pattern| {
+                    Regex::new(pattern).map_err(|error| {
+                        Error::parse("error.parser.template.invalid_pattern").with_reason(error)
+                    })
+                })
+                .transpose()
+        };
+        let details = &template.details;
+        let patterns = Patterns {
+            account_number: compile(&details.account_number)?,
+            ifsc: compile(&details.ifsc)?,
+            branch: compile(&details.branch)?,
+            holder_name: compile(&details.holder_name)?,
+            period_start: compile(&details.period_start)?,
+            period_end: compile(&details.period_end)?,
+        };
+
+        Ok(TemplateParser {
+            template,
+            timezone,
+            date_formats,
+            patterns,
+        })
+    }
+
+    /// Reads a template written in TOML
+    pub fn from_toml(template: &str) -> Result<Self, Error> {
+        let template = toml::from_str(template).map_err(|error| {
+            Error::parse("error.parser.template.invalid_toml").with_reason(error)
+        })?;
+        Self::new(template)
+    }
+
+    /// Reads a template written in YAML
+    pub fn from_yaml(template: &str) -> Result<Self, Error> {
+        let template = serde_yaml::from_str(template).map_err(|error| {
+            Error::parse("error.parser.template.invalid_yaml").with_reason(error)
+        })?;
+        Self::new(template)
+    }
+
+    /// Reads a template file, as TOML or YAML depending on its extension
+    /// (`.toml`, `.yaml` or `.yml`)
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let read = || {
+            fs::read_to_string(path).map_err(|error| Error::Io {
+                key: "error.parser.template.cannot_read_file",
+                reason: format!("{}: {}", path.display(), error),
+            })
+        };
+
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => Self::from_toml(&read()?),
+            Some("yaml" | "yml") => Self::from_yaml(&read()?),
+            _ => Err(
+                Error::unsupported_format("error.parser.template.unsupported_extension")
+                    .with_reason(path.display()),
+            ),
+        }
+    }
+
+    pub fn template(&self) -> &Template {
+        &self.template
+    }
+
+    /// Returns the index of the header row, and the index of each of its
+    /// headings in it
+    fn find_header(&self, table: &[Vec<String>]) -> Option<(usize, Vec<usize>)> {
+        table.iter().enumerate().find_map(|(index, row)| {
+            let positions = self
+                .template
+                .header
+                .iter()
+                .map(|heading| row.iter().position(|cell| cell.trim() == heading))
+                .collect::<Option<Vec<usize>>>()?;
+            Some((index, positions))
+        })
+    }
+
+    /// Certain when the header and all the markers are found, but only as sure
+    /// as a standard format without markers, since other exports may share
+    /// the headings; otherwise tells why the file is not the export
+    fn recognise(&self, file: &File) -> Result<Confidence, String> {
+        let FileData::Table(table) = &file.data else {
+            return Err("the file is not a table".to_string());
+        };
+        let (header_index, _) = self
+            .find_header(table)
+            .ok_or("no row holds all the headings of the header".to_string())?;
+
+        let preamble = &table[..header_index];
+        let missing = self.template.markers.iter().find(|marker| {
+            !preamble
+                .iter()
+                .flatten()
+                .any(|cell| cell.contains(marker.as_str()))
+        });
+        match missing {
+            Some(marker) => Err(format!(
+                "the cells above the header do not contain `{}`",
+                marker
+            )),
+            None if self.template.markers.is_empty() => Ok(Confidence::FORMAT),
+            None => Ok(Confidence::CERTAIN),
+        }
+    }
+
+    fn parse_table(&self, table: &[Vec<String>]) -> Result<Statement, Error> {
+        let (header_index, positions) = self
+            .find_header(table)
+            .ok_or(Error::parse("error.parser.template.header_not_found"))?;
+        let column = |heading: &str| {
+            self.template
+                .header
+                .iter()
+                .position(|name| name == heading)
+                .map(|index| positions[index])
+        };
+        let cell = |row: &[String], heading: &str| -> String {
+            column(heading)
+                .and_then(|index| row.get(index))
+                .map(|cell| cell.trim().to_string())
+                .unwrap_or_default()
+        };
+
+        let columns = &self.template.columns;
+        let parse_row = |row: &[String], previous: Option<Decimal>| -> Result<Transaction, Error> {
+            let (withdrawal, deposit) = match &self.template.amounts {
+                Amounts::DebitCredit { debit, credit } => (
+                    parse_amount(&cell(row, debit))?,
+                    parse_amount(&cell(row, credit))?,
+                ),
+                Amounts::Signed { amount } => {
+                    let amount = parse_amount(&cell(row, amount))?;
+                    if amount.is_sign_negative() {
+                        (-amount, Decimal::ZERO)
+                    } else {
+                        (Decimal::ZERO, amount)
+                    }
+                }
+                Amounts::Indicator {
+                    amount,
+                    indicator,
+                    debit,
+                } => {
+                    let amount = parse_amount(&cell(row, amount))?.abs();
+                    let indicator = cell(row, indicator);
+                    if debit
+                        .iter()
+                        .any(|value| value.eq_ignore_ascii_case(&indicator))
+                    {
+                        (amount, Decimal::ZERO)
+                    } else {
+                        (Decimal::ZERO, amount)
+                    }
+                }
+            };
+
+            let mut warnings = Vec::new();
+            let balance = match cell(row, &columns.balance) {
+                balance if balance.is_empty() => {
+                    Err(Error::parse("error.parser.template.balance_not_found"))
+                }
+                balance => parse_amount(&balance).map_err(|_| {
+                    Error::parse("error.parser.template.invalid_balance").with_reason(balance)
+                }),
+            };
+            let balance = derive_balance(balance, previous, withdrawal, deposit, &mut warnings)?;
+
+            Ok(Transaction {
+                date: self.parse_date(&cell(row, &columns.date))?,
+                description: cell(row, &columns.description),
+                ref_no: columns
+                    .ref_no
+                    .as_deref()
+                    .map(|ref_no| cell(row, ref_no))
+                    .unwrap_or_default(),
+                withdrawal,
+                deposit,
+                balance,
+                warnings,
+            })
+        };
+
+        let mut transactions: Vec<Transaction> = Vec::new();
+        let mut skipped_rows = Vec::new();
+        for (index, row) in table
+            .iter()
+            .enumerate()
+            .skip(header_index + 1 + self.template.skip_rows)
+        {
+            let is_empty = row.iter().all(|cell| cell.trim().is_empty());
+            match &self.template.end_marker {
+                Some(marker) if row.iter().any(|cell| cell.contains(marker.as_str())) => break,
+                Some(_) if is_empty => continue,
+                None if is_empty => break,
+                _ => {}
+            }
+
+            let previous = transactions.last().map(|transaction| transaction.balance);
+            match parse_row(row, previous) {
+                Ok(transaction) => transactions.push(transaction),
+                Err(error) => skipped_rows.push(SkippedRow::from_error(
+                    &error.at(Location::Row(index + 1)),
+                    row.join(" | "),
+                )),
+            }
+        }
+
+        // Every cell above the header is read as a line of its own
+        let preamble = table[..header_index]
+            .iter()
+            .flatten()
+            .map(|cell| cell.trim())
+            .collect::<Vec<&str>>()
+            .join("\n");
+        let capture = |pattern: &Option<Regex>| {
+            pattern
+                .as_ref()
+                .and_then(|pattern| pattern.captures(&preamble))
+                .and_then(|captures| captures.get(1))
+                .map(|value| value.as_str().trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        // A period date that cannot be read leaves the period open, rather
+        // than the statement unread
+        let mut warnings = Vec::new();
+        let mut period_date = |pattern: &Option<Regex>| {
+            capture(pattern).and_then(|date| match self.parse_date(&date) {
+                Ok(date) => Some(date),
+                Err(error) => {
+                    warnings.push(ParseWarning::from_error(&error));
+                    None
+                }
+            })
+        };
+        let period_start = period_date(&self.patterns.period_start);
+        let period_end = period_date(&self.patterns.period_end);
+        // A masked capture is kept as the masked account number only
+        let (account_number, masked_account_number) = match capture(&self.patterns.account_number) {
+            Some(number) if account_number::is_masked(&number) => (None, Some(number)),
+            number => (number.clone(), number.as_deref().map(account_number::mask)),
+        };
+
+        Ok(Statement {
+            institution: self.template.institution.clone(),
+            account_type: self
+                .template
+                .account_type
+                .clone()
+                .unwrap_or(AccountType::Unknown),
+            account_number,
+            masked_account_number,
+            ifsc: capture(&self.patterns.ifsc),
+            branch: capture(&self.patterns.branch),
+            holder_name: capture(&self.patterns.holder_name),
+            currency: self.template.currency.clone(),
+            date: period_end
+                .or(transactions.last().map(|transaction| transaction.date))
+                .unwrap_or_else(utils::datetime::get_current_datetime),
+            timezone: self.timezone,
+            period_start,
+            period_end,
+            opening_balance: None,
+            closing_balance: None,
+            totals: None,
+            source_hash: None,
+            skipped_rows,
+            warnings,
+            transactions,
+        })
+    }
+
+    fn parse_date(&self, date: &str) -> Result<DateTimeUtc, Error> {
+        utils::datetime::parse_date(date, &self.date_formats, self.timezone)
+    }
+}
+
+impl StatementParser for TemplateParser {
+    fn name(&self) -> &str {
+        &self.template.name
+    }
+
+    fn institution(&self) -> Option<InstitutionName> {
+        self.template.institution.clone()
+    }
+
+    fn file_types(&self) -> &[FileType] {
+        &self.template.file_types
+    }
+
+    fn version(&self) -> u32 {
+        self.template.version
+    }
+
+    fn identify(&self, file: &File) -> Result<Confidence, Error> {
+        Ok(self.recognise(file).unwrap_or(Confidence::NONE))
+    }
+
+    fn explain_mismatch(&self, file: &File) -> Option<String> {
+        self.recognise(file).err()
+    }
+
+    fn parse(&self, file: &File) -> Result<Statement, Error> {
+        match &file.data {
+            FileData::Table(table) => self.parse_table(table),
+            FileData::Text(_) | FileData::Tree(_) | FileData::Layout(_) => Err(
+                Error::parser_mismatch("error.parser.template.unsupported_file_data"),
+            ),
+        }
+    }
+}
+
+/// Reads an amount written with thousands separators, empty cells being zero
+fn parse_amount(amount: &str) -> Result<Decimal, Error> {
+    let amount = amount.replace([',', ' '], "");
+    if amount.is_empty() {
+        return Ok(Decimal::ZERO);
+    }
+    amount
+        .parse::<Decimal>()
+        .map_err(|_| Error::parse("error.parser.template.invalid_amount").with_reason(amount))
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,314 @@
+#[cfg(test)]
+use crate::{
+    error::{Error, Location},
+    models::entities::sea_orm_active_enums::{AccountType, InstitutionName},
+    readers::{
+        diagnose_file_content, get_statement_from_file_content_with_parsers,
+        parsers::{
+            templates::TemplateParser,
+            types::{Confidence, StatementParser},
+            ParserRegistry,
+        },
+        types::{File, FileData, FileType},
+    },
+    utils::datetime::{parse_date, DateFormat, IST},
+};
+#[cfg(test)]
+use rust_decimal_macros::dec;
+#[cfg(test)]
+use sea_orm::{prelude::DateTimeUtc, sqlx::types::chrono::FixedOffset};
+
+#[cfg(test)]
+const FIXTURES: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/src/readers/parsers/templates/fixtures"
+);
+
+#[cfg(test)]
+fn _date(date: &str) -> DateTimeUtc {
+    parse_date(date, &[DateFormat::ISO], IST).unwrap()
+}
+
+#[cfg(test)]
+fn _table(rows: &[&[&str]]) -> File {
+    File {
+        file_type: FileType::Csv,
+        data: FileData::Table(
+            rows.iter()
+                .map(|row| row.iter().map(|cell| cell.to_string()).collect())
+                .collect(),
+        ),
+    }
+}
+
+#[test]
+fn test_parse_statement_with_toml_template() {
+    let mut parsers = ParserRegistry::default();
+    parsers.register(TemplateParser::from_file(format!("{FIXTURES}/axisind.toml")).unwrap());
+    let content = include_bytes!("fixtures/statement.csv").to_vec();
+
+    let (statement, _) =
+        get_statement_from_file_content_with_parsers(content, "", &parsers, None).unwrap();
+
+    assert_eq!(statement.institution, Some(InstitutionName::Axis));
+    assert_eq!(statement.account_type, AccountType::SavingsAccount);
+    assert_eq!(statement.account_number.as_deref(), Some("912010012345678"));
+    assert_eq!(statement.holder_name.as_deref(), Some("MR TESTER KUMAR"));
+    assert_eq!(statement.currency.as_deref(), Some("INR"));
+    assert_eq!(statement.period_start, Some(_date("2024-01-01")));
+    assert_eq!(statement.period_end, Some(_date("2024-01-31")));
+    assert_eq!(statement.date, _date("2024-01-31"));
+
+    // The empty row is skipped, and the totals row ends the table
+    assert_eq!(statement.transactions.len(), 3);
+    let transaction = &statement.transactions[1];
+    assert_eq!(transaction.date, _date("2024-01-05"));
+    assert_eq!(transaction.description, "ATM-WDL/MUMBAI");
+    assert_eq!(transaction.ref_no, "000123");
+    assert_eq!(transaction.withdrawal, dec!(2500.00));
+    assert_eq!(transaction.deposit, dec!(0));
+    assert_eq!(transaction.balance, dec!(47500.00));
+    assert_eq!(statement.transactions[2].withdrawal, dec!(349.50));
+}
+
+#[test]
+fn test_unreadable_period_is_left_open() {
+    let mut parsers = ParserRegistry::default();
+    parsers.register(TemplateParser::from_file(format!("{FIXTURES}/axisind.toml")).unwrap());
+    let content = String::from_utf8(include_bytes!("fixtures/statement.csv").to_vec())
+        .unwrap()
+        .replace("to 31-01-2024", "to 31-13-2024");
+
+    let (statement, _) =
+        get_statement_from_file_content_with_parsers(content.into_bytes(), "", &parsers, None)
+            .unwrap();
+
+    assert_eq!(statement.period_start, Some(_date("2024-01-01")));
+    assert_eq!(statement.period_end, None);
+    assert_eq!(statement.transactions.len(), 3);
+    assert_eq!(statement.warnings.len(), 1);
+    assert_eq!(statement.warnings[0].key, "error.utils.datetime.parse_date");
+    assert_eq!(statement.warnings[0].reason.as_deref(), Some("31-13-2024"));
+}
+
+#[test]
+fn test_parse_signed_amounts_with_yaml_template() {
+    let parser = TemplateParser::from_file(format!("{FIXTURES}/signed.yaml")).unwrap();
+    let file = _table(&[
+        &["Date", "Details", "Amount", "Balance"],
+        &["2024-03-01", "Salary", "1,000.00", "1,000.00"],
+        &["2024-03-02", "Rent", "-400.00", "600.00"],
+        &[],
+        &["Generated on 2024-03-03"],
+    ]);
+
+    let statement = parser.parse(&file).unwrap();
+    assert_eq!(statement.institution, None);
+    assert_eq!(statement.account_type, AccountType::Unknown);
+    assert_eq!(statement.timezone, FixedOffset::east_opt(0).unwrap());
+    assert_eq!(statement.transactions.len(), 2);
+    assert_eq!(statement.transactions[0].deposit, dec!(1000.00));
+    assert_eq!(statement.transactions[1].withdrawal, dec!(400.00));
+    assert_eq!(statement.transactions[1].deposit, dec!(0));
+    assert_eq!(statement.date, statement.transactions[1].date);
+}
+
+#[test]
+fn test_parse_indicator_amounts() {
+    let parser = TemplateParser::from_toml(
+        r#"
+        name = "indicator"
+        file_types = ["xlsx"]
+        header = ["Date", "Narration", "Amount", "Dr/Cr", "Balance"]
+        skip_rows = 1
+        date_formats = ["%d/%m/%Y"]
+        columns = { date = "Date", description = "Narration", balance = "Balance" }
+        amounts = { style = "indicator", amount = "Amount", indicator = "Dr/Cr", debit = ["DR", "D"] }
+        "#,
+    )
+    .unwrap();
+    let file = _table(&[
+        &["Balance", "Dr/Cr", "Amount", "Narration", "Date"],
+        &["-", "-", "-", "-", "-"],
+        &["900.00", "dr", "100.00", "Card", "02/03/2024"],
+        &["1,400.00", "CR", "500.00", "Refund", "03/03/2024"],
+    ]);
+
+    let statement = parser.parse(&file).unwrap();
+    assert_eq!(statement.transactions.len(), 2);
+    assert_eq!(statement.transactions[0].withdrawal, dec!(100.00));
+    assert_eq!(statement.transactions[0].description, "Card");
+    assert_eq!(statement.transactions[1].deposit, dec!(500.00));
+    assert_eq!(statement.transactions[1].balance, dec!(1400.00));
+}
+
+#[test]
+fn test_masked_account_number() {
+    let parser = TemplateParser::from_toml(
+        r#"
+        name = "masked"
+        file_types = ["csv"]
+        header = ["Date", "Details", "Amount", "Balance"]
+        date_formats = ["%Y-%m-%d"]
+        columns = { date = "Date", description = "Details", balance = "Balance" }
+        amounts = { style = "signed", amount = "Amount" }
+        details = { account_number = 'Account No\s*:\s*([\dX]+)' }
+        "#,
+    )
+    .unwrap();
+    let file = _table(&[
+        &["Account No : XXXXXX1234"],
+        &["Date", "Details", "Amount", "Balance"],
+        &["2024-03-01", "Salary", "1,000.00", "1,000.00"],
+    ]);
+
+    let statement = parser.parse(&file).unwrap();
+    assert_eq!(statement.account_number, None);
+    assert_eq!(
+        statement.masked_account_number.as_deref(),
+        Some("XXXXXX1234")
+    );
+}
+
+#[test]
+fn test_identify_by_markers_and_header() {
+    let parser = TemplateParser::from_file(format!("{FIXTURES}/axisind.toml")).unwrap();
+    let header: &[&str] = &["Tran Date", "CHQNO", "PARTICULARS", "DR", "CR", "BAL"];
+
+    let file = _table(&[&["AXIS BANK"], header]);
+    assert_eq!(parser.identify(&file).unwrap(), Confidence::CERTAIN);
+
+    let file = _table(&[&["SOME OTHER BANK"], header]);
+    assert_eq!(parser.identify(&file).unwrap(), Confidence::NONE);
+
+    let file = _table(&[&["AXIS BANK"], &["Date", "Narration"]]);
+    assert_eq!(parser.identify(&file).unwrap(), Confidence::NONE);
+
+    // Without markers, the headings alone are not enough to be certain
+    let parser = TemplateParser::from_file(format!("{FIXTURES}/signed.yaml")).unwrap();
+    let file = _table(&[&["Date", "Details", "Amount", "Balance"]]);
+    assert_eq!(parser.identify(&file).unwrap(), Confidence::FORMAT);
+}
+
+#[test]
+fn test_invalid_amount_is_reported_at_its_row() {
+    let parser = TemplateParser::from_file(format!("{FIXTURES}/signed.yaml")).unwrap();
+    let file = _table(&[
+        &["Date", "Details", "Amount", "Balance"],
+        &["2024-03-01", "Salary", "one thousand", "1,000.00"],
+    ]);
+
+    let statement = parser.parse(&file).unwrap();
+    assert!(statement.transactions.is_empty());
+    let skipped = &statement.skipped_rows[0];
+    assert_eq!(skipped.key, "error.parser.template.invalid_amount");
+    assert_eq!(skipped.location, Some(Location::Row(2)));
+    assert_eq!(skipped.reason.as_deref(), Some("onethousand"));
+}
+
+#[test]
+fn test_unreadable_balance_is_worked_out() {
+    let parser = TemplateParser::from_file(format!("{FIXTURES}/signed.yaml")).unwrap();
+    let file = _table(&[
+        &["Date", "Details", "Amount", "Balance"],
+        &["2024-03-01", "Salary", "1,000.00", "1,000.00"],
+        &["2024-03-02", "Rent", "-400.00", "6OO.00"],
+    ]);
+
+    let statement = parser.parse(&file).unwrap();
+    let transaction = &statement.transactions[1];
+    assert_eq!(transaction.balance, dec!(600.00));
+    assert_eq!(transaction.warnings.len(), 1);
+    assert_eq!(
+        transaction.warnings[0].key,
+        "error.parser.template.invalid_balance"
+    );
+    assert_eq!(transaction.warnings[0].reason.as_deref(), Some("6OO.00"));
+    assert!(statement.transactions[0].warnings.is_empty());
+
+    // The first balance has none before it to be worked out from
+    let file = _table(&[
+        &["Date", "Details", "Amount", "Balance"],
+        &["2024-03-01", "Salary", "1,000.00", ""],
+    ]);
+    let statement = parser.parse(&file).unwrap();
+    assert!(statement.transactions.is_empty());
+    assert_eq!(
+        statement.skipped_rows[0].key,
+        "error.parser.template.balance_not_found"
+    );
+}
+
+#[test]
+fn test_diagnose_reports_skipped_rows() {
+    let mut parsers = ParserRegistry::default();
+    parsers.register(TemplateParser::from_file(format!("{FIXTURES}/axisind.toml")).unwrap());
+    let content = String::from_utf8(include_bytes!("fixtures/statement.csv").to_vec())
+        .unwrap()
+        .replace("349.50", "3A9.50");
+
+    let diagnosis = diagnose_file_content(content.into_bytes(), "", &parsers, None).unwrap();
+    assert_eq!(diagnosis.file_type, FileType::Csv);
+    assert_eq!(diagnosis.parser.as_deref(), Some("axisind"));
+    assert!(diagnosis.error.is_none());
+    assert_eq!(diagnosis.statement.unwrap().transactions.len(), 2);
+
+    let row = &diagnosis.skipped_rows[0];
+    assert_eq!(row.key, "error.parser.template.invalid_amount");
+    assert_eq!(row.location, Some(Location::Row(8)));
+    assert_eq!(
+        row.content,
+        "28-01-2024 |  | UPI/GROCERY | 3A9.50 |  | 47,150.50"
+    );
+    assert_eq!(row.reason.as_deref(), Some("3A9.50"));
+
+    let axisind = diagnosis.parsers.iter().find(|d| d.parser == "axisind");
+    assert_eq!(axisind.unwrap().confidence, Confidence::CERTAIN);
+}
+
+#[test]
+fn test_invalid_templates_are_rejected() {
+    let template = |amounts: &str, file_types: &str| {
+        TemplateParser::from_toml(&format!(
+            r#"
+            name = "broken"
+            file_types = {file_types}
+            header = ["Date", "Details", "Balance"]
+            date_formats = ["%Y-%m-%d"]
+            columns = {{ date = "Date", description = "Details", balance = "Balance" }}
+            amounts = {amounts}
+            "#
+        ))
+    };
+
+    let error = template(r#"{ style = "signed", amount = "Amount" }"#, r#"["csv"]"#).unwrap_err();
+    assert_eq!(error.key(), "error.parser.template.column_not_in_header");
+    assert_eq!(error.reason().as_deref(), Some("broken: Amount"));
+
+    let error = template(r#"{ style = "signed", amount = "Balance" }"#, r#"["pdf"]"#).unwrap_err();
+    assert_eq!(error.key(), "error.parser.template.unsupported_file_type");
+
+    let error = template(r#"{ style = "sideways" }"#, r#"["csv"]"#).unwrap_err();
+    assert_eq!(error.key(), "error.parser.template.invalid_toml");
+
+    assert!(matches!(
+        TemplateParser::from_file(format!("{FIXTURES}/statement.csv")),
+        Err(Error::UnsupportedFormat {
+            key: "error.parser.template.unsupported_extension",
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_register_templates_of_directory() {
+    let mut parsers = ParserRegistry::new();
+    parsers.register_templates(FIXTURES).unwrap();
+
+    let names: Vec<&str> = parsers.parsers().map(|parser| parser.name()).collect();
+    assert_eq!(names, ["axisind", "signedcsv"]);
+    assert_eq!(
+        parsers.get("axisind").unwrap().file_types(),
+        [FileType::Xls, FileType::Xlsx, FileType::Csv]
+    );
+}
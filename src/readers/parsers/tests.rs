@@ -0,0 +1,202 @@
+#[cfg(test)]
+use crate::{
+    error::Error,
+    models::entities::sea_orm_active_enums::{AccountType, InstitutionName},
+    readers::{
+        parsers::{
+            types::{Confidence, Statement, StatementParser},
+            ParserRegistry,
+        },
+        types::{File, FileData, FileType, Node},
+    },
+    utils::datetime::{get_current_datetime, IST},
+};
+
+/// Reads the OFX files of one bank, which put its name in the root element
+#[cfg(test)]
+struct _BankOfx;
+
+#[cfg(test)]
+impl StatementParser for _BankOfx {
+    fn name(&self) -> &str {
+        "bankofx"
+    }
+
+    fn institution(&self) -> Option<InstitutionName> {
+        Some(InstitutionName::Axis)
+    }
+
+    fn file_types(&self) -> &[FileType] {
+        &[FileType::Ofx]
+    }
+
+    fn version(&self) -> u32 {
+        2
+    }
+
+    fn identify(&self, file: &File) -> Result<Confidence, Error> {
+        match &file.data {
+            FileData::Tree(node) if node.attribute("bank") == Some("axis") => {
+                Ok(Confidence::new(0.9))
+            }
+            FileData::Tree(node) if node.attribute("bank") == Some("") => {
+                Err(Error::parse("error.parser.bankofx.bank_not_named").with_reason("bank=\"\""))
+            }
+            _ => Ok(Confidence::NONE),
+        }
+    }
+
+    fn parse(&self, _file: &File) -> Result<Statement, Error> {
+        Ok(Statement {
+            institution: self.institution(),
+            account_type: AccountType::SavingsAccount,
+            account_number: None,
+            masked_account_number: None,
+            ifsc: None,
+            branch: None,
+            holder_name: None,
+            currency: None,
+            date: get_current_datetime(),
+            timezone: IST,
+            period_start: None,
+            period_end: None,
+            opening_balance: None,
+            closing_balance: None,
+            totals: None,
+            source_hash: None,
+            skipped_rows: Vec::new(),
+            warnings: Vec::new(),
+            transactions: Vec::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+fn _ofx(attributes: Vec<(String, String)>) -> File {
+    File {
+        file_type: FileType::Ofx,
+        data: FileData::Tree(Node {
+            name: "OFX".to_string(),
+            attributes,
+            ..Default::default()
+        }),
+    }
+}
+
+#[test]
+fn test_identify_picks_the_most_confident_parser() {
+    let mut parsers = ParserRegistry::default();
+    parsers.register(_BankOfx);
+
+    let file = _ofx(vec![("bank".to_string(), "axis".to_string())]);
+    assert_eq!(parsers.identify(&file).unwrap().name(), "bankofx");
+
+    let file = _ofx(Vec::new());
+    assert_eq!(parsers.identify(&file).unwrap().name(), "ofx");
+}
+
+#[test]
+fn test_identify_skips_parsers_of_other_file_types() {
+    let parsers = ParserRegistry::default();
+    let file = File {
+        file_type: FileType::Csv,
+        data: FileData::Table(vec![vec!["HDFC BANK Ltd.".to_string()]]),
+    };
+    assert_eq!(parsers.identify(&file).unwrap().name(), "hdfcind");
+
+    let file = File {
+        file_type: FileType::Xml,
+        data: FileData::Table(vec![vec!["HDFC BANK Ltd.".to_string()]]),
+    };
+    assert!(matches!(
+        parsers.identify(&file),
+        Err(Error::ParserMismatch {
+            key: "error.reader.parsers.get_parser.no_matching_parser_found"
+        })
+    ));
+}
+
+#[test]
+fn test_identify_passes_over_failing_parsers() {
+    let mut parsers = ParserRegistry::default();
+    parsers.register(_BankOfx);
+
+    // The parser that fails does not keep the others from reading the file
+    let file = _ofx(vec![("bank".to_string(), String::new())]);
+    assert_eq!(parsers.identify(&file).unwrap().name(), "ofx");
+
+    let diagnoses = parsers.diagnose(&file);
+    let bankofx = diagnoses.iter().find(|d| d.parser == "bankofx").unwrap();
+    assert_eq!(bankofx.confidence, Confidence::NONE);
+    assert_eq!(
+        bankofx.mismatch.as_deref(),
+        Some("cannot identify the file: error.parser.bankofx.bank_not_named: bank=\"\"")
+    );
+}
+
+#[test]
+fn test_select_parser_by_name() {
+    let mut parsers = ParserRegistry::new();
+    parsers.register(_BankOfx);
+
+    // Forcing a parser does not ask it to identify the file
+    let file = _ofx(Vec::new());
+    let parser = parsers.select(&file, Some("bankofx")).unwrap();
+    assert_eq!(parser.version(), 2);
+    assert_eq!(
+        parser.parse(&file).unwrap().institution,
+        Some(InstitutionName::Axis)
+    );
+
+    assert!(matches!(
+        parsers.select(&file, Some("hdfcind")),
+        Err(Error::ParserMismatch {
+            key: "error.reader.parsers.get_parser.unknown_parser"
+        })
+    ));
+}
+
+#[test]
+fn test_register_replaces_parser_of_same_name() {
+    let mut parsers = ParserRegistry::default();
+    let count = parsers.parsers().count();
+
+    parsers.register(_BankOfx).register(_BankOfx);
+    assert_eq!(parsers.parsers().count(), count + 1);
+    assert_eq!(
+        parsers.get("bankofx").unwrap().institution(),
+        Some(InstitutionName::Axis)
+    );
+}
+
+#[test]
+fn test_confidence_is_clamped() {
+    assert_eq!(Confidence::new(1.5), Confidence::CERTAIN);
+    assert_eq!(Confidence::new(-1.0), Confidence::NONE);
+    assert_eq!(Confidence::new(f32::NAN), Confidence::NONE);
+    assert!(!Confidence::NONE.is_match());
+    assert!(Confidence::FORMAT < Confidence::CERTAIN);
+}
+
+#[test]
+fn test_diagnose_tells_why_parsers_do_not_read_file() {
+    let parsers = ParserRegistry::default();
+    let file = File {
+        file_type: FileType::Csv,
+        data: FileData::Table(vec![vec!["AXIS BANK".to_string()]]),
+    };
+
+    let diagnoses = parsers.diagnose(&file);
+    assert_eq!(diagnoses.len(), parsers.parsers().count());
+    assert!(diagnoses
+        .iter()
+        .all(|diagnosis| diagnosis.confidence == Confidence::NONE));
+
+    let hdfcind = diagnoses.iter().find(|d| d.parser == "hdfcind").unwrap();
+    assert_eq!(
+        hdfcind.mismatch.as_deref(),
+        Some("the first cell does not contain `HDFC BANK Ltd.`")
+    );
+    let ofx = diagnoses.iter().find(|d| d.parser == "ofx").unwrap();
+    assert_eq!(ofx.mismatch.as_deref(), Some("does not read Csv files"));
+}
@@ -1,34 +1,229 @@
-use crate::{models::entities::sea_orm_active_enums::AccountType, readers::types::File};
-use sea_orm::prelude::DateTimeUtc;
+use crate::{
+    error::{Error, Location},
+    models::entities::sea_orm_active_enums::{AccountType, InstitutionName},
+    readers::types::{File, FileType},
+};
+use sea_orm::{
+    prelude::{DateTimeUtc, Decimal},
+    sqlx::types::chrono::FixedOffset,
+};
+use serde::Serialize;
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct Statement {
+    /// Institution that issued the statement, when the parser is specific to one
+    pub institution: Option<InstitutionName>,
     pub account_type: AccountType,
+    /// Account number in full, when the statement prints it
+    pub account_number: Option<String>,
+    /// Account number with all but the last four characters masked, either
+    /// as printed or derived from the full number
+    pub masked_account_number: Option<String>,
+    /// IFSC of the branch holding the account
+    pub ifsc: Option<String>,
+    pub branch: Option<String>,
+    pub holder_name: Option<String>,
+    /// ISO 4217 code of the currency the amounts are in
+    pub currency: Option<String>,
     pub date: DateTimeUtc,
+    /// Timezone the dates of the statement are written in
+    #[serde(serialize_with = "serialize_offset")]
+    pub timezone: FixedOffset,
+    /// First day covered by the statement
+    pub period_start: Option<DateTimeUtc>,
+    /// Last day covered by the statement
+    pub period_end: Option<DateTimeUtc>,
+    pub opening_balance: Option<Decimal>,
+    pub closing_balance: Option<Decimal>,
+    /// Totals printed in the statement's summary, to check the rows against
+    pub totals: Option<StatementTotals>,
+    /// SHA-256 of the file the statement was read from, set by the readers
+    pub source_hash: Option<String>,
     pub transactions: Vec<Transaction>,
+    /// Rows of the transaction table that were not read as transactions
+    pub skipped_rows: Vec<SkippedRow>,
+    /// Details outside the transaction table that could not be read, and
+    /// were left out
+    pub warnings: Vec<ParseWarning>,
 }
 
-#[derive(Debug)]
+// Written as the UTC offset, such as `+05:30`
+fn serialize_offset<S>(offset: &FixedOffset, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.collect_str(offset)
+}
+
+/// Totals a statement prints for its transactions
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StatementTotals {
+    pub withdrawals: Decimal,
+    pub deposits: Decimal,
+    pub withdrawal_count: Option<usize>,
+    pub deposit_count: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
 pub struct Transaction {
     pub date: DateTimeUtc,
     pub description: String,
     pub ref_no: String,
-    pub withdrawal: f32,
-    pub deposit: f32,
-    pub balance: f32,
+    pub withdrawal: Decimal,
+    pub deposit: Decimal,
+    pub balance: Decimal,
+    /// Cells of the row that could not be read as written
+    pub warnings: Vec<ParseWarning>,
+}
+
+/// A cell of a transaction that could not be read as written, and was
+/// worked out from the rest of the statement instead, or a detail of the
+/// statement that was left out
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ParseWarning {
+    /// Stable key of why the cell could not be read
+    pub key: &'static str,
+    pub reason: Option<String>,
+}
+
+impl ParseWarning {
+    pub(crate) fn from_error(error: &Error) -> Self {
+        ParseWarning {
+            key: error.key(),
+            reason: error.reason(),
+        }
+    }
+}
+
+/// Balance after a transaction, read from its cell or, when the cell cannot
+/// be read, worked out from the balance before it with a warning
+///
+/// # Errors
+/// Returns the error of the cell if there is no balance before it
+pub(crate) fn derive_balance(
+    balance: Result<Decimal, Error>,
+    previous: Option<Decimal>,
+    withdrawal: Decimal,
+    deposit: Decimal,
+    warnings: &mut Vec<ParseWarning>,
+) -> Result<Decimal, Error> {
+    match (balance, previous) {
+        (Ok(balance), _) => Ok(balance),
+        (Err(error), Some(previous)) => {
+            warnings.push(ParseWarning::from_error(&error));
+            Ok(previous + deposit - withdrawal)
+        }
+        (Err(error), None) => Err(error),
+    }
+}
+
+/// A row of the transaction table a parser passed over
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SkippedRow {
+    /// Where the row is in the file
+    pub location: Option<Location>,
+    /// Text of the row as found in the file, cells separated by ` | `
+    pub content: String,
+    /// Stable key of why the row was skipped
+    pub key: &'static str,
+    pub reason: Option<String>,
+}
+
+impl SkippedRow {
+    /// Records a row that could not be read as a transaction
+    pub(crate) fn from_error(error: &Error, content: String) -> Self {
+        SkippedRow {
+            location: error.location(),
+            content,
+            key: error.key(),
+            reason: error.reason(),
+        }
+    }
+}
+
+/// What a parser made of a file, when diagnosing why it could not be read
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ParserDiagnosis {
+    pub parser: String,
+    pub confidence: Confidence,
+    /// Why the parser does not read the file, when it does not
+    pub mismatch: Option<String>,
 }
 
-pub struct Parser {
-    pub identify: fn(&File) -> Result<bool, String>,
-    pub parse: fn(&File) -> Result<Statement, String>,
+/// Everything learnt while reading a file, to tell why it could not be read
+#[derive(Debug, Serialize)]
+pub struct Diagnosis {
+    pub file_type: FileType,
+    /// What each registered parser made of the file, in registration order
+    pub parsers: Vec<ParserDiagnosis>,
+    /// Name of the parser the file was read with, `None` if none was found
+    pub parser: Option<String>,
+    pub statement: Option<Statement>,
+    /// Why the file could not be read, if it could not
+    pub error: Option<Error>,
+    /// Rows the parser passed over, or the row it failed at
+    pub skipped_rows: Vec<SkippedRow>,
 }
 
-impl Parser {
-    pub fn identify(&self, file: &File) -> Result<bool, String> {
-        (self.identify)(file)
+/// How sure a parser is that a file holds a statement it reads, from 0 (not
+/// its format at all) to 1 (certainly its format)
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd, Serialize)]
+pub struct Confidence(f32);
+
+impl Confidence {
+    /// The file is not in the parser's format
+    pub const NONE: Confidence = Confidence(0.0);
+    /// The file is in a standard format the parser reads whoever issued it,
+    /// so a parser for the institution's own flavour of it wins over it
+    pub const FORMAT: Confidence = Confidence(0.5);
+    /// The file is certainly in the parser's format
+    pub const CERTAIN: Confidence = Confidence(1.0);
+
+    /// Makes a confidence from a score, clamped between 0 and 1
+    pub fn new(score: f32) -> Self {
+        if score.is_nan() {
+            Confidence::NONE
+        } else {
+            Confidence(score.clamp(0.0, 1.0))
+        }
+    }
+
+    pub fn score(&self) -> f32 {
+        self.0
+    }
+
+    /// Whether the parser can read the file at all
+    pub fn is_match(&self) -> bool {
+        self.0 > 0.0
     }
+}
+
+/// Reads the statements of one institution, or of one standard format
+///
+/// Parsers are picked for a file by their [`identify`](Self::identify)
+/// confidence, through a [`ParserRegistry`](super::ParserRegistry).
+pub trait StatementParser: Send + Sync {
+    /// Stable name of the parser, such as `hdfcind`, used to pick it by hand
+    fn name(&self) -> &str;
+
+    /// Institution whose statements the parser reads, `None` for the parsers
+    /// of standard formats
+    fn institution(&self) -> Option<InstitutionName>;
 
-    pub fn parse(&self, file: &File) -> Result<Statement, String> {
-        (self.parse)(file)
+    /// Kinds of files the parser reads, the others are never handed to it
+    fn file_types(&self) -> &[FileType];
+
+    /// Version of the statement layout the parser reads, raised when the
+    /// institution changes its statements
+    fn version(&self) -> u32;
+
+    /// Tells how sure the parser is that it reads the file
+    fn identify(&self, file: &File) -> Result<Confidence, Error>;
+
+    /// Tells why the parser does not identify the file, for diagnostics
+    fn explain_mismatch(&self, _file: &File) -> Option<String> {
+        None
     }
+
+    fn parse(&self, file: &File) -> Result<Statement, Error>;
 }
@@ -0,0 +1,240 @@
+//! Passwords to try when opening encrypted statements
+//!
+//! Banks rarely let customers choose the statement password. Most derive it
+//! from the customer's details instead, e.g. the first four letters of the
+//! name followed by the day and month of birth. A [`Keyring`] holds plain
+//! passwords and such templates, and renders them into the candidates a
+//! reader tries one after the other.
+use std::collections::BTreeMap;
+
+use sea_orm::sqlx::types::chrono::NaiveDate;
+
+use crate::models::entities::sea_orm_active_enums::InstitutionName;
+
+/// A password to try, along with a label that identifies it without
+/// revealing it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    pub institution: Option<InstitutionName>,
+    pub label: String,
+    pub password: String,
+}
+
+/// Anything that can list the passwords to try, in order
+pub trait SecretProvider {
+    fn candidates(&self) -> Vec<Candidate>;
+}
+
+/// A single password, as passed to `get_statement_from_file`
+impl SecretProvider for str {
+    fn candidates(&self) -> Vec<Candidate> {
+        vec![Candidate {
+            institution: None,
+            label: "file_secret".to_string(),
+            password: self.to_string(),
+        }]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Secret {
+    Password(String),
+    Template(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Entry {
+    institution: Option<InstitutionName>,
+    secret: Secret,
+}
+
+/// Passwords and password templates, each either generic or tied to an institution
+///
+/// Templates refer to the values set on the keyring between braces, with
+/// optional modifiers separated by underscores:
+///
+/// * `{pan}` - the value as is
+/// * `{pan_first4}`, `{mobile_last4}` - its first or last characters
+/// * `{name_lower}`, `{name_upper}` - its case changed
+/// * `{dob_ddmm}`, `{dob_ddmmyyyy}`, `{dob_yyyymmdd}`, `{dob_ddmmmyy}` - a
+///   date value (`dd/mm/yyyy` or `yyyy-mm-dd`) in another format, where
+///   `mmm` is the abbreviated month name
+///
+/// Modifiers apply in order, so `{name_first4_lower}` lowercases the first
+/// four characters of the name. Templates using a value that is not set, or
+/// a modifier that does not apply, are skipped.
+#[derive(Debug, Clone, Default)]
+pub struct Keyring {
+    institution: Option<InstitutionName>,
+    values: BTreeMap<String, String>,
+    entries: Vec<Entry>,
+}
+
+impl Keyring {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets a value templates can refer to, such as `pan`, `dob` or `customer_id`
+    pub fn set_value(&mut self, name: &str, value: &str) -> &mut Self {
+        self.values.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    pub fn add_password(
+        &mut self,
+        institution: Option<InstitutionName>,
+        password: &str,
+    ) -> &mut Self {
+        self.entries.push(Entry {
+            institution,
+            secret: Secret::Password(password.to_string()),
+        });
+        self
+    }
+
+    pub fn add_template(
+        &mut self,
+        institution: Option<InstitutionName>,
+        template: &str,
+    ) -> &mut Self {
+        self.entries.push(Entry {
+            institution,
+            secret: Secret::Template(template.to_string()),
+        });
+        self
+    }
+
+    /// Only tries the generic entries and those of the given institution
+    pub fn for_institution(&mut self, institution: InstitutionName) -> &mut Self {
+        self.institution = Some(institution);
+        self
+    }
+
+    /// Renders a template with the values of the keyring
+    fn render(&self, template: &str) -> Option<String> {
+        let mut rendered = String::new();
+        let mut rest = template;
+
+        while let Some(start) = rest.find('{') {
+            let end = start + rest[start..].find('}')?;
+            rendered.push_str(&rest[..start]);
+            rendered.push_str(&self.placeholder(&rest[start + 1..end])?);
+            rest = &rest[end + 1..];
+        }
+        rendered.push_str(rest);
+
+        Some(rendered)
+    }
+
+    fn placeholder(&self, placeholder: &str) -> Option<String> {
+        // Names can contain underscores themselves, so take the longest one
+        let (name, value) = self
+            .values
+            .iter()
+            .filter(|(name, _)| {
+                placeholder == name.as_str() || placeholder.starts_with(&format!("{}_", name))
+            })
+            .max_by_key(|(name, _)| name.len())?;
+
+        placeholder[name.len()..]
+            .split('_')
+            .filter(|modifier| !modifier.is_empty())
+            .try_fold(value.clone(), |value, modifier| {
+                apply_modifier(&value, modifier)
+            })
+    }
+}
+
+impl SecretProvider for Keyring {
+    fn candidates(&self) -> Vec<Candidate> {
+        let mut candidates: Vec<Candidate> = Vec::new();
+
+        for entry in &self.entries {
+            let applies = match (&self.institution, &entry.institution) {
+                (Some(institution), Some(entry_institution)) => institution == entry_institution,
+                _ => true,
+            };
+            if !applies {
+                continue;
+            }
+
+            let (label, password) = match &entry.secret {
+                Secret::Password(password) => (
+                    format!("password {}", candidates.len() + 1),
+                    password.clone(),
+                ),
+                Secret::Template(template) => match self.render(template) {
+                    Some(password) => (template.clone(), password),
+                    None => continue,
+                },
+            };
+
+            if candidates
+                .iter()
+                .any(|candidate| candidate.password == password)
+            {
+                continue;
+            }
+
+            candidates.push(Candidate {
+                institution: entry.institution.clone(),
+                label,
+                password,
+            });
+        }
+
+        candidates
+    }
+}
+
+fn apply_modifier(value: &str, modifier: &str) -> Option<String> {
+    if let Some(count) = modifier.strip_prefix("first") {
+        let count: usize = count.parse().ok()?;
+        return Some(value.chars().take(count).collect());
+    }
+
+    if let Some(count) = modifier.strip_prefix("last") {
+        let count: usize = count.parse().ok()?;
+        let length = value.chars().count();
+        return Some(value.chars().skip(length.saturating_sub(count)).collect());
+    }
+
+    match modifier {
+        "lower" => Some(value.to_lowercase()),
+        "upper" => Some(value.to_uppercase()),
+        _ => format_date(value, modifier),
+    }
+}
+
+/// Formats a date value with a pattern made of `dd`, `mm`, `mmm`, `yy` and `yyyy`
+fn format_date(value: &str, pattern: &str) -> Option<String> {
+    let date = NaiveDate::parse_from_str(value.trim(), "%d/%m/%Y")
+        .or_else(|_| NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d"))
+        .ok()?;
+
+    let mut format = String::new();
+    let mut rest = pattern;
+    while !rest.is_empty() {
+        let (specifier, length) = if rest.starts_with("yyyy") {
+            ("%Y", 4)
+        } else if rest.starts_with("yy") {
+            ("%y", 2)
+        } else if rest.starts_with("mmm") {
+            ("%b", 3)
+        } else if rest.starts_with("mm") {
+            ("%m", 2)
+        } else if rest.starts_with("dd") {
+            ("%d", 2)
+        } else {
+            return None;
+        };
+        format.push_str(specifier);
+        rest = &rest[length..];
+    }
+
+    Some(date.format(&format).to_string())
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,88 @@
+#[cfg(test)]
+use crate::{
+    models::entities::sea_orm_active_enums::InstitutionName,
+    readers::secrets::{Keyring, SecretProvider},
+};
+
+#[cfg(test)]
+fn _keyring() -> Keyring {
+    let mut keyring = Keyring::new();
+    keyring
+        .set_value("name", "Tester Kumar")
+        .set_value("pan", "ABCDE1234F")
+        .set_value("dob", "07/03/1990")
+        .set_value("customer_id", "12345678");
+    keyring
+}
+
+#[cfg(test)]
+fn _passwords(keyring: &Keyring) -> Vec<String> {
+    keyring
+        .candidates()
+        .into_iter()
+        .map(|candidate| candidate.password)
+        .collect()
+}
+
+#[test]
+fn test_render_templates() {
+    let mut keyring = _keyring();
+    keyring
+        .add_template(None, "{pan_first4}{dob_ddmm}")
+        .add_template(None, "{name_first4_lower}{dob_ddmm}")
+        .add_template(None, "{customer_id}")
+        .add_template(None, "{dob_ddmmmyyyy}")
+        .add_template(None, "{dob_yyyymmdd}");
+
+    assert_eq!(
+        _passwords(&keyring),
+        vec!["ABCD0703", "test0703", "12345678", "07Mar1990", "19900307"]
+    );
+}
+
+#[test]
+fn test_skip_unrenderable_templates() {
+    let mut keyring = _keyring();
+    keyring
+        .add_template(None, "{mobile_last4}")
+        .add_template(None, "{pan_reverse}")
+        .add_template(None, "{name_dd}")
+        .add_template(None, "{pan");
+
+    assert!(keyring.candidates().is_empty());
+}
+
+#[test]
+fn test_candidates_for_institution() {
+    let mut keyring = _keyring();
+    keyring
+        .add_password(None, "generic")
+        .add_template(Some(InstitutionName::Hdfc), "{customer_id}")
+        .add_template(
+            Some(InstitutionName::Icici),
+            "{name_first4_lower}{dob_ddmm}",
+        )
+        .add_password(Some(InstitutionName::Hdfc), "generic");
+
+    assert_eq!(
+        _passwords(&keyring),
+        vec!["generic", "12345678", "test0703"]
+    );
+
+    keyring.for_institution(InstitutionName::Icici);
+    let candidates = keyring.candidates();
+    assert_eq!(candidates.len(), 2);
+    assert_eq!(candidates[0].label, "password 1");
+    assert_eq!(candidates[0].institution, None);
+    assert_eq!(candidates[1].label, "{name_first4_lower}{dob_ddmm}");
+    assert_eq!(candidates[1].institution, Some(InstitutionName::Icici));
+}
+
+#[test]
+fn test_single_secret() {
+    let candidates = "secret".candidates();
+
+    assert_eq!(candidates.len(), 1);
+    assert_eq!(candidates[0].password, "secret");
+    assert_eq!(candidates[0].label, "file_secret");
+}
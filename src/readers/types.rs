@@ -1,15 +1,272 @@
+use crate::error::Location;
+use serde::{Deserialize, Serialize};
+
+/// Kind of a file, as told by its content
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum FileType {
     Xls,
+    Xlsx,
+    Xlsb,
+    Ods,
+    Csv,
+    Ofx,
+    Xml,
+    Mt940,
     Pdf,
 }
 
+impl FileType {
+    /// Whether the file is a spreadsheet workbook, regardless of its container format
+    pub fn is_workbook(&self) -> bool {
+        matches!(
+            self,
+            FileType::Xls | FileType::Xlsx | FileType::Xlsb | FileType::Ods
+        )
+    }
+
+    /// Whether the file holds a table, either as a workbook or as delimited text
+    pub fn is_table(&self) -> bool {
+        self.is_workbook() || matches!(self, FileType::Csv)
+    }
+}
+
 pub struct File {
     pub file_type: FileType,
     pub data: FileData,
 }
 
+impl File {
+    /// Returns the text found at a location of the file, the cells of a
+    /// table row separated by ` | `
+    pub fn content_at(&self, location: Location) -> Option<String> {
+        match (&self.data, location) {
+            (FileData::Table(table), Location::Row(row)) => table
+                .get(row.checked_sub(1)?)
+                .map(|cells| cells.join(" | ")),
+            (FileData::Text(text), Location::Line(line))
+            | (FileData::Layout(Layout { text, .. }), Location::Line(line)) => text
+                .lines()
+                .nth(line.checked_sub(1)?)
+                .map(|line| line.trim().to_string()),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum FileData {
     Text(String),
     Table(Vec<Vec<String>>),
+    Tree(Node),
+    Layout(Layout),
+}
+
+/// An element of a markup document (OFX, XML), with its text and child elements
+///
+/// Names are kept without their namespace prefix.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Node {
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub text: String,
+    pub children: Vec<Node>,
+}
+
+impl Node {
+    /// Returns the first child element with the given name
+    pub fn child(&self, name: &str) -> Option<&Node> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    /// Returns all the child elements with the given name
+    pub fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Node> {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+
+    /// Follows a path of child element names, starting from this element
+    pub fn path(&self, path: &[&str]) -> Option<&Node> {
+        path.iter().try_fold(self, |node, name| node.child(name))
+    }
+
+    /// Returns the value of the attribute with the given name
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns the trimmed text of the element found at the given path
+    pub fn text_at(&self, path: &[&str]) -> Option<&str> {
+        self.path(path)
+            .map(|node| node.text.trim())
+            .filter(|text| !text.is_empty())
+    }
+
+    /// Returns the texts of the element and of all the elements within it,
+    /// in document order
+    pub fn texts(&self) -> Vec<&str> {
+        let text = Some(self.text.trim()).filter(|text| !text.is_empty());
+        text.into_iter()
+            .chain(self.children.iter().flat_map(|child| child.texts()))
+            .collect()
+    }
+}
+
+/// Text of a document along with where each piece of it was drawn
+///
+/// `text` is the plain text of all the pages, in the form the PDF parsers
+/// were first written against. `runs` keep the position of every text
+/// showing operation, so that lines and table columns can be rebuilt.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Layout {
+    pub text: String,
+    pub runs: Vec<TextRun>,
+}
+
+/// A piece of text drawn in one go, positioned in page space
+///
+/// Coordinates are in points from the bottom left corner of the page, so
+/// `y` grows upwards. `x` and `y` are the start of the run's baseline.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextRun {
+    pub page: u32,
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub font_size: f32,
+    pub text: String,
+}
+
+impl TextRun {
+    fn end(&self) -> f32 {
+        self.x + self.width
+    }
+
+    fn center(&self) -> f32 {
+        self.x + self.width / 2.0
+    }
+}
+
+/// The runs sharing a baseline on a page, ordered left to right
+#[derive(Debug, Clone, PartialEq)]
+pub struct Row {
+    pub page: u32,
+    pub y: f32,
+    pub runs: Vec<TextRun>,
+}
+
+impl Layout {
+    /// Groups the runs into rows, top to bottom and page by page
+    ///
+    /// Runs whose baselines are less than half their font size apart are
+    /// read as the same row.
+    pub fn rows(&self) -> Vec<Row> {
+        let mut runs: Vec<&TextRun> = self.runs.iter().collect();
+        runs.sort_by(|a, b| {
+            a.page
+                .cmp(&b.page)
+                .then(b.y.total_cmp(&a.y))
+                .then(a.x.total_cmp(&b.x))
+        });
+
+        let mut rows: Vec<Row> = Vec::new();
+        for run in runs {
+            match rows.last_mut() {
+                Some(row)
+                    if row.page == run.page
+                        && (row.y - run.y).abs() < run.font_size.max(1.0) / 2.0 =>
+                {
+                    row.runs.push(run.clone())
+                }
+                _ => rows.push(Row {
+                    page: run.page,
+                    y: run.y,
+                    runs: vec![run.clone()],
+                }),
+            }
+        }
+
+        for row in &mut rows {
+            row.runs.sort_by(|a, b| a.x.total_cmp(&b.x));
+        }
+
+        rows
+    }
+}
+
+impl Row {
+    /// Finds the column boundaries of a table from its header row
+    ///
+    /// The headings must appear in order, each made of one or more
+    /// consecutive runs (whitespace is ignored when comparing). Each
+    /// boundary lies halfway between two neighbouring headings.
+    pub fn boundaries(&self, headings: &[&str]) -> Option<Vec<f32>> {
+        let squash = |text: &str| text.split_whitespace().collect::<String>();
+        let headings: Vec<String> = headings.iter().map(|heading| squash(heading)).collect();
+
+        (0..self.runs.len()).find_map(|start| {
+            let mut spans: Vec<(f32, f32)> = Vec::new();
+            let mut index = start;
+            for heading in &headings {
+                let first = self.runs.get(index)?;
+                let mut text = String::new();
+                loop {
+                    let run = self.runs.get(index)?;
+                    text.push_str(&squash(&run.text));
+                    index += 1;
+                    if text == *heading {
+                        spans.push((first.x, run.end()));
+                        break;
+                    }
+                    if !heading.starts_with(&text) {
+                        return None;
+                    }
+                }
+            }
+
+            Some(
+                spans
+                    .windows(2)
+                    .map(|pair| (pair[0].1 + pair[1].0) / 2.0)
+                    .collect(),
+            )
+        })
+    }
+
+    /// Splits the row into cells at the given column boundaries
+    ///
+    /// A run belongs to the column its center falls in, so both left and
+    /// right aligned columns are read correctly. Runs that are visibly
+    /// apart are joined with a space; without boundaries, the whole row
+    /// is returned as a single cell.
+    pub fn cells(&self, boundaries: &[f32]) -> Vec<String> {
+        (0..=boundaries.len())
+            .map(|column| {
+                join_runs(self.runs.iter().filter(|run| {
+                    boundaries
+                        .iter()
+                        .filter(|boundary| run.center() >= **boundary)
+                        .count()
+                        == column
+                }))
+            })
+            .collect()
+    }
+}
+
+fn join_runs<'a>(runs: impl Iterator<Item = &'a TextRun>) -> String {
+    let mut text = String::new();
+    let mut previous: Option<&TextRun> = None;
+    for run in runs {
+        if let Some(previous) = previous {
+            if run.x - previous.end() > previous.font_size * 0.15 {
+                text.push(' ');
+            }
+        }
+        text.push_str(&run.text);
+        previous = Some(run);
+    }
+    text
 }
@@ -0,0 +1,261 @@
+//! Checks that the rows of a parsed statement agree with each other and with
+//! the balances and totals the statement prints
+//!
+//! A misread row, such as a PDF line split in the wrong place, still parses
+//! into a transaction. Its amounts then no longer add up to the balances
+//! around it, which is what these checks look for.
+use sea_orm::prelude::Decimal;
+use serde::Serialize;
+
+use crate::readers::parsers::types::{SkippedRow, Statement};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// Unusual but possibly correct, such as rows out of date order
+    Warning,
+    /// The rows cannot all have been read correctly
+    Error,
+}
+
+/// A single finding of the validation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Issue {
+    pub severity: Severity,
+    /// Stable key of the issue, for translations
+    pub key: &'static str,
+    /// Transaction the issue was found at, counted from 1
+    pub row: Option<usize>,
+    pub expected: Option<Decimal>,
+    pub found: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ValidationReport {
+    pub issues: Vec<Issue>,
+    /// Rows of the statement the parser could not read as transactions, so
+    /// that the review can show what is missing
+    pub skipped_rows: Vec<SkippedRow>,
+}
+
+impl ValidationReport {
+    /// Whether no check failed with an error; warnings are allowed
+    pub fn is_valid(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &Issue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == Severity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &Issue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == Severity::Warning)
+    }
+
+    fn push(
+        &mut self,
+        severity: Severity,
+        key: &'static str,
+        row: Option<usize>,
+        expected: Option<Decimal>,
+        found: Option<Decimal>,
+    ) {
+        self.issues.push(Issue {
+            severity,
+            key,
+            row,
+            expected,
+            found,
+        });
+    }
+}
+
+/// Checks a statement for rows that do not add up
+///
+/// # Arguments
+/// * `statement` - Parsed statement
+///
+/// # Returns
+/// * `ValidationReport` - Every issue found, empty when the statement is consistent
+pub fn validate_statement(statement: &Statement) -> ValidationReport {
+    let mut report = ValidationReport::default();
+
+    check_running_balance(statement, &mut report);
+    check_balances(statement, &mut report);
+    check_totals(statement, &mut report);
+    check_dates(statement, &mut report);
+    check_parse_warnings(statement, &mut report);
+    report.skipped_rows = statement.skipped_rows.clone();
+
+    report
+}
+
+/// Every balance has to follow from the one before it, starting from the
+/// opening balance when the statement has one
+fn check_running_balance(statement: &Statement, report: &mut ValidationReport) {
+    let mut previous = statement.opening_balance;
+
+    for (index, transaction) in statement.transactions.iter().enumerate() {
+        if let Some(previous) = previous {
+            let expected = previous + transaction.deposit - transaction.withdrawal;
+            if expected != transaction.balance {
+                report.push(
+                    Severity::Error,
+                    "error.validation.balance_discontinuity",
+                    Some(index + 1),
+                    Some(expected),
+                    Some(transaction.balance),
+                );
+            }
+        }
+        previous = Some(transaction.balance);
+    }
+}
+
+/// The closing balance has to be the last balance, and the opening balance
+/// changed by every row
+fn check_balances(statement: &Statement, report: &mut ValidationReport) {
+    let Some(closing_balance) = statement.closing_balance else {
+        return;
+    };
+
+    let last_balance = statement
+        .transactions
+        .last()
+        .map(|transaction| transaction.balance)
+        .or(statement.opening_balance);
+    if let Some(last_balance) = last_balance {
+        if last_balance != closing_balance {
+            report.push(
+                Severity::Error,
+                "error.validation.closing_balance_mismatch",
+                None,
+                Some(closing_balance),
+                Some(last_balance),
+            );
+        }
+    }
+
+    if let Some(opening_balance) = statement.opening_balance {
+        let net_change: Decimal = statement
+            .transactions
+            .iter()
+            .map(|transaction| transaction.deposit - transaction.withdrawal)
+            .sum();
+        if opening_balance + net_change != closing_balance {
+            report.push(
+                Severity::Error,
+                "error.validation.net_change_mismatch",
+                None,
+                Some(closing_balance - opening_balance),
+                Some(net_change),
+            );
+        }
+    }
+}
+
+/// The rows have to add up to the totals of the statement's summary
+fn check_totals(statement: &Statement, report: &mut ValidationReport) {
+    let Some(totals) = &statement.totals else {
+        return;
+    };
+
+    let withdrawals = statement
+        .transactions
+        .iter()
+        .filter(|transaction| transaction.withdrawal != Decimal::ZERO);
+    let deposits = statement
+        .transactions
+        .iter()
+        .filter(|transaction| transaction.deposit != Decimal::ZERO);
+
+    let checks = [
+        (
+            "error.validation.withdrawal_total_mismatch",
+            Some(totals.withdrawals),
+            withdrawals
+                .clone()
+                .map(|transaction| transaction.withdrawal)
+                .sum(),
+        ),
+        (
+            "error.validation.deposit_total_mismatch",
+            Some(totals.deposits),
+            deposits
+                .clone()
+                .map(|transaction| transaction.deposit)
+                .sum(),
+        ),
+        (
+            "error.validation.withdrawal_count_mismatch",
+            totals.withdrawal_count.map(Decimal::from),
+            Decimal::from(withdrawals.count()),
+        ),
+        (
+            "error.validation.deposit_count_mismatch",
+            totals.deposit_count.map(Decimal::from),
+            Decimal::from(deposits.count()),
+        ),
+    ];
+
+    for (key, expected, found) in checks {
+        if let Some(expected) = expected.filter(|expected| *expected != found) {
+            report.push(Severity::Error, key, None, Some(expected), Some(found));
+        }
+    }
+}
+
+/// Rows should be in date order and within the period of the statement
+fn check_dates(statement: &Statement, report: &mut ValidationReport) {
+    let mut previous = None;
+
+    for (index, transaction) in statement.transactions.iter().enumerate() {
+        if previous.is_some_and(|previous| transaction.date < previous) {
+            report.push(
+                Severity::Warning,
+                "warning.validation.date_out_of_order",
+                Some(index + 1),
+                None,
+                None,
+            );
+        }
+        previous = Some(transaction.date);
+
+        let before_period = statement
+            .period_start
+            .is_some_and(|start| transaction.date < start);
+        let after_period = statement
+            .period_end
+            .is_some_and(|end| transaction.date > end);
+        if before_period || after_period {
+            report.push(
+                Severity::Warning,
+                "warning.validation.date_outside_period",
+                Some(index + 1),
+                None,
+                None,
+            );
+        }
+    }
+}
+
+/// Cells the parser could not read as written were worked out from the
+/// rest of the statement, and details it could not read were left out,
+/// which the review should double check
+fn check_parse_warnings(statement: &Statement, report: &mut ValidationReport) {
+    for warning in &statement.warnings {
+        report.push(Severity::Warning, warning.key, None, None, None);
+    }
+    for (index, transaction) in statement.transactions.iter().enumerate() {
+        for warning in &transaction.warnings {
+            report.push(Severity::Warning, warning.key, Some(index + 1), None, None);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
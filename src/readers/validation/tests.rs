@@ -0,0 +1,198 @@
+#[cfg(test)]
+use crate::{
+    error::Location,
+    models::entities::sea_orm_active_enums::AccountType,
+    readers::{
+        parsers::types::{ParseWarning, SkippedRow, Statement, StatementTotals, Transaction},
+        validation::{validate_statement, Severity},
+    },
+    utils::datetime::{parse_date, DateFormat, IST},
+};
+use rust_decimal_macros::dec;
+use sea_orm::prelude::Decimal;
+
+fn _transaction(
+    date: &str,
+    withdrawal: Decimal,
+    deposit: Decimal,
+    balance: Decimal,
+) -> Transaction {
+    Transaction {
+        date: parse_date(date, &[DateFormat::DD_MM_YYYY], IST).unwrap(),
+        description: String::new(),
+        ref_no: String::new(),
+        withdrawal,
+        deposit,
+        balance,
+        warnings: Vec::new(),
+    }
+}
+
+fn _statement() -> Statement {
+    Statement {
+        institution: None,
+        account_type: AccountType::SavingsAccount,
+        account_number: None,
+        masked_account_number: None,
+        ifsc: None,
+        branch: None,
+        holder_name: None,
+        currency: None,
+        date: parse_date("31/01/2023", &[DateFormat::DD_MM_YYYY], IST).unwrap(),
+        timezone: IST,
+        period_start: parse_date("01/01/2023", &[DateFormat::DD_MM_YYYY], IST).ok(),
+        period_end: parse_date("31/01/2023", &[DateFormat::DD_MM_YYYY], IST).ok(),
+        opening_balance: Some(dec!(1000.00)),
+        closing_balance: Some(dec!(1150.00)),
+        totals: Some(StatementTotals {
+            withdrawals: dec!(50.00),
+            deposits: dec!(200.00),
+            withdrawal_count: Some(1),
+            deposit_count: Some(1),
+        }),
+        source_hash: None,
+        skipped_rows: Vec::new(),
+        warnings: Vec::new(),
+        transactions: vec![
+            _transaction("05/01/2023", dec!(50.00), dec!(0), dec!(950.00)),
+            _transaction("10/01/2023", dec!(0), dec!(200.00), dec!(1150.00)),
+        ],
+    }
+}
+
+#[test]
+fn test_consistent_statement() {
+    let report = validate_statement(&_statement());
+
+    assert!(report.issues.is_empty());
+    assert!(report.is_valid());
+}
+
+#[test]
+fn test_balance_discontinuity() {
+    let mut statement = _statement();
+    // A deposit read as part of the balance
+    statement.transactions[1].deposit = dec!(20.00);
+
+    let report = validate_statement(&statement);
+    let keys: Vec<&str> = report.errors().map(|issue| issue.key).collect();
+
+    assert!(!report.is_valid());
+    assert_eq!(
+        keys,
+        [
+            "error.validation.balance_discontinuity",
+            "error.validation.net_change_mismatch",
+            "error.validation.deposit_total_mismatch",
+        ]
+    );
+
+    let issue = &report.issues[0];
+    assert_eq!(issue.row, Some(2));
+    assert_eq!(issue.expected, Some(dec!(970.00)));
+    assert_eq!(issue.found, Some(dec!(1150.00)));
+}
+
+#[test]
+fn test_opening_and_closing_balance() {
+    let mut statement = _statement();
+    statement.opening_balance = Some(dec!(900.00));
+    statement.closing_balance = Some(dec!(1050.00));
+
+    let report = validate_statement(&statement);
+    let keys: Vec<&str> = report.errors().map(|issue| issue.key).collect();
+
+    assert_eq!(
+        keys,
+        [
+            "error.validation.balance_discontinuity",
+            "error.validation.closing_balance_mismatch",
+        ]
+    );
+    assert_eq!(report.issues[0].row, Some(1));
+}
+
+#[test]
+fn test_summary_counts() {
+    let mut statement = _statement();
+    statement.totals = Some(StatementTotals {
+        withdrawals: dec!(50.00),
+        deposits: dec!(200.00),
+        withdrawal_count: Some(2),
+        deposit_count: None,
+    });
+
+    let report = validate_statement(&statement);
+
+    assert_eq!(report.issues.len(), 1);
+    assert_eq!(
+        report.issues[0].key,
+        "error.validation.withdrawal_count_mismatch"
+    );
+    assert_eq!(report.issues[0].expected, Some(dec!(2)));
+    assert_eq!(report.issues[0].found, Some(dec!(1)));
+}
+
+#[test]
+fn test_dates() {
+    let mut statement = _statement();
+    statement.opening_balance = None;
+    statement.closing_balance = None;
+    statement.totals = None;
+    statement.transactions = vec![
+        _transaction("10/01/2023", dec!(0), dec!(0), dec!(0)),
+        _transaction("05/01/2023", dec!(0), dec!(0), dec!(0)),
+        _transaction("01/02/2023", dec!(0), dec!(0), dec!(0)),
+    ];
+
+    let report = validate_statement(&statement);
+    let issues: Vec<(&str, Option<usize>)> = report
+        .issues
+        .iter()
+        .map(|issue| (issue.key, issue.row))
+        .collect();
+
+    assert!(report.is_valid());
+    assert!(report
+        .issues
+        .iter()
+        .all(|issue| issue.severity == Severity::Warning));
+    assert_eq!(
+        issues,
+        [
+            ("warning.validation.date_out_of_order", Some(2)),
+            ("warning.validation.date_outside_period", Some(3)),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_warnings_and_skipped_rows() {
+    let mut statement = _statement();
+    statement.transactions[1].warnings.push(ParseWarning {
+        key: "error.parser.hdfcind.invalid_balance",
+        reason: Some("1,15O.00".to_string()),
+    });
+    let skipped = SkippedRow {
+        location: Some(Location::Row(3)),
+        content: "12/01/2023 | Card | 1O.00".to_string(),
+        key: "error.parser.hdfcind.invalid_amount",
+        reason: Some("1O.00".to_string()),
+    };
+    statement.skipped_rows.push(skipped.clone());
+    statement.warnings.push(ParseWarning {
+        key: "error.utils.datetime.parse_date",
+        reason: Some("31-13-2023".to_string()),
+    });
+
+    let report = validate_statement(&statement);
+    let warnings: Vec<_> = report.warnings().collect();
+
+    assert!(report.is_valid());
+    assert_eq!(warnings.len(), 2);
+    assert_eq!(warnings[0].key, "error.utils.datetime.parse_date");
+    assert_eq!(warnings[0].row, None);
+    assert_eq!(warnings[1].key, "error.parser.hdfcind.invalid_balance");
+    assert_eq!(warnings[1].row, Some(2));
+    assert_eq!(report.skipped_rows, [skipped]);
+}
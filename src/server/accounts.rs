@@ -0,0 +1,107 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use sea_orm::DatabaseConnection;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use super::{
+    error::ApiError,
+    extract::{Body, Query},
+};
+use crate::{
+    error::{Entity, Error},
+    models::{
+        entities::{
+            accounts,
+            sea_orm_active_enums::{AccountType, InstitutionName},
+        },
+        helpers::accounts::{AccountFilter, AccountsQueryOptions},
+        manage::accounts::{
+            create_account, delete_account, get_account, get_accounts, update_account,
+        },
+    },
+};
+
+#[derive(Deserialize)]
+pub(super) struct NewAccount {
+    account_number: String,
+    r#type: AccountType,
+    institution_name: InstitutionName,
+}
+
+#[derive(Deserialize)]
+pub(super) struct AccountChanges {
+    account_number: Option<String>,
+    r#type: Option<AccountType>,
+    institution_name: Option<InstitutionName>,
+}
+
+pub(super) async fn list(
+    State(db): State<DatabaseConnection>,
+    Query(options): Query<AccountsQueryOptions>,
+) -> Result<Json<Vec<accounts::Model>>, ApiError> {
+    Ok(Json(get_accounts(&db, options).await?))
+}
+
+pub(super) async fn create(
+    State(db): State<DatabaseConnection>,
+    Body(account): Body<NewAccount>,
+) -> Result<(StatusCode, Json<accounts::Model>), ApiError> {
+    let account = create_account(
+        &db,
+        &account.account_number,
+        &account.r#type,
+        &account.institution_name,
+    )
+    .await?;
+    Ok((StatusCode::CREATED, Json(account)))
+}
+
+pub(super) async fn show(
+    State(db): State<DatabaseConnection>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<accounts::Model>, ApiError> {
+    let account = get_account(
+        &db,
+        AccountsQueryOptions {
+            filter: Some(AccountFilter {
+                id: Some(id),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+    )
+    .await?
+    .ok_or(Error::not_found(
+        "error.server.accounts.not_found",
+        Entity::Account,
+    ))?;
+    Ok(Json(account))
+}
+
+pub(super) async fn update(
+    State(db): State<DatabaseConnection>,
+    Path(id): Path<Uuid>,
+    Body(changes): Body<AccountChanges>,
+) -> Result<Json<accounts::Model>, ApiError> {
+    let account = update_account(
+        &db,
+        id,
+        changes.r#type,
+        changes.account_number,
+        changes.institution_name,
+    )
+    .await?;
+    Ok(Json(account))
+}
+
+pub(super) async fn delete(
+    State(db): State<DatabaseConnection>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    delete_account(&db, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,61 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::{json, Value};
+
+use crate::error::{Error, Location};
+
+/// An error answered with its status and a JSON body carrying the error key
+#[derive(Debug)]
+pub struct ApiError {
+    pub status: StatusCode,
+    pub body: Value,
+}
+
+impl ApiError {
+    pub(crate) fn bad_request(key: &'static str, reason: impl ToString) -> Self {
+        ApiError {
+            status: StatusCode::BAD_REQUEST,
+            body: json!({ "key": key, "reason": reason.to_string() }),
+        }
+    }
+}
+
+impl From<Error> for ApiError {
+    fn from(error: Error) -> Self {
+        let status = match &error {
+            Error::NotFound { .. } => StatusCode::NOT_FOUND,
+            Error::AmbiguousAccount { .. }
+            | Error::AlreadyImported { .. }
+            | Error::InvalidImportStatus { .. } => StatusCode::CONFLICT,
+            Error::UnsupportedFormat { .. }
+            | Error::Decryption { .. }
+            | Error::ParserMismatch { .. }
+            | Error::Parse { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+            Error::Io { .. } | Error::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+
+        let mut body = json!({ "key": error.key(), "reason": error.reason() });
+        match error.location() {
+            Some(Location::Row(row)) => body["row"] = json!(row),
+            Some(Location::Line(line)) => body["line"] = json!(line),
+            None => {}
+        }
+        match &error {
+            Error::AmbiguousAccount { candidates, .. } => body["candidates"] = json!(candidates),
+            Error::AlreadyImported { import_id, .. } => body["import_id"] = json!(import_id),
+            Error::InvalidImportStatus { status, .. } => body["status"] = json!(status),
+            _ => {}
+        }
+
+        ApiError { status, body }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(self.body)).into_response()
+    }
+}
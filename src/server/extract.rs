@@ -0,0 +1,48 @@
+use axum::{
+    extract::{FromRequest, FromRequestParts, Request},
+    http::request::Parts,
+};
+use serde::de::DeserializeOwned;
+
+use super::error::ApiError;
+
+/// Query string read into the models' query options
+///
+/// Nested fields and filter tuples are written with brackets, such as
+/// `filter[description][0]=Contains&filter[description][1]=SALARY&sort[column]=date`.
+pub struct Query<T>(pub T);
+
+impl<T, S> FromRequestParts<S> for Query<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        serde_qs::Config::new(5, false)
+            .deserialize_str(parts.uri.query().unwrap_or_default())
+            .map(Query)
+            .map_err(|error| ApiError::bad_request("error.server.invalid_query", error))
+    }
+}
+
+/// JSON request body, rejected with the same error body as everything else
+pub struct Body<T>(pub T);
+
+impl<T, S> FromRequest<S> for Body<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        axum::Json::<T>::from_request(request, state)
+            .await
+            .map(|axum::Json(value)| Body(value))
+            .map_err(|rejection| {
+                ApiError::bad_request("error.server.invalid_body", rejection.body_text())
+            })
+    }
+}
@@ -0,0 +1,163 @@
+use axum::{
+    extract::{Multipart, Path, State},
+    http::StatusCode,
+    Json,
+};
+use std::sync::Arc;
+
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{
+    error::ApiError,
+    extract::{Body, Query},
+};
+use crate::{
+    error::{Entity, Error},
+    models::{
+        entities::imports,
+        helpers::{
+            imports::{ImportFilter, ImportsQueryOptions},
+            staged_transactions::StagedTransactionFilter,
+        },
+        manage::imports::{
+            approve_staged_transactions, delete_import, get_import, get_imports, import_statement,
+            reject_import, rollback_import, update_import, ImportApproval, StatementImport,
+        },
+    },
+    readers::{get_statement_from_file_content_with_parsers, parsers::ParserRegistry},
+};
+
+#[derive(Deserialize)]
+pub(super) struct ImportChanges {
+    account_id: Option<Uuid>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct Approval {
+    /// Staged transactions to approve, all of them if not given
+    filter: Option<StagedTransactionFilter>,
+}
+
+#[derive(Serialize)]
+pub(super) struct Rollback {
+    deleted: u64,
+}
+
+pub(super) async fn list(
+    State(db): State<DatabaseConnection>,
+    Query(options): Query<ImportsQueryOptions>,
+) -> Result<Json<Vec<imports::Model>>, ApiError> {
+    Ok(Json(get_imports(&db, options).await?))
+}
+
+/// Reads a statement uploaded as the `file` field, decrypted with the `secret`
+/// field and read by the `parser` field's parser if given, and imports it into
+/// the `account_id` field's account or the one it belongs to
+pub(super) async fn upload(
+    State(db): State<DatabaseConnection>,
+    State(parsers): State<Arc<ParserRegistry>>,
+    mut multipart: Multipart,
+) -> Result<(StatusCode, Json<StatementImport>), ApiError> {
+    let invalid_upload = |error: axum::extract::multipart::MultipartError| {
+        ApiError::bad_request("error.server.imports.invalid_upload", error.body_text())
+    };
+    let (mut file, mut secret, mut parser, mut account_id) = (None, String::new(), None, None);
+
+    while let Some(field) = multipart.next_field().await.map_err(invalid_upload)? {
+        match field.name() {
+            Some("file") => file = Some(field.bytes().await.map_err(invalid_upload)?.to_vec()),
+            Some("secret") => secret = field.text().await.map_err(invalid_upload)?,
+            Some("parser") => parser = Some(field.text().await.map_err(invalid_upload)?),
+            Some("account_id") => {
+                let value = field.text().await.map_err(invalid_upload)?;
+                account_id = Some(value.parse::<Uuid>().map_err(|error| {
+                    ApiError::bad_request("error.server.imports.invalid_account_id", error)
+                })?);
+            }
+            _ => {}
+        }
+    }
+
+    let file = file.ok_or(ApiError::bad_request(
+        "error.server.imports.file_missing",
+        "The statement has to be sent as the `file` field",
+    ))?;
+    let (statement, _) = get_statement_from_file_content_with_parsers(
+        file,
+        secret.as_str(),
+        &parsers,
+        parser.as_deref(),
+    )?;
+    let import = import_statement(&db, &statement, account_id).await?;
+    Ok((StatusCode::CREATED, Json(import)))
+}
+
+pub(super) async fn show(
+    State(db): State<DatabaseConnection>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<imports::Model>, ApiError> {
+    Ok(Json(find_import(&db, id).await?))
+}
+
+pub(super) async fn update(
+    State(db): State<DatabaseConnection>,
+    Path(id): Path<Uuid>,
+    Body(changes): Body<ImportChanges>,
+) -> Result<Json<imports::Model>, ApiError> {
+    Ok(Json(update_import(&db, id, changes.account_id).await?))
+}
+
+pub(super) async fn delete(
+    State(db): State<DatabaseConnection>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    delete_import(&db, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub(super) async fn approve(
+    State(db): State<DatabaseConnection>,
+    Path(id): Path<Uuid>,
+    Body(approval): Body<Approval>,
+) -> Result<Json<ImportApproval>, ApiError> {
+    let account_id = find_import(&db, id).await?.account_id;
+    let filter = approval.filter.unwrap_or_default();
+    Ok(Json(
+        approve_staged_transactions(&db, id, account_id, filter).await?,
+    ))
+}
+
+pub(super) async fn reject(
+    State(db): State<DatabaseConnection>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<imports::Model>, ApiError> {
+    Ok(Json(reject_import(&db, id).await?))
+}
+
+pub(super) async fn rollback(
+    State(db): State<DatabaseConnection>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Rollback>, ApiError> {
+    let deleted = rollback_import(&db, id).await?;
+    Ok(Json(Rollback { deleted }))
+}
+
+async fn find_import(db: &DatabaseConnection, id: Uuid) -> Result<imports::Model, Error> {
+    get_import(
+        db,
+        ImportsQueryOptions {
+            filter: Some(ImportFilter {
+                id: Some(id),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+    )
+    .await?
+    .ok_or(Error::not_found(
+        "error.server.imports.not_found",
+        Entity::Import,
+    ))
+}
@@ -0,0 +1,129 @@
+//! REST API over the models, for front ends to drive NetworthDB with
+//!
+//! Every route answers with JSON: the entities as the models return them, or
+//! on failure the error's stable key with its details (see [`ApiError`]).
+//! List routes read their query string into the models' query options, so
+//! they filter, sort and paginate the way the `get_*` functions do.
+//!
+//! The routes are described by the OpenAPI document served at `/openapi.json`.
+mod accounts;
+mod error;
+mod extract;
+mod imports;
+mod staged_transactions;
+mod transactions;
+
+pub use error::ApiError;
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{DefaultBodyLimit, FromRef},
+    http::header,
+    response::IntoResponse,
+    routing::{get, post},
+    Router,
+};
+use sea_orm::DatabaseConnection;
+use tokio::net::ToSocketAddrs;
+
+use crate::readers::parsers::ParserRegistry;
+
+/// The OpenAPI 3.1 document of the routes
+pub const OPENAPI: &str = include_str!("openapi.json");
+
+/// Statements are uploaded whole, so they can be larger than most requests
+const UPLOAD_LIMIT: usize = 32 * 1024 * 1024;
+
+/// What the routes share: the database, and the parsers uploads are read with
+#[derive(Clone)]
+struct AppState {
+    db: DatabaseConnection,
+    parsers: Arc<ParserRegistry>,
+}
+
+impl FromRef<AppState> for DatabaseConnection {
+    fn from_ref(state: &AppState) -> Self {
+        state.db.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<ParserRegistry> {
+    fn from_ref(state: &AppState) -> Self {
+        state.parsers.clone()
+    }
+}
+
+/// Builds the routes of the API over a database, reading uploads with the
+/// built-in parsers
+pub fn router(db: DatabaseConnection) -> Router {
+    router_with_parsers(db, ParserRegistry::default())
+}
+
+/// Builds the routes of the API over a database, reading uploads with a
+/// registry of parsers
+pub fn router_with_parsers(db: DatabaseConnection, parsers: ParserRegistry) -> Router {
+    Router::new()
+        .route("/openapi.json", get(openapi))
+        .route("/accounts", get(accounts::list).post(accounts::create))
+        .route(
+            "/accounts/{id}",
+            get(accounts::show)
+                .patch(accounts::update)
+                .delete(accounts::delete),
+        )
+        .route("/imports", get(imports::list).post(imports::upload))
+        .route(
+            "/imports/{id}",
+            get(imports::show)
+                .patch(imports::update)
+                .delete(imports::delete),
+        )
+        .route("/imports/{id}/approve", post(imports::approve))
+        .route("/imports/{id}/reject", post(imports::reject))
+        .route("/imports/{id}/rollback", post(imports::rollback))
+        .route("/staged-transactions", get(staged_transactions::list))
+        .route(
+            "/staged-transactions/{id}",
+            get(staged_transactions::show)
+                .patch(staged_transactions::update)
+                .delete(staged_transactions::delete),
+        )
+        .route(
+            "/transactions",
+            get(transactions::list).post(transactions::create),
+        )
+        .route(
+            "/transactions/{id}",
+            get(transactions::show)
+                .patch(transactions::update)
+                .delete(transactions::delete),
+        )
+        .layer(DefaultBodyLimit::max(UPLOAD_LIMIT))
+        .with_state(AppState {
+            db,
+            parsers: Arc::new(parsers),
+        })
+}
+
+/// Serves the API on an address until the process stops
+///
+/// # Arguments
+/// * `db` - Database connection handle
+/// * `parsers` - Parsers to read the uploaded statements with
+/// * `address` - Address to listen on, such as `127.0.0.1:3000`
+pub async fn serve(
+    db: DatabaseConnection,
+    parsers: ParserRegistry,
+    address: impl ToSocketAddrs,
+) -> std::io::Result<()> {
+    let listener = tokio::net::TcpListener::bind(address).await?;
+    axum::serve(listener, router_with_parsers(db, parsers)).await
+}
+
+async fn openapi() -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, "application/json")], OPENAPI)
+}
+
+#[cfg(test)]
+mod tests;
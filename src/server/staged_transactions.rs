@@ -0,0 +1,91 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use sea_orm::{
+    prelude::{DateTime, Decimal},
+    DatabaseConnection,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use super::{
+    error::ApiError,
+    extract::{Body, Query},
+};
+use crate::{
+    error::{Entity, Error},
+    models::{
+        entities::staged_transactions,
+        helpers::staged_transactions::{StagedTransactionFilter, StagedTransactionsQueryOptions},
+        manage::staged_transactions::{
+            delete_staged_transaction, get_staged_transaction, get_staged_transactions,
+            update_staged_transaction,
+        },
+    },
+};
+
+#[derive(Deserialize)]
+pub(super) struct StagedTransactionChanges {
+    date: Option<DateTime>,
+    amount: Option<Decimal>,
+    ref_no: Option<String>,
+    description: Option<String>,
+    sequence_number: Option<i64>,
+}
+
+pub(super) async fn list(
+    State(db): State<DatabaseConnection>,
+    Query(options): Query<StagedTransactionsQueryOptions>,
+) -> Result<Json<Vec<staged_transactions::Model>>, ApiError> {
+    Ok(Json(get_staged_transactions(&db, options).await?))
+}
+
+pub(super) async fn show(
+    State(db): State<DatabaseConnection>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<staged_transactions::Model>, ApiError> {
+    let transaction = get_staged_transaction(
+        &db,
+        StagedTransactionsQueryOptions {
+            filter: Some(StagedTransactionFilter {
+                id: Some(id),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+    )
+    .await?
+    .ok_or(Error::not_found(
+        "error.server.staged_transactions.not_found",
+        Entity::StagedTransaction,
+    ))?;
+    Ok(Json(transaction))
+}
+
+pub(super) async fn update(
+    State(db): State<DatabaseConnection>,
+    Path(id): Path<Uuid>,
+    Body(changes): Body<StagedTransactionChanges>,
+) -> Result<Json<staged_transactions::Model>, ApiError> {
+    let transaction = update_staged_transaction(
+        &db,
+        id,
+        changes.date,
+        changes.amount,
+        changes.ref_no,
+        changes.description,
+        changes.sequence_number,
+    )
+    .await?;
+    Ok(Json(transaction))
+}
+
+pub(super) async fn delete(
+    State(db): State<DatabaseConnection>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    delete_staged_transaction(&db, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,239 @@
+#[cfg(test)]
+use crate::{models::manage::tests::_database, server::router};
+use axum::{
+    body::{to_bytes, Body},
+    http::{header, Method, Request, StatusCode},
+    Router,
+};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+const STATEMENT: &[u8] = include_bytes!("../readers/parsers/sources/mt940/fixtures/statement.sta");
+
+async fn _send(router: &Router, request: Request<Body>) -> (StatusCode, Value) {
+    let response = router.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let body = if body.is_empty() {
+        Value::Null
+    } else {
+        serde_json::from_slice(&body).unwrap()
+    };
+    (status, body)
+}
+
+async fn _json(
+    router: &Router,
+    method: Method,
+    uri: &str,
+    body: Option<Value>,
+) -> (StatusCode, Value) {
+    let request = Request::builder().method(method).uri(uri);
+    let request = match body {
+        Some(body) => request
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string())),
+        None => request.body(Body::empty()),
+    };
+    _send(router, request.unwrap()).await
+}
+
+async fn _upload(router: &Router, file: &[u8]) -> (StatusCode, Value) {
+    let boundary = "statement-boundary";
+    let mut body = format!(
+        "--{boundary}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"statement.sta\"\r\n\r\n"
+    )
+    .into_bytes();
+    body.extend_from_slice(file);
+    body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("/imports")
+        .header(
+            header::CONTENT_TYPE,
+            format!("multipart/form-data; boundary={boundary}"),
+        )
+        .body(Body::from(body))
+        .unwrap();
+    _send(router, request).await
+}
+
+#[tokio::test]
+async fn test_accounts_with_query_options() {
+    let router = router(_database().await);
+    for number in ["50100012345678", "XXXXXXXXXX9876"] {
+        let (status, _) = _json(
+            &router,
+            Method::POST,
+            "/accounts",
+            Some(json!({
+                "account_number": number,
+                "type": "SavingsAccount",
+                "institution_name": "Hdfc"
+            })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+    }
+
+    let (status, accounts) = _json(
+        &router,
+        Method::GET,
+        "/accounts?filter[account_number][0]=EndsWith&filter[account_number][1]=5678&limit=5",
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(accounts.as_array().unwrap().len(), 1);
+    let id = accounts[0]["id"].as_str().unwrap().to_string();
+
+    let (status, account) = _json(
+        &router,
+        Method::PATCH,
+        &format!("/accounts/{id}"),
+        Some(json!({ "type": "CheckingAccount" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(account["type"], "CheckingAccount");
+    assert_eq!(account["account_number"], "50100012345678");
+
+    let (status, _) = _json(&router, Method::DELETE, &format!("/accounts/{id}"), None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, error) = _json(&router, Method::GET, &format!("/accounts/{id}"), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(error["key"], "error.server.accounts.not_found");
+}
+
+#[tokio::test]
+async fn test_invalid_requests_answer_error_keys() {
+    let router = router(_database().await);
+
+    let (status, error) = _json(
+        &router,
+        Method::GET,
+        "/transactions?sort[column]=nope",
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(error["key"], "error.server.invalid_query");
+
+    let (status, error) = _json(
+        &router,
+        Method::POST,
+        "/accounts",
+        Some(json!({ "account_number": "1234" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(error["key"], "error.server.invalid_body");
+
+    let (status, error) = _upload(&router, b"not a statement").await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(error["key"].as_str().unwrap().starts_with("error."));
+}
+
+#[tokio::test]
+async fn test_upload_approve_and_roll_back_import() {
+    let router = router(_database().await);
+
+    let (status, import) = _upload(&router, STATEMENT).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(import["account"]["resolution"], "created");
+    let id = import["import_id"].as_str().unwrap().to_string();
+
+    let (status, error) = _upload(&router, STATEMENT).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(error["import_id"], id.as_str());
+
+    let (_, staged) = _json(
+        &router,
+        Method::GET,
+        &format!("/staged-transactions?filter[import_id]={id}&sort[column]=sequence_number&sort[direction]=asc"),
+        None,
+    )
+    .await;
+    assert_eq!(staged.as_array().unwrap().len(), 3);
+
+    let (status, approval) = _json(
+        &router,
+        Method::POST,
+        &format!("/imports/{id}/approve"),
+        Some(json!({ "filter": { "ids": [staged[0]["id"]] } })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(approval["status"], "PartiallyApproved");
+    assert_eq!(approval["remaining"], 2);
+
+    let (status, approval) = _json(
+        &router,
+        Method::POST,
+        &format!("/imports/{id}/approve"),
+        Some(json!({})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(approval["status"], "Approved");
+
+    let (_, transactions) = _json(
+        &router,
+        Method::GET,
+        &format!(
+            "/transactions?filter[import_id]={id}&filter[amount][0]=LessThan&filter[amount][1]=0"
+        ),
+        None,
+    )
+    .await;
+    assert_eq!(transactions.as_array().unwrap().len(), 2);
+
+    let (status, error) = _json(
+        &router,
+        Method::POST,
+        &format!("/imports/{id}/reject"),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(error["status"], "Approved");
+
+    let (status, rollback) = _json(
+        &router,
+        Method::POST,
+        &format!("/imports/{id}/rollback"),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(rollback["deleted"], 3);
+}
+
+#[tokio::test]
+async fn test_openapi_document() {
+    let router = router(_database().await);
+    let (status, document) = _json(&router, Method::GET, "/openapi.json", None).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(document["openapi"], "3.1.0");
+    for path in [
+        "/accounts",
+        "/accounts/{id}",
+        "/imports",
+        "/imports/{id}",
+        "/imports/{id}/approve",
+        "/imports/{id}/reject",
+        "/imports/{id}/rollback",
+        "/staged-transactions",
+        "/staged-transactions/{id}",
+        "/transactions",
+        "/transactions/{id}",
+    ] {
+        assert!(
+            document["paths"][path].is_object(),
+            "{} is not documented",
+            path
+        );
+    }
+}
@@ -0,0 +1,145 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use sea_orm::{
+    prelude::{DateTime, Decimal},
+    DatabaseConnection,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use super::{
+    error::ApiError,
+    extract::{Body, Query},
+};
+use crate::{
+    error::{Entity, Error},
+    models::{
+        entities::transactions,
+        helpers::transactions::{
+            build_transaction, TransactionFilter, TransactionSort, TransactionsQueryOptions,
+        },
+        manage::transactions::{
+            create_transaction, delete_transaction, get_transaction, get_transactions,
+            update_transaction,
+        },
+    },
+};
+
+#[derive(Deserialize)]
+pub(super) struct NewTransaction {
+    account_id: Uuid,
+    date: DateTime,
+    amount: Decimal,
+    #[serde(default)]
+    ref_no: String,
+    #[serde(default)]
+    description: String,
+    /// Where the transaction goes in the account, after the last one if not given
+    sequence_number: Option<i64>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TransactionChanges {
+    account_id: Option<Uuid>,
+    date: Option<DateTime>,
+    amount: Option<Decimal>,
+    ref_no: Option<String>,
+    description: Option<String>,
+    sequence_number: Option<i64>,
+}
+
+pub(super) async fn list(
+    State(db): State<DatabaseConnection>,
+    Query(options): Query<TransactionsQueryOptions>,
+) -> Result<Json<Vec<transactions::Model>>, ApiError> {
+    Ok(Json(get_transactions(&db, options).await?))
+}
+
+pub(super) async fn create(
+    State(db): State<DatabaseConnection>,
+    Body(transaction): Body<NewTransaction>,
+) -> Result<(StatusCode, Json<transactions::Model>), ApiError> {
+    let sequence_number = match transaction.sequence_number {
+        Some(sequence_number) => sequence_number,
+        None => get_transaction(
+            &db,
+            TransactionsQueryOptions {
+                filter: Some(TransactionFilter {
+                    account_id: Some(transaction.account_id),
+                    ..Default::default()
+                }),
+                // Latest first
+                sort: Some(TransactionSort::default()),
+                ..Default::default()
+            },
+        )
+        .await?
+        .map_or(1, |last| last.sequence_number + 1),
+    };
+
+    let transaction = create_transaction(
+        &db,
+        build_transaction(
+            transaction.amount,
+            transaction.account_id,
+            transaction.date,
+            sequence_number,
+            transaction.ref_no,
+            transaction.description,
+        ),
+    )
+    .await?;
+    Ok((StatusCode::CREATED, Json(transaction)))
+}
+
+pub(super) async fn show(
+    State(db): State<DatabaseConnection>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<transactions::Model>, ApiError> {
+    let transaction = get_transaction(
+        &db,
+        TransactionsQueryOptions {
+            filter: Some(TransactionFilter {
+                id: Some(id),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+    )
+    .await?
+    .ok_or(Error::not_found(
+        "error.server.transactions.not_found",
+        Entity::Transaction,
+    ))?;
+    Ok(Json(transaction))
+}
+
+pub(super) async fn update(
+    State(db): State<DatabaseConnection>,
+    Path(id): Path<Uuid>,
+    Body(changes): Body<TransactionChanges>,
+) -> Result<Json<transactions::Model>, ApiError> {
+    let transaction = update_transaction(
+        &db,
+        id,
+        changes.account_id,
+        changes.amount,
+        changes.date,
+        changes.ref_no,
+        changes.description,
+        changes.sequence_number,
+    )
+    .await?;
+    Ok(Json(transaction))
+}
+
+pub(super) async fn delete(
+    State(db): State<DatabaseConnection>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    delete_transaction(&db, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
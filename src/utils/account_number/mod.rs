@@ -0,0 +1,80 @@
+#[cfg(test)]
+mod tests;
+
+/// Number of trailing characters statements leave readable when masking
+const VISIBLE_DIGITS: usize = 4;
+
+/// Masks an account number the way statements print it, keeping the last
+/// four characters
+///
+/// # Arguments
+/// * `number` - Account number, surrounding whitespace is ignored
+///
+/// # Returns
+/// * `String` - `X` in place of every other character, e.g. `XXXXX6789`
+pub fn mask(number: &str) -> String {
+    let number = number.trim();
+    let length = number.chars().count();
+
+    number
+        .chars()
+        .enumerate()
+        .map(|(index, character)| {
+            if index + VISIBLE_DIGITS < length {
+                'X'
+            } else {
+                character
+            }
+        })
+        .collect()
+}
+
+/// Characters statements print in place of hidden digits
+const MASK_CHARACTERS: [char; 3] = ['X', 'x', '*'];
+
+/// Whether the account number hides some of its digits, which statements
+/// mark with a run of at least two mask characters
+pub fn is_masked(number: &str) -> bool {
+    number
+        .chars()
+        .collect::<Vec<char>>()
+        .windows(2)
+        .any(|pair| {
+            pair.iter()
+                .all(|character| MASK_CHARACTERS.contains(character))
+        })
+}
+
+/// Whether two account numbers can belong to the same account
+///
+/// Numbers in full have to be equal. When either is masked, the digits it
+/// leaves readable at the end have to end the other one. Spaces and dashes
+/// are ignored.
+pub fn matches(number: &str, other: &str) -> bool {
+    let number = normalize(number);
+    let other = normalize(other);
+
+    if !is_masked(&number) && !is_masked(&other) {
+        return number == other;
+    }
+
+    let visible = |number: &str| {
+        number
+            .rsplit(MASK_CHARACTERS)
+            .next()
+            .unwrap_or("")
+            .to_string()
+    };
+    let (number, other) = (visible(&number), visible(&other));
+
+    !number.is_empty()
+        && !other.is_empty()
+        && (number.ends_with(&other) || other.ends_with(&number))
+}
+
+fn normalize(number: &str) -> String {
+    number
+        .chars()
+        .filter(|character| !character.is_whitespace() && *character != '-')
+        .collect()
+}
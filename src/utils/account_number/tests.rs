@@ -0,0 +1,37 @@
+#[cfg(test)]
+use crate::utils::account_number::{is_masked, mask, matches};
+
+#[test]
+fn test_mask() {
+    assert_eq!(mask("123456789"), "XXXXX6789");
+    assert_eq!(mask(" 50100123456789 "), "XXXXXXXXXX6789");
+}
+
+#[test]
+fn test_mask_short_number() {
+    assert_eq!(mask("6789"), "6789");
+    assert_eq!(mask("89"), "89");
+}
+
+#[test]
+fn test_is_masked() {
+    assert!(is_masked("XXXXX6789"));
+    assert!(is_masked("4893 **** **** 1234"));
+    assert!(!is_masked("123456789"));
+    assert!(!is_masked("GB29NWBK60161331926819"));
+}
+
+#[test]
+fn test_matches_full_numbers() {
+    assert!(matches("123456789", " 1234-56789 "));
+    assert!(!matches("123456789", "923456789"));
+}
+
+#[test]
+fn test_matches_masked_suffix() {
+    assert!(matches("XXXXX6789", "123456789"));
+    assert!(matches("50100123456789", "5010XXXXXX6789"));
+    assert!(matches("XXXX6789", "XXXXXXXX6789"));
+    assert!(!matches("XXXXX6789", "123451234"));
+    assert!(!matches("XXXXXXXXX", "123456789"));
+}
@@ -1,11 +1,64 @@
 #[cfg(test)]
 mod tests;
 
+use std::borrow::Cow;
+
 use sea_orm::{
     prelude::DateTimeUtc,
-    sqlx::types::chrono::{DateTime, Utc},
+    sqlx::types::chrono::{FixedOffset, NaiveDate, Utc},
+};
+
+use crate::error::Error;
+
+/// India Standard Time, the timezone statements are read in unless a parser
+/// says otherwise
+pub const IST: FixedOffset = match FixedOffset::east_opt(5 * 3600 + 30 * 60) {
+    Some(offset) => offset,
+    None => panic!("IST is a valid offset"),
 };
 
+/// Days from the start of the common era to 1899-12-30, the day spreadsheets
+/// count their serial dates from
+const EXCEL_EPOCH_DAYS_FROM_CE: i32 = 693_594;
+
+/// A way a date can be written in a statement
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DateFormat {
+    /// A `chrono` format string, such as `%d/%m/%Y`
+    Pattern(Cow<'static, str>),
+    /// Days since 1899-12-30, as spreadsheets store dates; any time of day
+    /// in the fraction is dropped
+    ExcelSerial,
+}
+
+impl DateFormat {
+    /// `31/01/2023`
+    pub const DD_MM_YYYY: DateFormat = DateFormat::Pattern(Cow::Borrowed("%d/%m/%Y"));
+    /// `31/01/23`
+    pub const DD_MM_YY: DateFormat = DateFormat::Pattern(Cow::Borrowed("%d/%m/%y"));
+    /// `31-Jan-2023`
+    pub const DD_MMM_YYYY: DateFormat = DateFormat::Pattern(Cow::Borrowed("%d-%b-%Y"));
+    /// `31 Jan 2023`, or `1 Jan 2023`
+    pub const D_MMM_YYYY: DateFormat = DateFormat::Pattern(Cow::Borrowed("%d %b %Y"));
+    /// `2023-01-31`
+    pub const ISO: DateFormat = DateFormat::Pattern(Cow::Borrowed("%Y-%m-%d"));
+
+    fn parse(&self, date: &str) -> Option<NaiveDate> {
+        match self {
+            DateFormat::Pattern(pattern) => NaiveDate::parse_from_str(date, pattern).ok(),
+            DateFormat::ExcelSerial => {
+                let serial = date.parse::<f64>().ok()?;
+                // Serials before 1900-03-01 would need Excel's fictional
+                // 1900-02-29, and nothing before 1900 is a statement date
+                if !(61.0..2_958_466.0).contains(&serial) {
+                    return None;
+                }
+                NaiveDate::from_num_days_from_ce_opt(EXCEL_EPOCH_DAYS_FROM_CE + serial as i32)
+            }
+        }
+    }
+}
+
 /// Returns the current UTC datetime
 pub fn get_current_datetime() -> DateTimeUtc {
     Utc::now()
@@ -15,29 +68,30 @@ pub fn get_current_naive_datetime() -> sea_orm::prelude::DateTime {
     Utc::now().naive_utc()
 }
 
-/// Converts a date string in format "DD/MM/YYYY" to a UTC datetime
+/// Parses a date with the first of the given formats that reads it
 ///
 /// # Arguments
-/// * `date` - Date string in format "DD/MM/YYYY"
+/// * `date` - Date string, surrounding whitespace is ignored
+/// * `formats` - Formats to try, in order
+/// * `timezone` - Timezone the statement is written in
 ///
 /// # Returns
-/// * `DateTimeUtc` - UTC datetime with time set to midnight
+/// * `DateTimeUtc` - The start of that day in the statement's timezone
 ///
-/// # Panics
-/// * If the date string cannot be parsed
-pub fn date_str_to_datetime(date: &str) -> DateTimeUtc {
-    return match DateTime::parse_from_str(
-        &format!("{} 00:00:00 +0000", date.trim()),
-        "%d/%m/%Y %H:%M:%S %z",
-    ) {
-        Ok(dt) => dt.with_timezone(&Utc),
-        Err(e) => {
-            tracing::error!(
-                "error.utils.datetime.date_str_to_datetime: {} - {}",
-                date,
-                e
-            );
-            panic!("error.utils.datetime.date_str_to_datetime");
-        }
-    };
+/// # Errors
+/// * If none of the formats reads the date
+pub fn parse_date(
+    date: &str,
+    formats: &[DateFormat],
+    timezone: FixedOffset,
+) -> Result<DateTimeUtc, Error> {
+    let trimmed = date.trim();
+
+    formats
+        .iter()
+        .find_map(|format| format.parse(trimmed))
+        .and_then(|day| day.and_hms_opt(0, 0, 0))
+        .and_then(|midnight| midnight.and_local_timezone(timezone).single())
+        .map(|datetime| datetime.with_timezone(&Utc))
+        .ok_or_else(|| Error::parse("error.utils.datetime.parse_date").with_reason(date))
 }
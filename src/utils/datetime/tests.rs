@@ -1,21 +1,88 @@
 #[cfg(test)]
-use crate::utils::datetime::{date_str_to_datetime, get_current_datetime};
-use sea_orm::sqlx::types::chrono::{TimeZone, Utc};
+use crate::utils::datetime::{get_current_datetime, parse_date, DateFormat, IST};
+use sea_orm::sqlx::types::chrono::{FixedOffset, TimeZone, Utc};
 
 #[test]
-fn test_date_str_to_datetime() {
+fn test_parse_date() {
     let date = "01/01/2023";
-    let expected = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
-    assert_eq!(date_str_to_datetime(date), expected);
+    let expected = Utc.with_ymd_and_hms(2022, 12, 31, 18, 30, 0).unwrap();
+    assert_eq!(
+        parse_date(date, &[DateFormat::DD_MM_YYYY], IST).unwrap(),
+        expected
+    );
 
     let date_with_spaces = "  01/01/2023  ";
-    assert_eq!(date_str_to_datetime(date_with_spaces), expected);
+    assert_eq!(
+        parse_date(date_with_spaces, &[DateFormat::DD_MM_YYYY], IST).unwrap(),
+        expected
+    );
 }
 
 #[test]
-#[should_panic(expected = "error.utils.datetime.date_str_to_datetime")]
-fn test_date_str_to_datetime_invalid_format() {
-    date_str_to_datetime("2023-01-01");
+fn test_parse_date_in_timezone() {
+    let utc = FixedOffset::east_opt(0).unwrap();
+    assert_eq!(
+        parse_date("2023-01-01", &[DateFormat::ISO], utc).unwrap(),
+        Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap()
+    );
+}
+
+#[test]
+fn test_parse_date_with_several_formats() {
+    let formats = [
+        DateFormat::DD_MM_YY,
+        DateFormat::DD_MM_YYYY,
+        DateFormat::DD_MMM_YYYY,
+        DateFormat::D_MMM_YYYY,
+        DateFormat::ISO,
+        DateFormat::ExcelSerial,
+    ];
+    let expected = parse_date("15/03/2023", &formats, IST).unwrap();
+
+    for date in [
+        "15/03/23",
+        "15-Mar-2023",
+        "15 Mar 2023",
+        "2023-03-15",
+        "45000",
+        "45000.75",
+    ] {
+        assert_eq!(
+            parse_date(date, &formats, IST).unwrap(),
+            expected,
+            "{}",
+            date
+        );
+    }
+
+    // Days before the 10th are not padded
+    assert_eq!(
+        parse_date("5 Mar 2023", &formats, IST).unwrap(),
+        parse_date("05/03/2023", &formats, IST).unwrap()
+    );
+}
+
+#[test]
+fn test_parse_date_two_digit_year() {
+    // `%Y` would read the year as 23 AD
+    assert_eq!(
+        parse_date(
+            "01/01/23",
+            &[DateFormat::DD_MM_YY, DateFormat::DD_MM_YYYY],
+            IST
+        )
+        .unwrap(),
+        parse_date("01/01/2023", &[DateFormat::DD_MM_YYYY], IST).unwrap()
+    );
+}
+
+#[test]
+fn test_parse_date_invalid_format() {
+    let error = parse_date("2023-01-01", &[DateFormat::DD_MM_YYYY], IST).unwrap_err();
+    assert_eq!(error.key(), "error.utils.datetime.parse_date");
+    assert_eq!(error.reason().as_deref(), Some("2023-01-01"));
+
+    assert!(parse_date("12", &[DateFormat::ExcelSerial], IST).is_err());
 }
 
 #[test]
@@ -1 +1,2 @@
+pub(crate) mod account_number;
 pub(crate) mod datetime;